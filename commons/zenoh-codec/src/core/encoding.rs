@@ -19,9 +19,10 @@ use zenoh_buffers::{
 };
 use zenoh_protocol::core::Encoding;
 
+// The well-known encodings unknown to older zenoh versions are sent as strings after the empty prefix.
 impl LCodec<&Encoding> for Zenoh080 {
     fn w_len(self, x: &Encoding) -> usize {
-        1 + self.w_len(x.suffix())
+        1 + self.w_len(x.wire_suffix().as_ref())
     }
 }
 
@@ -33,8 +34,8 @@ where
 
     fn write(self, writer: &mut W, x: &Encoding) -> Self::Output {
         let zodec = Zenoh080Bounded::<u8>::new();
-        zodec.write(&mut *writer, *x.prefix() as u8)?;
-        zodec.write(&mut *writer, x.suffix())?;
+        zodec.write(&mut *writer, x.wire_prefix())?;
+        zodec.write(&mut *writer, x.wire_suffix().as_ref())?;
         Ok(())
     }
 }
@@ -49,7 +50,7 @@ where
         let zodec = Zenoh080Bounded::<u8>::new();
         let prefix: u8 = zodec.read(&mut *reader)?;
         let suffix: String = zodec.read(&mut *reader)?;
-        let encoding = Encoding::new(prefix, suffix).map_err(|_| DidntRead)?;
+        let encoding = Encoding::from_wire(prefix, suffix).map_err(|_| DidntRead)?;
         Ok(encoding)
     }
 }
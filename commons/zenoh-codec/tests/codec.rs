@@ -16,6 +16,7 @@ use rand::{
     *,
 };
 use std::convert::TryFrom;
+use std::str::FromStr;
 use zenoh_buffers::{
     reader::{HasReader, Reader},
     writer::HasWriter,
@@ -322,6 +323,107 @@ fn codec_encoding() {
     run!(Encoding, Encoding::rand());
 }
 
+#[test]
+fn codec_encoding_schema() {
+    let codec = Zenoh080::new();
+    for e in [
+        Encoding::APP_PROTOBUF,
+        Encoding::APP_PROTOBUF
+            .with_schema("sensors.Temperature")
+            .unwrap(),
+        Encoding::from("text/plain;charset=utf-8;schema=42"),
+    ] {
+        let mut buff = vec![];
+        let mut writer = buff.writer();
+        codec.write(&mut writer, &e).unwrap();
+        assert_eq!(codec.w_len(&e), buff.len());
+
+        let mut reader = buff.reader();
+        let d: Encoding = codec.read(&mut reader).unwrap();
+        assert_eq!(e, d);
+        assert_eq!(Encoding::from(e.to_string()), e);
+    }
+
+    let e = Encoding::from("text/plain; charset=\"utf-8\";schema=42");
+    assert_eq!(e.prefix(), &KnownEncoding::TextPlain);
+    assert_eq!(e.suffix(), "; charset=\"utf-8\";schema=42");
+    assert_eq!(e.schema(), Some("42"));
+    assert_eq!(e.parameter("charset"), Some("utf-8"));
+    assert_eq!(e.parameters().count(), 2);
+
+    // The schema replaces the previous one
+    let e = e.with_schema("43").unwrap();
+    assert_eq!(e.suffix(), "; charset=\"utf-8\";schema=43");
+    let e = e.with_schema("").unwrap();
+    assert_eq!(e, Encoding::from("text/plain; charset=\"utf-8\""));
+
+    // The longest well-known MIME type is used as prefix
+    let e = Encoding::from("application/json-seq");
+    assert_eq!(e.prefix(), &KnownEncoding::AppJsonSeq);
+    assert_eq!(e.suffix(), "");
+
+    // The well-known encodings understood by every zenoh version are sent as a single byte
+    let e = Encoding::TEXT_PLAIN
+        .with_suffix(";charset=utf-8")
+        .unwrap()
+        .with_schema("42")
+        .unwrap();
+    let mut buff = vec![];
+    let mut writer = buff.writer();
+    codec.write(&mut writer, &e).unwrap();
+    let mut reader = buff.reader();
+    let prefix: u8 = codec.read(&mut reader).unwrap();
+    let suffix: String = codec.read(&mut reader).unwrap();
+    assert_eq!(prefix, KnownEncoding::TextPlain as u8);
+    assert_eq!(suffix, ";charset=utf-8;schema=42");
+
+    // The other ones are sent as strings after the empty prefix
+    let e = Encoding::APP_PROTOBUF.with_schema("42").unwrap();
+    let mut buff = vec![];
+    let mut writer = buff.writer();
+    codec.write(&mut writer, &e).unwrap();
+    let mut reader = buff.reader();
+    let prefix: u8 = codec.read(&mut reader).unwrap();
+    let suffix: String = codec.read(&mut reader).unwrap();
+    assert_eq!(prefix, KnownEncoding::Empty as u8);
+    assert_eq!(suffix, "application/protobuf;schema=42");
+
+    // An unknown prefix is decoded as application/custom
+    let mut buff = vec![];
+    let mut writer = buff.writer();
+    let zodec = Zenoh080Bounded::<u8>::new();
+    zodec.write(&mut writer, 200u8).unwrap();
+    zodec.write(&mut writer, ";charset=utf-8").unwrap();
+    let mut reader = buff.reader();
+    let d: Encoding = codec.read(&mut reader).unwrap();
+    assert_eq!(d, Encoding::from("application/custom;charset=utf-8"));
+
+    // The suffix must fit on the wire
+    let long = "a".repeat(200);
+    assert!(Encoding::from_str(&format!("text/plain;{long}")).is_ok());
+    assert!(Encoding::from_str(&format!("text/plain;{long};schema={long}")).is_err());
+    assert!(
+        Encoding::from_str(&format!("application/protobuf;{long};{}", "a".repeat(40))).is_err()
+    );
+    assert!(Encoding::TEXT_PLAIN
+        .with_suffix(format!(";{long}"))
+        .unwrap()
+        .with_schema(&long)
+        .is_err());
+
+    // Converting from a string never fails: the schema is dropped, then the suffix truncated
+    let e = Encoding::from(format!("text/plain;{long};schema={long}"));
+    assert_eq!(e.suffix(), format!(";{long}"));
+    assert_eq!(e.schema(), None);
+    let e = Encoding::from(format!("text/plain;{long}{long}é"));
+    assert_eq!(e.prefix(), &KnownEncoding::TextPlain);
+    assert_eq!(e.suffix().len(), 255);
+    let e = Encoding::from(format!("text/plain;{}é", "a".repeat(253)));
+    assert_eq!(e.suffix(), format!(";{}", "a".repeat(253)));
+    let e = Encoding::from(format!("application/protobuf;{long}{long}"));
+    assert_eq!(e.to_string().len(), 255);
+}
+
 #[cfg(feature = "shared-memory")]
 #[test]
 fn codec_shm_info() {
@@ -40,6 +40,21 @@ impl<T> RingBuffer<T> {
         Some(elem)
     }
 
+    /// Pushes an element, dropping the oldest one if the buffer is full.
+    /// Returns the dropped element, if any.
+    #[inline]
+    pub fn push_force(&mut self, elem: T) -> Option<T> {
+        let elem = self.push(elem)?;
+        match self.buffer.pop_front() {
+            Some(oldest) => {
+                self.buffer.push_back(elem);
+                Some(oldest)
+            }
+            // Nothing to make room from: the buffer has no capacity
+            None => Some(elem),
+        }
+    }
+
     #[inline]
     pub fn pull(&mut self) -> Option<T> {
         let x = self.buffer.pop_front();
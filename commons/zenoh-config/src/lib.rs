@@ -73,20 +73,33 @@ impl Zeroize for SecretString {
 
 pub type SecretValue = Secret<SecretString>;
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
-pub enum DownsamplingFlow {
+pub enum InterceptorFlow {
     Egress,
     Ingress,
 }
 
+pub type DownsamplingFlow = InterceptorFlow;
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct DownsamplingRuleConf {
-    /// A list of key-expressions to which the downsampling will be applied.
-    /// Downsampling will be applied for all key extensions if the parameter is None
+    /// The key-expression to which the downsampling will be applied.
+    /// The rule applies to all the messages whose key expression is included in `key_expr`,
+    /// the first matching rule applying when several of them do.
     pub key_expr: OwnedKeyExpr,
     /// The maximum frequency in Hertz;
     pub freq: f64,
+    /// Whether the maximum frequency applies to each key expression matching `key_expr` separately
+    /// rather than to all of them together (false by default).
+    #[serde(default)]
+    pub per_key: bool,
+    /// The maximum number of key expressions tracked when `per_key` is set,
+    /// the least recently sent ones being evicted (1024 by default).
+    pub max_keys: Option<usize>,
+    /// Whether the latest message dropped in a period is sent at the end of the period (false by default).
+    #[serde(default)]
+    pub keep_latest: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -100,6 +113,103 @@ pub struct DownsamplingItemConf {
     pub flow: DownsamplingFlow,
 }
 
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Permission {
+    Allow,
+    #[default]
+    Deny,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AclMessage {
+    Put,
+    Delete,
+    Query,
+    Reply,
+    DeclareSubscriber,
+    DeclareQueryable,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AclConfigRule {
+    /// A list of interfaces to which the rule will be applied.
+    /// The rule will be applied for all interfaces if the parameter is None
+    pub interfaces: Option<Vec<String>>,
+    /// A list of users, authenticated with user-password authentication, to which the rule will be applied.
+    /// The rule will be applied for all users if the parameter is None
+    pub usernames: Option<Vec<String>>,
+    /// A list of key-expressions to which the rule will be applied.
+    pub key_exprs: Vec<OwnedKeyExpr>,
+    /// A list of messages to which the rule will be applied.
+    pub messages: Vec<AclMessage>,
+    /// A list of flow directions to which the rule will be applied: egress, ingress.
+    /// The rule will be applied for both directions if the parameter is None
+    pub flows: Option<Vec<InterceptorFlow>>,
+    /// Whether the matching messages are allowed or denied.
+    pub permission: Permission,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PayloadTransformationRuleConf {
+    /// A list of key-expressions to which the transformation will be applied.
+    pub key_exprs: Vec<OwnedKeyExpr>,
+    /// Whether the payloads are compressed (false by default).
+    #[serde(default)]
+    pub compression: bool,
+    /// The AES-128 key the payloads are encrypted and authenticated with (AES-128-GCM),
+    /// as 32 hexadecimal digits. Each payload is encrypted with a random nonce:
+    /// the key should be renewed before 2^32 payloads are encrypted with it.
+    /// The payloads will not be encrypted if the parameter is None
+    #[serde(skip_serializing)]
+    pub encryption_key: Option<SecretValue>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitMessage {
+    Put,
+    Delete,
+    Query,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RateLimitRuleConf {
+    /// The key-expression to which the rate limit will be applied.
+    pub key_expr: OwnedKeyExpr,
+    /// A list of messages to which the rate limit will be applied: put, delete, query.
+    /// The rate limit will be applied to all of them if the parameter is None
+    pub messages: Option<Vec<RateLimitMessage>>,
+    /// The maximum number of messages per second.
+    pub messages_per_sec: Option<f64>,
+    /// The maximum number of messages let through at once (messages_per_sec by default).
+    pub messages_burst: Option<f64>,
+    /// The maximum number of payload bytes per second.
+    pub bytes_per_sec: Option<f64>,
+    /// The maximum number of payload bytes let through at once (bytes_per_sec by default).
+    /// A larger message is only let through when the bytes bucket is full.
+    pub bytes_burst: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RateLimitItemConf {
+    /// A list of interfaces to which the rate limit will be applied
+    /// Rate limit will be applied for all interfaces if the parameter is None
+    pub interfaces: Option<Vec<String>>,
+    /// A list of remote zenoh ids to which the rate limit will be applied
+    /// Rate limit will be applied for all remote nodes if the parameter is None
+    pub zids: Option<Vec<ZenohId>>,
+    /// A list of rate limit rules. The first rule matching a key expression and limiting
+    /// the kind of the message applies.
+    pub rules: Vec<RateLimitRuleConf>,
+    /// Rate limit flow direction: egress, ingress
+    pub flow: InterceptorFlow,
+    /// Whether the rejected queries are answered with an error rather than dropped (ingress only).
+    #[serde(default)]
+    pub reply_error: bool,
+}
+
 pub trait ConfigValidator: Send + Sync {
     fn check_config(
         &self,
@@ -456,6 +566,32 @@ validated_struct::validator! {
         /// Configuration of the downsampling.
         downsampling: Vec<DownsamplingItemConf>,
 
+        /// Configuration of the rate limiting.
+        rate_limit: Vec<RateLimitItemConf>,
+
+        /// Configuration of the access control.
+        pub access_control: #[derive(Default)]
+        AclConfig {
+            /// Whether the access control is enforced (false by default).
+            pub enabled: bool,
+            /// The permission applied to messages no rule matches (deny by default).
+            pub default_permission: Permission,
+            /// The list of access control rules. Deny rules take precedence over allow rules.
+            pub rules: Vec<AclConfigRule>,
+        },
+
+        /// Configuration of the payload transformations.
+        pub payload_transformation: #[derive(Default)]
+        PayloadTransformationConf {
+            /// A list of interfaces to which the transformations will be applied.
+            /// The transformations will be applied for all interfaces if the parameter is None
+            pub interfaces: Option<Vec<String>>,
+            /// The kinds of remote nodes whose payloads are transformed on ingress and restored on egress (client by default).
+            pub edge: Option<WhatAmIMatcher>,
+            /// The list of payload transformation rules. The first rule matching a key expression applies.
+            pub rules: Vec<PayloadTransformationRuleConf>,
+        },
+
         /// A list of directories where plugins may be searched for if no `__path__` was specified for them.
         /// The executable's current directory will be added to the search paths.
         plugins_search_dirs: Vec<String>, // TODO (low-prio): Switch this String to a PathBuf? (applies to other paths in the config as well)
@@ -15,7 +15,8 @@ use super::PseudoRng;
 use aes::cipher::{generic_array::GenericArray, BlockDecrypt, BlockEncrypt, KeyInit};
 use aes::Aes128;
 use rand::Rng;
-use zenoh_result::{bail, ZResult};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_128_GCM};
+use zenoh_result::{bail, zerror, ZResult};
 
 pub struct BlockCipher {
     inner: Aes128,
@@ -65,7 +66,91 @@ impl BlockCipher {
     }
 }
 
+/// An authenticated cipher (AES-128-GCM): the encrypted bytes are followed by a tag
+/// authenticating them along with some associated data.
+///
+/// A nonce must never be used twice with the same key: a random nonce is only safe
+/// for up to 2^32 encryptions per key.
+pub struct AeadCipher {
+    inner: LessSafeKey,
+}
+
+impl AeadCipher {
+    pub const KEY_SIZE: usize = 16;
+    pub const NONCE_SIZE: usize = 12;
+    pub const TAG_SIZE: usize = 16;
+
+    pub fn new(key: [u8; Self::KEY_SIZE]) -> AeadCipher {
+        AeadCipher {
+            inner: LessSafeKey::new(UnboundKey::new(&AES_128_GCM, &key).unwrap()),
+        }
+    }
+
+    pub fn encrypt(
+        &self,
+        nonce: [u8; Self::NONCE_SIZE],
+        aad: &[u8],
+        mut bytes: Vec<u8>,
+    ) -> Vec<u8> {
+        // sealing only fails for inputs larger than what GCM supports (64 GiB)
+        self.inner
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(aad),
+                &mut bytes,
+            )
+            .unwrap();
+        bytes
+    }
+
+    pub fn decrypt(
+        &self,
+        nonce: [u8; Self::NONCE_SIZE],
+        aad: &[u8],
+        mut bytes: Vec<u8>,
+    ) -> ZResult<Vec<u8>> {
+        let encrypted_len = bytes.len();
+        let len = self
+            .inner
+            .open_in_place(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(aad),
+                &mut bytes,
+            )
+            .map_err(|_| zerror!("Unable to decrypt or authenticate {} bytes", encrypted_len))?
+            .len();
+        bytes.truncate(len);
+        Ok(bytes)
+    }
+}
+
 mod tests {
+    #[test]
+    fn aead_cipher() {
+        use super::{AeadCipher, PseudoRng};
+        use rand::{RngCore, SeedableRng};
+
+        let mut prng = PseudoRng::from_entropy();
+        let mut key = [0_u8; AeadCipher::KEY_SIZE];
+        prng.fill_bytes(&mut key);
+        let cipher = AeadCipher::new(key);
+
+        for clear in ["", "A", "This is a medium string with some text"] {
+            let mut nonce = [0_u8; AeadCipher::NONCE_SIZE];
+            prng.fill_bytes(&mut nonce);
+            let encrypted = cipher.encrypt(nonce, b"aad", clear.as_bytes().to_vec());
+            assert_eq!(encrypted.len(), clear.len() + AeadCipher::TAG_SIZE);
+            let decrypted = cipher.decrypt(nonce, b"aad", encrypted.clone()).unwrap();
+            assert_eq!(clear.as_bytes(), &decrypted[..]);
+
+            // the encrypted bytes and the associated data are authenticated
+            let mut tampered = encrypted.clone();
+            tampered[0] ^= 1;
+            assert!(cipher.decrypt(nonce, b"aad", tampered).is_err());
+            assert!(cipher.decrypt(nonce, b"add", encrypted).is_err());
+        }
+    }
+
     #[test]
     fn cipher() {
         use super::{BlockCipher, PseudoRng};
@@ -11,17 +11,16 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use alloc::{borrow::ToOwned, boxed::Box, string::String, vec::Vec};
+use alloc::{borrow::ToOwned, boxed::Box, string::String};
 use core::fmt::{Debug, Display, Formatter};
-use core::num::NonZeroUsize;
 
 enum CowStrInner<'a> {
     Borrowed(&'a str),
-    Owned { s: Box<str>, capacity: NonZeroUsize },
+    Owned(Box<str>),
 }
 pub struct CowStr<'a>(CowStrInner<'a>);
 impl<'a> CowStr<'a> {
-    pub(crate) fn borrowed(s: &'a str) -> Self {
+    pub(crate) const fn borrowed(s: &'a str) -> Self {
         Self(CowStrInner::Borrowed(s))
     }
     pub fn as_str(&self) -> &str {
@@ -46,11 +45,7 @@ impl<'a> From<String> for CowStr<'a> {
         if s.is_empty() {
             CowStr::borrowed("")
         } else {
-            let capacity = unsafe { NonZeroUsize::new_unchecked(s.capacity()) };
-            Self(CowStrInner::Owned {
-                s: s.into_boxed_str(),
-                capacity,
-            })
+            Self(CowStrInner::Owned(s.into_boxed_str()))
         }
     }
 }
@@ -64,7 +59,7 @@ impl core::ops::Deref for CowStr<'_> {
     fn deref(&self) -> &Self::Target {
         match &self.0 {
             CowStrInner::Borrowed(s) => s,
-            CowStrInner::Owned { s, .. } => s,
+            CowStrInner::Owned(s) => s,
         }
     }
 }
@@ -99,15 +94,11 @@ impl core::ops::Add<&str> for CowStr<'_> {
                 ans.push_str(rhs);
                 ans
             }
-            CowStrInner::Owned { mut s, capacity } => unsafe {
-                let mut s = String::from_utf8_unchecked(Vec::from_raw_parts(
-                    s.as_mut_ptr(),
-                    s.len(),
-                    capacity.get(),
-                ));
+            CowStrInner::Owned(s) => {
+                let mut s = String::from(s);
                 s += rhs;
                 s
-            },
+            }
         }
     }
 }
@@ -17,11 +17,12 @@ use core::{
     convert::TryFrom,
     fmt::{self, Debug},
     mem,
+    str::FromStr,
 };
 use zenoh_result::{bail, zerror, ZError, ZResult};
 
 mod consts {
-    pub(super) const MIMES: [&str; 21] = [
+    pub(super) const MIMES: [&str; 53] = [
         /*  0 */ "",
         /*  1 */ "application/octet-stream",
         /*  2 */ "application/custom", // non iana standard
@@ -44,9 +45,52 @@ mod consts {
         /* 18 */ "image/jpeg",
         /* 19 */ "image/png",
         /* 20 */ "image/gif",
+        /* 21 */ "application/cbor",
+        /* 22 */ "application/protobuf",
+        /* 23 */ "application/avro", // non iana standard
+        /* 24 */ "application/msgpack", // non iana standard
+        /* 25 */ "application/yaml",
+        /* 26 */ "application/cdr", // non iana standard
+        /* 27 */ "application/ld+json",
+        /* 28 */ "application/geo+json",
+        /* 29 */ "application/json-seq",
+        /* 30 */ "application/pdf",
+        /* 31 */ "application/zip",
+        /* 32 */ "application/gzip",
+        /* 33 */ "application/openmetrics-text",
+        /* 34 */ "text/yaml",
+        /* 35 */ "text/markdown",
+        /* 36 */ "text/event-stream",
+        /* 37 */ "image/bmp",
+        /* 38 */ "image/webp",
+        /* 39 */ "image/svg+xml",
+        /* 40 */ "audio/aac",
+        /* 41 */ "audio/flac",
+        /* 42 */ "audio/mp4",
+        /* 43 */ "audio/ogg",
+        /* 44 */ "audio/vorbis",
+        /* 45 */ "video/h264",
+        /* 46 */ "video/h265",
+        /* 47 */ "video/mp4",
+        /* 48 */ "video/ogg",
+        /* 49 */ "video/raw",
+        /* 50 */ "video/vp8",
+        /* 51 */ "video/vp9",
+        /* 52 */ "zenoh/serialized", // the format of zenoh::serialization
     ];
+
+    /// The number of well-known encodings understood by every zenoh version, i.e. sent as
+    /// a single byte on the wire. The ones added later are sent as strings after the empty prefix.
+    pub(super) const WIRE_PREFIXES: usize = 21;
+
+    /// The MIME parameter carrying the schema of an encoding.
+    pub(super) const SCHEMA_PARAMETER: &str = "schema";
+
+    /// The maximum length of the suffix of an encoding as sent on the wire.
+    pub(super) const MAX_SUFFIX_LEN: usize = u8::MAX as usize;
 }
 
+/// The well-known encodings, identified on the wire by a single byte.
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum KnownEncoding {
@@ -71,6 +115,53 @@ pub enum KnownEncoding {
     ImageJpeg = 18,
     ImagePng = 19,
     ImageGif = 20,
+    AppCbor = 21,
+    AppProtobuf = 22,
+    AppAvro = 23,
+    AppMsgpack = 24,
+    AppYaml = 25,
+    AppCdr = 26,
+    AppLdJson = 27,
+    AppGeoJson = 28,
+    AppJsonSeq = 29,
+    AppPdf = 30,
+    AppZip = 31,
+    AppGzip = 32,
+    AppOpenmetricsText = 33,
+    TextYaml = 34,
+    TextMarkdown = 35,
+    TextEventStream = 36,
+    ImageBmp = 37,
+    ImageWebp = 38,
+    ImageSvgXml = 39,
+    AudioAac = 40,
+    AudioFlac = 41,
+    AudioMp4 = 42,
+    AudioOgg = 43,
+    AudioVorbis = 44,
+    VideoH264 = 45,
+    VideoH265 = 46,
+    VideoMp4 = 47,
+    VideoOgg = 48,
+    VideoRaw = 49,
+    VideoVp8 = 50,
+    VideoVp9 = 51,
+    ZenohSerialized = 52,
+}
+
+impl KnownEncoding {
+    /// Returns the well-known encoding whose MIME type is the longest prefix of `s`,
+    /// along with the length of that MIME type.
+    fn parse(s: &str) -> (KnownEncoding, usize) {
+        consts::MIMES
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter(|(_, v)| s.starts_with(*v))
+            .max_by_key(|(_, v)| v.len())
+            .map(|(i, v)| (unsafe { mem::transmute(i as u8) }, v.len()))
+            .unwrap_or((KnownEncoding::Empty, 0))
+    }
 }
 
 impl From<KnownEncoding> for u8 {
@@ -88,7 +179,7 @@ impl From<KnownEncoding> for &str {
 impl TryFrom<u8> for KnownEncoding {
     type Error = ZError;
     fn try_from(value: u8) -> Result<Self, Self::Error> {
-        if value < consts::MIMES.len() as u8 + 1 {
+        if (value as usize) < consts::MIMES.len() {
             Ok(unsafe { mem::transmute(value) })
         } else {
             Err(zerror!("Unknown encoding"))
@@ -106,6 +197,20 @@ impl AsRef<str> for KnownEncoding {
 ///
 /// A zenoh encoding is a HTTP Mime type represented, for wire efficiency,
 /// as an integer prefix (that maps to a string) and a string suffix.
+/// The suffix typically holds the MIME parameters, e.g. `;charset=utf-8`.
+///
+/// An encoding may also carry a schema, e.g. a protobuf message name or a schema registry id,
+/// as the `schema` MIME parameter of its suffix.
+///
+/// # Wire compatibility
+///
+/// Only the well-known encodings understood by every zenoh version (`EMPTY` to `IMAGE_GIF`)
+/// are sent as a single byte prefix. The others are sent as their string representation after
+/// the empty prefix, so that older peers decode the same string instead of rejecting the message,
+/// and are turned back into their well-known prefix when decoded.
+/// Any other MIME type may be used the same way, with the empty prefix.
+/// A prefix unknown to this version is decoded as `application/custom` with its suffix,
+/// rather than failing the decoding of the whole message.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Encoding {
     Exact(KnownEncoding),
@@ -117,16 +222,90 @@ impl Encoding {
     where
         IntoCowStr: Into<Cow<'static, str>> + AsRef<str>,
     {
-        let prefix = KnownEncoding::try_from(prefix)?;
-        let suffix = suffix.into();
-        if suffix.as_bytes().len() > u8::MAX as usize {
-            bail!("Suffix length is limited to 255 characters")
-        }
+        let encoding = Encoding::unchecked(KnownEncoding::try_from(prefix)?, suffix);
+        encoding.check()?;
+        Ok(encoding)
+    }
+
+    fn unchecked<IntoCowStr>(prefix: KnownEncoding, suffix: IntoCowStr) -> Self
+    where
+        IntoCowStr: Into<Cow<'static, str>> + AsRef<str>,
+    {
         if suffix.as_ref().is_empty() {
-            Ok(Encoding::Exact(prefix))
+            Encoding::Exact(prefix)
+        } else {
+            Encoding::WithSuffix(prefix, suffix.into().into())
+        }
+    }
+
+    /// Creates an encoding from the prefix and suffix received on the wire.
+    ///
+    /// The string representation following the empty prefix is parsed to recover
+    /// its well-known prefix, and an unknown prefix gives an `application/custom` encoding.
+    pub fn from_wire(prefix: u8, suffix: String) -> ZResult<Self> {
+        match KnownEncoding::try_from(prefix) {
+            Ok(KnownEncoding::Empty) => Encoding::from_str(&suffix),
+            Ok(prefix) => Encoding::new(prefix as u8, suffix),
+            Err(_) => Encoding::new(KnownEncoding::AppCustom as u8, suffix),
+        }
+    }
+
+    /// Returns the prefix of this encoding as sent on the wire.
+    pub fn wire_prefix(&self) -> u8 {
+        match *self.prefix() as u8 {
+            prefix if (prefix as usize) < consts::WIRE_PREFIXES => prefix,
+            _ => KnownEncoding::Empty as u8,
+        }
+    }
+
+    /// Returns the suffix of this encoding as sent on the wire.
+    pub fn wire_suffix(&self) -> Cow<'_, str> {
+        if (*self.prefix() as usize) < consts::WIRE_PREFIXES {
+            Cow::Borrowed(self.suffix())
+        } else {
+            Cow::Owned(self.to_string())
+        }
+    }
+
+    fn wire_suffix_len(&self) -> usize {
+        if (*self.prefix() as usize) < consts::WIRE_PREFIXES {
+            self.suffix().len()
         } else {
-            Ok(Encoding::WithSuffix(prefix, suffix.into()))
+            self.prefix().as_ref().len() + self.suffix().len()
+        }
+    }
+
+    /// Checks that the suffix of this encoding fits on the wire.
+    fn check(&self) -> ZResult<()> {
+        if self.wire_suffix_len() > consts::MAX_SUFFIX_LEN {
+            bail!(
+                "Suffix length is limited to {} bytes on the wire",
+                consts::MAX_SUFFIX_LEN
+            )
+        }
+        Ok(())
+    }
+
+    /// Makes this encoding fit on the wire by dropping its schema and then truncating its suffix.
+    fn truncated(self) -> Self {
+        if self.check().is_ok() {
+            return self;
+        }
+        let prefix = *self.prefix();
+        let mut suffix = without_parameter(self.suffix(), consts::SCHEMA_PARAMETER);
+        let mut len = consts::MAX_SUFFIX_LEN
+            - if (prefix as usize) < consts::WIRE_PREFIXES {
+                0
+            } else {
+                prefix.as_ref().len()
+            };
+        if suffix.len() > len {
+            while !suffix.is_char_boundary(len) {
+                len -= 1;
+            }
+            suffix.truncate(len);
         }
+        Encoding::unchecked(prefix, suffix)
     }
 
     /// Sets the suffix of this encoding.
@@ -140,6 +319,29 @@ impl Encoding {
         }
     }
 
+    /// Adds a MIME parameter to the suffix of this encoding.
+    pub fn with_parameter(self, key: &str, value: &str) -> ZResult<Self> {
+        let mut parameter = String::with_capacity(key.len() + value.len() + 2);
+        parameter.push(';');
+        parameter.push_str(key);
+        parameter.push('=');
+        parameter.push_str(value);
+        self.with_suffix(parameter)
+    }
+
+    /// Sets the schema of this encoding, replacing its `schema` MIME parameter, if any.
+    /// An empty schema removes it.
+    pub fn with_schema(self, schema: &str) -> ZResult<Self> {
+        let prefix = *self.prefix();
+        let suffix = without_parameter(self.suffix(), consts::SCHEMA_PARAMETER);
+        let encoding = Encoding::new(prefix as u8, suffix)?;
+        if schema.is_empty() {
+            Ok(encoding)
+        } else {
+            encoding.with_parameter(consts::SCHEMA_PARAMETER, schema)
+        }
+    }
+
     pub fn as_ref<'a, T>(&'a self) -> T
     where
         &'a Self: Into<T>,
@@ -169,6 +371,32 @@ impl Encoding {
             Encoding::WithSuffix(_, s) => s.as_ref(),
         }
     }
+
+    /// Returns the schema of this encoding, i.e. its `schema` MIME parameter, if any.
+    pub fn schema(&self) -> Option<&str> {
+        self.parameter(consts::SCHEMA_PARAMETER)
+    }
+
+    /// Returns the MIME parameters of this encoding, i.e. the `key=value` pairs
+    /// separated by `;` in its suffix.
+    pub fn parameters(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.suffix().split(';').skip(1).filter_map(|parameter| {
+            let (key, value) = parameter.split_once('=')?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value);
+            Some((key.trim(), value))
+        })
+    }
+
+    /// Returns the value of the given MIME parameter, if any.
+    pub fn parameter(&self, key: &str) -> Option<&str> {
+        self.parameters()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    }
 }
 
 impl Encoding {
@@ -194,6 +422,38 @@ impl Encoding {
     pub const IMAGE_JPEG: Encoding = Encoding::Exact(KnownEncoding::ImageJpeg);
     pub const IMAGE_PNG: Encoding = Encoding::Exact(KnownEncoding::ImagePng);
     pub const IMAGE_GIF: Encoding = Encoding::Exact(KnownEncoding::ImageGif);
+    pub const APP_CBOR: Encoding = Encoding::Exact(KnownEncoding::AppCbor);
+    pub const APP_PROTOBUF: Encoding = Encoding::Exact(KnownEncoding::AppProtobuf);
+    pub const APP_AVRO: Encoding = Encoding::Exact(KnownEncoding::AppAvro);
+    pub const APP_MSGPACK: Encoding = Encoding::Exact(KnownEncoding::AppMsgpack);
+    pub const APP_YAML: Encoding = Encoding::Exact(KnownEncoding::AppYaml);
+    pub const APP_CDR: Encoding = Encoding::Exact(KnownEncoding::AppCdr);
+    pub const APP_LD_JSON: Encoding = Encoding::Exact(KnownEncoding::AppLdJson);
+    pub const APP_GEO_JSON: Encoding = Encoding::Exact(KnownEncoding::AppGeoJson);
+    pub const APP_JSON_SEQ: Encoding = Encoding::Exact(KnownEncoding::AppJsonSeq);
+    pub const APP_PDF: Encoding = Encoding::Exact(KnownEncoding::AppPdf);
+    pub const APP_ZIP: Encoding = Encoding::Exact(KnownEncoding::AppZip);
+    pub const APP_GZIP: Encoding = Encoding::Exact(KnownEncoding::AppGzip);
+    pub const APP_OPENMETRICS_TEXT: Encoding = Encoding::Exact(KnownEncoding::AppOpenmetricsText);
+    pub const TEXT_YAML: Encoding = Encoding::Exact(KnownEncoding::TextYaml);
+    pub const TEXT_MARKDOWN: Encoding = Encoding::Exact(KnownEncoding::TextMarkdown);
+    pub const TEXT_EVENT_STREAM: Encoding = Encoding::Exact(KnownEncoding::TextEventStream);
+    pub const IMAGE_BMP: Encoding = Encoding::Exact(KnownEncoding::ImageBmp);
+    pub const IMAGE_WEBP: Encoding = Encoding::Exact(KnownEncoding::ImageWebp);
+    pub const IMAGE_SVG_XML: Encoding = Encoding::Exact(KnownEncoding::ImageSvgXml);
+    pub const AUDIO_AAC: Encoding = Encoding::Exact(KnownEncoding::AudioAac);
+    pub const AUDIO_FLAC: Encoding = Encoding::Exact(KnownEncoding::AudioFlac);
+    pub const AUDIO_MP4: Encoding = Encoding::Exact(KnownEncoding::AudioMp4);
+    pub const AUDIO_OGG: Encoding = Encoding::Exact(KnownEncoding::AudioOgg);
+    pub const AUDIO_VORBIS: Encoding = Encoding::Exact(KnownEncoding::AudioVorbis);
+    pub const VIDEO_H264: Encoding = Encoding::Exact(KnownEncoding::VideoH264);
+    pub const VIDEO_H265: Encoding = Encoding::Exact(KnownEncoding::VideoH265);
+    pub const VIDEO_MP4: Encoding = Encoding::Exact(KnownEncoding::VideoMp4);
+    pub const VIDEO_OGG: Encoding = Encoding::Exact(KnownEncoding::VideoOgg);
+    pub const VIDEO_RAW: Encoding = Encoding::Exact(KnownEncoding::VideoRaw);
+    pub const VIDEO_VP8: Encoding = Encoding::Exact(KnownEncoding::VideoVp8);
+    pub const VIDEO_VP9: Encoding = Encoding::Exact(KnownEncoding::VideoVp9);
+    pub const ZENOH_SERIALIZED: Encoding = Encoding::Exact(KnownEncoding::ZenohSerialized);
 }
 
 impl fmt::Display for Encoding {
@@ -208,42 +468,50 @@ impl fmt::Display for Encoding {
     }
 }
 
-impl From<&'static str> for Encoding {
-    fn from(s: &'static str) -> Self {
-        for (i, v) in consts::MIMES.iter().enumerate().skip(1) {
-            if let Some(suffix) = s.strip_prefix(v) {
-                if suffix.is_empty() {
-                    return Encoding::Exact(unsafe { mem::transmute(i as u8) });
-                } else {
-                    return Encoding::WithSuffix(unsafe { mem::transmute(i as u8) }, suffix.into());
+/// Returns the given suffix without the given MIME parameter.
+fn without_parameter(suffix: &str, key: &str) -> String {
+    let mut remaining = String::with_capacity(suffix.len());
+    for (i, segment) in suffix.split(';').enumerate() {
+        match segment.split_once('=') {
+            Some((k, _)) if i > 0 && k.trim().eq_ignore_ascii_case(key) => {}
+            _ => {
+                if i > 0 {
+                    remaining.push(';');
                 }
+                remaining.push_str(segment);
             }
         }
-        if s.is_empty() {
-            Encoding::Exact(KnownEncoding::Empty)
-        } else {
-            Encoding::WithSuffix(KnownEncoding::Empty, s.into())
-        }
+    }
+    remaining
+}
+
+impl FromStr for Encoding {
+    type Err = zenoh_result::Error;
+
+    /// Parses the string representation of an encoding, failing if its suffix
+    /// does not fit on the wire.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (prefix, len) = KnownEncoding::parse(s);
+        Encoding::new(prefix as u8, String::from(&s[len..]))
     }
 }
 
+/// A suffix longer than 255 bytes on the wire does not fit: the schema is dropped
+/// and the suffix truncated. Use [`Encoding::from_str`] to reject it instead.
+impl From<&'static str> for Encoding {
+    fn from(s: &'static str) -> Self {
+        let (prefix, len) = KnownEncoding::parse(s);
+        Encoding::unchecked(prefix, &s[len..]).truncated()
+    }
+}
+
+/// A suffix longer than 255 bytes on the wire does not fit: the schema is dropped
+/// and the suffix truncated. Use [`Encoding::from_str`] to reject it instead.
 impl From<String> for Encoding {
     fn from(mut s: String) -> Self {
-        for (i, v) in consts::MIMES.iter().enumerate().skip(1) {
-            if s.starts_with(v) {
-                s.replace_range(..v.len(), "");
-                if s.is_empty() {
-                    return Encoding::Exact(unsafe { mem::transmute(i as u8) });
-                } else {
-                    return Encoding::WithSuffix(unsafe { mem::transmute(i as u8) }, s.into());
-                }
-            }
-        }
-        if s.is_empty() {
-            Encoding::Exact(KnownEncoding::Empty)
-        } else {
-            Encoding::WithSuffix(KnownEncoding::Empty, s.into())
-        }
+        let (prefix, len) = KnownEncoding::parse(&s);
+        s.replace_range(..len, "");
+        Encoding::unchecked(prefix, s).truncated()
     }
 }
 
@@ -278,13 +546,22 @@ impl Encoding {
 
         let mut rng = rand::thread_rng();
 
-        let prefix: u8 = rng.gen_range(0..20);
+        let prefix: u8 = rng.gen_range(0..consts::MIMES.len() as u8);
         let suffix: String = if rng.gen_bool(0.5) {
             let len = rng.gen_range(MIN..MAX);
             Alphanumeric.sample_string(&mut rng, len)
         } else {
             String::new()
         };
-        Encoding::new(prefix, suffix).unwrap()
+        let schema: String = if rng.gen_bool(0.5) {
+            let len = rng.gen_range(MIN..MAX);
+            Alphanumeric.sample_string(&mut rng, len)
+        } else {
+            String::new()
+        };
+        Encoding::new(prefix, suffix)
+            .unwrap()
+            .with_schema(&schema)
+            .unwrap()
     }
 }
@@ -105,6 +105,7 @@ struct RecvOpenSynOut {
     other_whatami: WhatAmI,
     other_lease: Duration,
     other_initial_sn: TransportSn,
+    other_auth_user: Option<Vec<u8>>,
 }
 
 // OpenAck
@@ -468,11 +469,14 @@ impl<'a, 'b: 'a> AcceptFsm for &'a mut AcceptLink<'b> {
             .map_err(|e| (e, Some(close::reason::GENERIC)))?;
 
         // Extension Auth
-        #[cfg(feature = "transport_auth")]
-        self.ext_auth
-            .recv_open_syn((&mut state.link.ext_auth, open_syn.ext_auth))
-            .await
-            .map_err(|e| (e, Some(close::reason::GENERIC)))?;
+        let other_auth_user = zcondfeat!(
+            "transport_auth",
+            self.ext_auth
+                .recv_open_syn((&mut state.link.ext_auth, open_syn.ext_auth))
+                .await
+                .map_err(|e| (e, Some(close::reason::GENERIC)))?,
+            None
+        );
 
         // Extension MultiLink
         #[cfg(feature = "transport_multilink")]
@@ -499,6 +503,7 @@ impl<'a, 'b: 'a> AcceptFsm for &'a mut AcceptLink<'b> {
             other_whatami: cookie.whatami,
             other_lease: open_syn.lease,
             other_initial_sn: open_syn.initial_sn,
+            other_auth_user,
         };
         Ok((state, output))
     }
@@ -708,6 +713,7 @@ pub(crate) async fn accept_link(link: LinkUnicast, manager: &TransportManager) -
         #[cfg(feature = "shared-memory")]
         is_shm: state.transport.ext_shm.is_shm(),
         is_lowlatency: state.transport.ext_lowlatency.is_lowlatency(),
+        auth_user: osyn_out.other_auth_user,
     };
 
     let a_config = TransportLinkUnicastConfig {
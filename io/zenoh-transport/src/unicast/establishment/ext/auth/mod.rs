@@ -571,7 +571,8 @@ impl<'a> AcceptFsm for &'a AuthFsm<'a> {
     }
 
     type RecvOpenSynIn = (&'a mut StateAccept, Option<open::ext::Auth>);
-    type RecvOpenSynOut = ();
+    // The user authenticated with the UsrPwd extension, if any
+    type RecvOpenSynOut = Option<Vec<u8>>;
     async fn recv_open_syn(
         self,
         input: Self::RecvOpenSynIn,
@@ -600,18 +601,18 @@ impl<'a> AcceptFsm for &'a AuthFsm<'a> {
         }
 
         #[cfg(feature = "auth_usrpwd")]
-        {
-            match (self.usrpwd.as_ref(), state.usrpwd.as_mut()) {
-                (Some(e), Some(s)) => {
-                    let x = ztake!(exts, id::USRPWD);
-                    e.recv_open_syn((s, ztryinto!(x, S))).await?;
-                }
-                (None, None) => {}
-                _ => bail!("{S} Invalid UsrPwd configuration."),
+        let user = match (self.usrpwd.as_ref(), state.usrpwd.as_mut()) {
+            (Some(e), Some(s)) => {
+                let x = ztake!(exts, id::USRPWD);
+                Some(e.recv_open_syn((s, ztryinto!(x, S))).await?)
             }
-        }
+            (None, None) => None,
+            _ => bail!("{S} Invalid UsrPwd configuration."),
+        };
+        #[cfg(not(feature = "auth_usrpwd"))]
+        let user = None;
 
-        Ok(())
+        Ok(user)
     }
 
     type SendOpenAckIn = &'a StateAccept;
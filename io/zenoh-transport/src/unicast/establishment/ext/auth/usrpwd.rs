@@ -403,7 +403,7 @@ impl<'a> AcceptFsm for &'a AuthUsrPwdFsm<'a> {
     }
 
     type RecvOpenSynIn = (&'a mut StateAccept, Option<ext::OpenSyn>);
-    type RecvOpenSynOut = ();
+    type RecvOpenSynOut = User;
     async fn recv_open_syn(
         self,
         input: Self::RecvOpenSynIn,
@@ -434,7 +434,7 @@ impl<'a> AcceptFsm for &'a AuthUsrPwdFsm<'a> {
             bail!("{S} Invalid password.");
         }
 
-        Ok(())
+        Ok(open_syn.user)
     }
 
     type SendOpenAckIn = &'a StateAccept;
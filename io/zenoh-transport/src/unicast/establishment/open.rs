@@ -621,6 +621,7 @@ pub(crate) async fn open_link(
         #[cfg(feature = "shared-memory")]
         is_shm: state.transport.ext_shm.is_shm(),
         is_lowlatency: state.transport.ext_lowlatency.is_lowlatency(),
+        auth_user: None,
     };
 
     let o_config = TransportLinkUnicastConfig {
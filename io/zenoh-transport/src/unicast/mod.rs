@@ -56,6 +56,7 @@ pub(crate) struct TransportConfigUnicast {
     #[cfg(feature = "shared-memory")]
     pub(crate) is_shm: bool,
     pub(crate) is_lowlatency: bool,
+    pub(crate) auth_user: Option<Vec<u8>>,
 }
 
 /// [`TransportUnicast`] is the transport handler returned
@@ -109,6 +110,18 @@ impl TransportUnicast {
         Ok(tp)
     }
 
+    /// Returns the user the remote peer authenticated as with user-password
+    /// authentication, if any. Only known on the accepting side.
+    #[inline(always)]
+    pub fn get_auth_user(&self) -> ZResult<Option<String>> {
+        let transport = self.get_inner()?;
+        Ok(transport
+            .get_config()
+            .auth_user
+            .as_ref()
+            .map(|u| String::from_utf8_lossy(u).into_owned()))
+    }
+
     #[inline(always)]
     pub fn get_links(&self) -> ZResult<Vec<Link>> {
         let transport = self.get_inner()?;
@@ -532,6 +532,18 @@ async fn auth_usrpwd(endpoint: &EndPoint, lowlatency_transport: bool) {
     println!("Transport Authenticator UserPassword [2a1]: {res:?}");
     assert!(res.is_ok());
     let c_ses1 = res.unwrap();
+    // The router knows the user the client authenticated as
+    println!("Transport Authenticator UserPassword [2a2]");
+    let r_ses1 = ztimeout!(async {
+        loop {
+            if let Some(s) = router_manager.get_transport_unicast(&client01_id).await {
+                break s;
+            }
+            tokio::time::sleep(SLEEP).await;
+        }
+    });
+    assert_eq!(r_ses1.get_auth_user().unwrap(), Some(user01.clone()));
+    assert_eq!(c_ses1.get_auth_user().unwrap(), None);
 
     /* [3] */
     println!("Transport Authenticator UserPassword [3a1]");
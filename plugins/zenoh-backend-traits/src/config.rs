@@ -60,6 +60,13 @@ pub struct StorageConfig {
     pub garbage_collection_config: GarbageCollectionConfig,
     // Note: ReplicaConfig is optional. Alignment will be performed only if it is a replica
     pub replica_config: Option<ReplicaConfig>,
+    // Note: BatchingConfig is optional. Samples will be written one by one if not configured
+    pub batching_config: Option<BatchingConfig>,
+    // The time-to-live of the values, after which they are deleted
+    // Note: ttl is optional. Values will be kept until they are deleted if not configured
+    pub ttl: Option<Duration>,
+    // The policy resolving the conflicts between writes on a same key
+    pub conflict_resolution: ConflictResolution,
 }
 // Note: All parameters should be same for replicas, else will result on huge overhead
 #[derive(JsonSchema, Debug, Clone, PartialEq, Eq)]
@@ -117,6 +124,38 @@ impl Default for GarbageCollectionConfig {
     }
 }
 
+// The configuration for batching the writes of a storage
+#[derive(JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct BatchingConfig {
+    // The maximum number of samples in a batch
+    // The batch is written as soon as it reaches this size
+    pub max_size: usize,
+    // The maximum duration a sample can wait in a batch before the batch is written
+    pub max_delay: Duration,
+}
+
+// The policy resolving the conflicts between writes on a same key, in local writes as in replica alignment
+// Note: it applies to storages keeping the latest value of each key (History::Latest)
+#[derive(JsonSchema, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictResolution {
+    // The write with the latest timestamp wins
+    #[default]
+    LastWriterWins,
+    // The put with the earliest timestamp since the latest deletion wins
+    FirstWriterWins,
+    // The values are merged by the merge function of the volume (deletions are resolved as last-writer-wins)
+    Merge,
+}
+
+impl Default for BatchingConfig {
+    fn default() -> Self {
+        Self {
+            max_size: 1000,
+            max_delay: Duration::from_millis(100),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum ConfigDiff {
     DeleteVolume(VolumeConfig),
@@ -479,6 +518,47 @@ impl StorageConfig {
             }
             None => None,
         };
+        let batching_config = match config.get("batching") {
+            Some(s) => {
+                let mut batching_config = BatchingConfig::default();
+                if let Some(size) = s.get("max_size") {
+                    match size.as_u64() {
+                        Some(size) if size > 0 => batching_config.max_size = size as usize,
+                        _ => bail!("Invalid value for field `max_size` in `batching` of storage `{}`. Only strictly positive integer values are accepted.", storage_name),
+                    }
+                }
+                if let Some(delay) = s.get("max_delay") {
+                    let delay = delay.to_string().parse::<u64>();
+                    if let Ok(delay) = delay {
+                        batching_config.max_delay = Duration::from_millis(delay)
+                    } else {
+                        bail!("Invalid type for field `max_delay` in `batching` of storage `{}`. Only integer values are accepted.", storage_name)
+                    }
+                }
+                Some(batching_config)
+            }
+            None => None,
+        };
+        let ttl = match config.get("ttl") {
+            Some(ttl) => {
+                let ttl = ttl.to_string().parse::<u64>();
+                if let Ok(ttl) = ttl {
+                    Some(Duration::from_secs(ttl))
+                } else {
+                    bail!("Invalid type for field `ttl` of storage `{}`. Only integer values are accepted.", storage_name)
+                }
+            }
+            None => None,
+        };
+        let conflict_resolution = match config.get("conflict_resolution") {
+            Some(policy) => match policy.as_str() {
+                Some("last_writer_wins") => ConflictResolution::LastWriterWins,
+                Some("first_writer_wins") => ConflictResolution::FirstWriterWins,
+                Some("merge") => ConflictResolution::Merge,
+                _ => bail!("Invalid value for field `conflict_resolution` of storage `{}`. Accepted values: ['last_writer_wins', 'first_writer_wins', 'merge']", storage_name),
+            },
+            None => ConflictResolution::default(),
+        };
         Ok(StorageConfig {
             name: storage_name.into(),
             key_expr,
@@ -488,6 +568,9 @@ impl StorageConfig {
             volume_cfg,
             garbage_collection_config,
             replica_config,
+            batching_config,
+            ttl,
+            conflict_resolution,
         })
     }
 }
@@ -197,6 +197,14 @@ pub struct StoredData {
     pub timestamp: Timestamp,
 }
 
+/// Function merging the data stored for a key (first argument) with the data of an incoming write on this key
+/// (second argument), for the storages configured with the `merge` conflict resolution.
+/// The merged data is stored with a new timestamp, later than both ones, and is aligned as is (without being merged
+/// again) between the replicas of a storage. The merge should still be commutative, associative and idempotent
+/// (e.g. the merge of CRDT states encoded in the payloads), so that concurrent merges on several replicas don't lose writes.
+pub type MergeFunction =
+    Arc<dyn Fn(&OwnedKeyExpr, &StoredData, &StoredData) -> Value + Send + Sync>;
+
 /// Trait to be implemented by a Backend.
 ///
 #[async_trait]
@@ -218,6 +226,13 @@ pub trait Volume: Send + Sync {
     /// Returns an interceptor that will be called before sending any reply
     /// to a query from a storage created by this backend. `None` can be returned for no interception point.
     fn outgoing_data_interceptor(&self) -> Option<Arc<dyn Fn(Sample) -> Sample + Send + Sync>>;
+
+    /// Returns the function merging concurrent writes on a same key, for the storages created by this backend
+    /// and configured with the `merge` conflict resolution.
+    /// By default, `None` is returned and such storages fail to start.
+    fn merge_function(&self) -> Option<MergeFunction> {
+        None
+    }
 }
 
 pub type VolumeInstance = Box<dyn Volume + 'static>;
@@ -246,6 +261,12 @@ pub trait Storage: Send + Sync {
     /// on the administration space for this storage.
     fn get_admin_status(&self) -> serde_json::Value;
 
+    /// Returns the capability of this storage, if it differs from the one of its volume.
+    /// By default, a storage has the capability returned by [`Volume::get_capability`].
+    fn get_capability(&self) -> Option<Capability> {
+        None
+    }
+
     /// Function called for each incoming data ([`Sample`]) to be stored in this storage.
     /// A key can be `None` if it matches the `strip_prefix` exactly.
     /// In order to avoid data loss, the storage must store the `value` and `timestamp` associated with the `None` key
@@ -270,17 +291,104 @@ pub trait Storage: Send + Sync {
     /// Function to retrieve the sample associated with a single key.
     /// A key can be `None` if it matches the `strip_prefix` exactly.
     /// In order to avoid data loss, the storage must retrieve the `value` and `timestamp` associated with the `None` key
-    /// in a manner suitable for the given backend technology
+    /// in a manner suitable for the given backend technology.
+    /// If the storage has the [`History::All`] capability and the `parameters` contain a `_time` range,
+    /// all the values stored for the key within this range must be returned.
     async fn get(
         &mut self,
         key: Option<OwnedKeyExpr>,
         parameters: &str,
     ) -> ZResult<Vec<StoredData>>;
 
+    /// Function called for a batch of incoming data to be stored in this storage,
+    /// when the storage is configured with `batching`.
+    /// One result must be returned per entry, in the same order as the `entries`: only the entries
+    /// with an `Ok` result are considered as stored by the storage manager.
+    /// By default, [`Storage::put`] is called for each entry. A backend can override this function to
+    /// store the batch with a bulk insert or in a transaction, returning an error for every entry if it fails.
+    async fn put_batch(
+        &mut self,
+        entries: Vec<(Option<OwnedKeyExpr>, Value, Timestamp)>,
+    ) -> Vec<ZResult<StorageInsertionResult>> {
+        let mut results = Vec::with_capacity(entries.len());
+        for (key, value, timestamp) in entries {
+            results.push(self.put(key, value, timestamp).await);
+        }
+        results
+    }
+
+    /// Function called for a batch of incoming delete requests to this storage,
+    /// when the storage is configured with `batching`.
+    /// One result must be returned per entry, in the same order as the `entries`: only the entries
+    /// with an `Ok` result are considered as deleted by the storage manager.
+    /// By default, [`Storage::delete`] is called for each entry.
+    async fn delete_batch(
+        &mut self,
+        entries: Vec<(Option<OwnedKeyExpr>, Timestamp)>,
+    ) -> Vec<ZResult<StorageInsertionResult>> {
+        let mut results = Vec::with_capacity(entries.len());
+        for (key, timestamp) in entries {
+            results.push(self.delete(key, timestamp).await);
+        }
+        results
+    }
+
+    /// Function called for each incoming put on a key expression containing wildcards, stripped of the `strip_prefix`
+    /// (a `None` key being included if `key_expr` is `**`).
+    /// A storage supporting it must apply the value to all the stored keys included in `key_expr` and older than
+    /// `timestamp`, atomically and durably (i.e. the update is not lost on restart), and return the updated keys.
+    /// By default, `None` is returned: the storage manager then calls [`Storage::put`] for each matching key.
+    /// In both cases, the storage manager keeps track of the wildcard update (until garbage collected), in order to apply
+    /// it to the older writes on matching keys received later.
+    async fn put_wildcard(
+        &mut self,
+        _key_expr: OwnedKeyExpr,
+        _value: Value,
+        _timestamp: Timestamp,
+    ) -> ZResult<Option<Vec<Option<OwnedKeyExpr>>>> {
+        Ok(None)
+    }
+
+    /// Function called for each incoming delete on a key expression containing wildcards, stripped of the `strip_prefix`
+    /// (a `None` key being included if `key_expr` is `**`).
+    /// A storage supporting it must delete all the stored keys included in `key_expr` and older than `timestamp`,
+    /// atomically and durably (i.e. the deletion is not lost on restart), and return the deleted keys.
+    /// By default, `None` is returned: the storage manager then calls [`Storage::delete`] for each matching key.
+    /// In both cases, the storage manager keeps track of the wildcard delete (until garbage collected), in order to apply
+    /// it to the older writes on matching keys received later.
+    async fn delete_wildcard(
+        &mut self,
+        _key_expr: OwnedKeyExpr,
+        _timestamp: Timestamp,
+    ) -> ZResult<Option<Vec<Option<OwnedKeyExpr>>>> {
+        Ok(None)
+    }
+
     /// Function called to get the list of all storage content (key, timestamp)
     /// The latest Timestamp corresponding to each key is either the timestamp of the delete or put whichever is the latest.
     /// Remember to fetch the entry corresponding to the `None` key
     async fn get_all_entries(&self) -> ZResult<Vec<(Option<OwnedKeyExpr>, Timestamp)>>;
+
+    /// Function called to get a page of the storage content (key, timestamp), ordered by key,
+    /// in order to reply to queries without retrieving all the storage content at once.
+    /// The `None` key comes first. If `after` is `Some(key)`, only the entries after this key are returned.
+    /// At most `limit` entries are returned: a page with fewer entries is the last one.
+    /// By default, `None` is returned: the storage manager then calls [`Storage::get_all_entries`] once per query.
+    /// A backend holding a large number of keys should override this function to rely on a cursor of its own.
+    async fn get_entries_page(
+        &self,
+        _after: Option<Option<OwnedKeyExpr>>,
+        _limit: usize,
+    ) -> ZResult<Option<Vec<(Option<OwnedKeyExpr>, Timestamp)>>> {
+        Ok(None)
+    }
+}
+
+/// Compares the keys of a storage in the order of [`Storage::get_entries_page`].
+pub fn cmp_keys(a: &Option<OwnedKeyExpr>, b: &Option<OwnedKeyExpr>) -> std::cmp::Ordering {
+    a.as_deref()
+        .map(|k| k.as_str())
+        .cmp(&b.as_deref().map(|k| k.as_str()))
 }
 
 /// A wrapper around the [`zenoh::queryable::Query`] allowing to call the
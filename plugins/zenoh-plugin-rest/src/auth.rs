@@ -0,0 +1,181 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Authentication of the requests to the REST plugin.
+//!
+//! When users are configured, every request must carry an `Authorization` header, either
+//! `Basic` with the name and password of a user, or `Bearer` with the token of a user.
+//! The authenticated [`User`] is attached to the request, and each operation on a key
+//! expression is checked against its permissions before reaching the session.
+use base64::{engine::general_purpose::STANDARD as b64_std_engine, Engine};
+use http_types::Method;
+use std::str::FromStr;
+use std::sync::Arc;
+use tide::{Middleware, Next, Request, Response, StatusCode};
+use zenoh::prelude::r#async::*;
+use zenoh_result::{bail, zerror, ZResult};
+
+use crate::config::UserConfig;
+
+const AUTHORIZATION_HEADER: &str = "Authorization";
+const AUTHENTICATE_HEADER: &str = "WWW-Authenticate";
+const REALM: &str = r#"Basic realm="zenoh""#;
+
+pub(crate) struct User {
+    name: String,
+    password: Option<String>,
+    token: Option<String>,
+    permissions: Vec<(OwnedKeyExpr, Vec<Method>)>,
+}
+
+impl User {
+    // Returns true if the user is allowed to use `method` on all the keys of `key_expr`
+    pub(crate) fn is_allowed(&self, method: Method, key_expr: &keyexpr) -> bool {
+        self.permissions
+            .iter()
+            .any(|(allowed, methods)| methods.contains(&method) && allowed.includes(key_expr))
+    }
+}
+
+impl TryFrom<&UserConfig> for User {
+    type Error = zenoh_result::Error;
+
+    fn try_from(config: &UserConfig) -> ZResult<Self> {
+        if config.password.is_none() && config.token.is_none() {
+            bail!("User `{}` has neither password nor token", config.name);
+        }
+        let mut permissions = Vec::with_capacity(config.permissions.len());
+        for permission in &config.permissions {
+            let key_expr = OwnedKeyExpr::from_str(&permission.key_expr).map_err(|e| {
+                zerror!(
+                    "Invalid key expression `{}` in the permissions of user `{}`: {}",
+                    permission.key_expr,
+                    config.name,
+                    e
+                )
+            })?;
+            let methods = permission
+                .methods
+                .iter()
+                .map(|m| {
+                    Method::from_str(&m.to_uppercase()).map_err(|_| {
+                        zerror!(
+                            "Invalid method `{}` in the permissions of user `{}`",
+                            m,
+                            config.name
+                        )
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            permissions.push((key_expr, methods));
+        }
+        Ok(User {
+            name: config.name.clone(),
+            password: config.password.clone(),
+            token: config.token.clone(),
+            permissions,
+        })
+    }
+}
+
+// The middleware rejecting the requests that are not authenticated as one of the users
+pub(crate) struct Authenticator {
+    users: Vec<Arc<User>>,
+}
+
+impl Authenticator {
+    pub(crate) fn new(users: &[UserConfig]) -> ZResult<Self> {
+        let users = users
+            .iter()
+            .map(|u| User::try_from(u).map(Arc::new))
+            .collect::<ZResult<Vec<_>>>()?;
+        Ok(Authenticator { users })
+    }
+
+    fn authenticate(&self, authorization: &str) -> Option<Arc<User>> {
+        let (scheme, credentials) = authorization.split_once(' ')?;
+        let credentials = credentials.trim();
+        if scheme.eq_ignore_ascii_case("bearer") {
+            self.users
+                .iter()
+                .find(|u| u.token.as_deref() == Some(credentials))
+                .cloned()
+        } else if scheme.eq_ignore_ascii_case("basic") {
+            let decoded = b64_std_engine.decode(credentials).ok()?;
+            let decoded = String::from_utf8(decoded).ok()?;
+            let (name, password) = decoded.split_once(':')?;
+            self.users
+                .iter()
+                .find(|u| u.name == name && u.password.as_deref() == Some(password))
+                .cloned()
+        } else {
+            None
+        }
+    }
+}
+
+#[tide::utils::async_trait]
+impl<State: Clone + Send + Sync + 'static> Middleware<State> for Authenticator {
+    async fn handle(&self, mut req: Request<State>, next: Next<'_, State>) -> tide::Result {
+        // the CORS preflight requests carry no credentials
+        if req.method() == Method::Options {
+            return Ok(next.run(req).await);
+        }
+        let user = req
+            .header(AUTHORIZATION_HEADER)
+            .and_then(|values| self.authenticate(values.last().as_str()));
+        match user {
+            Some(user) => {
+                log::trace!("Request authenticated as user `{}`", user.name);
+                req.set_ext(user);
+                Ok(next.run(req).await)
+            }
+            None => {
+                let mut response = super::response(
+                    StatusCode::Unauthorized,
+                    "text/plain",
+                    "Missing or invalid credentials",
+                );
+                response.insert_header(AUTHENTICATE_HEADER, REALM);
+                Ok(response)
+            }
+        }
+    }
+}
+
+// Checks that the user authenticated for the request, if any, may use `method` on `key_expr`
+pub(crate) fn authorize<State>(
+    req: &Request<State>,
+    method: Method,
+    key_expr: &keyexpr,
+) -> Result<(), Response> {
+    is_allowed(req.ext::<Arc<User>>(), method, key_expr)
+        .map_err(|e| super::response(StatusCode::Forbidden, "text/plain", &e.to_string()))
+}
+
+pub(crate) fn is_allowed(
+    user: Option<&Arc<User>>,
+    method: Method,
+    key_expr: &keyexpr,
+) -> ZResult<()> {
+    match user {
+        Some(user) if !user.is_allowed(method, key_expr) => bail!(
+            "User `{}` is not allowed to {} `{}`",
+            user.name,
+            method,
+            key_expr
+        ),
+        _ => Ok(()),
+    }
+}
@@ -17,20 +17,118 @@ use serde::{de, Deserialize, Deserializer};
 use std::fmt;
 
 const DEFAULT_HTTP_INTERFACE: &str = "[::]";
+const DEFAULT_CORS_ALLOWED_ORIGIN: &str = "*";
+const REDACTED: &str = "***";
+const DEFAULT_SSE_HEARTBEAT_INTERVAL: u64 = 15;
+const DEFAULT_SSE_HISTORY: usize = 1000;
+const DEFAULT_SSE_LINGER: u64 = 60;
 
 #[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     #[serde(deserialize_with = "deserialize_http_port")]
     pub http_port: String,
+    #[serde(default)]
+    pub tls: Option<TlsConfig>,
+    #[serde(default = "default_cors_allowed_origins")]
+    pub cors_allowed_origins: Vec<String>,
+    // if not empty, the requests must be authenticated as one of the users
+    #[serde(default)]
+    pub users: Vec<UserConfig>,
+    #[serde(default)]
+    pub sse: SseConfig,
     #[serde(default, deserialize_with = "deserialize_path")]
     __path__: Option<Vec<String>>,
     __required__: Option<bool>,
     __config__: Option<String>,
 }
 
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TlsConfig {
+    // the path of the PEM file of the server certificate
+    pub server_certificate: String,
+    // the path of the PEM file of the server private key
+    pub server_private_key: String,
+}
+
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct UserConfig {
+    pub name: String,
+    // the password for basic authentication
+    #[serde(default)]
+    pub password: Option<String>,
+    // the token for bearer authentication
+    #[serde(default)]
+    pub token: Option<String>,
+    #[serde(default)]
+    pub permissions: Vec<PermissionConfig>,
+}
+
+// Allows the HTTP methods on the key expressions included in `key_expr`
+// (the admin space is only included in key expressions starting with `@`)
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PermissionConfig {
+    pub key_expr: String,
+    pub methods: Vec<String>,
+}
+
+// The Server-Sent Events streams of a key expression share a subscriber, and a history of its latest events
+// from which each stream is sent at its own pace, and resumed after a reconnection
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SseConfig {
+    // the interval in seconds of the heartbeats sent on idle streams
+    #[serde(default = "default_sse_heartbeat_interval")]
+    pub heartbeat_interval: u64,
+    // the maximum number of events in the history of a key expression
+    #[serde(default = "default_sse_history")]
+    pub history: usize,
+    // the time in seconds the history of a key expression is kept after its last stream is closed
+    #[serde(default = "default_sse_linger")]
+    pub linger: u64,
+}
+
+impl Default for SseConfig {
+    fn default() -> Self {
+        SseConfig {
+            heartbeat_interval: DEFAULT_SSE_HEARTBEAT_INTERVAL,
+            history: DEFAULT_SSE_HISTORY,
+            linger: DEFAULT_SSE_LINGER,
+        }
+    }
+}
+
+fn default_sse_heartbeat_interval() -> u64 {
+    DEFAULT_SSE_HEARTBEAT_INTERVAL
+}
+
+fn default_sse_history() -> usize {
+    DEFAULT_SSE_HISTORY
+}
+
+fn default_sse_linger() -> u64 {
+    DEFAULT_SSE_LINGER
+}
+
+fn default_cors_allowed_origins() -> Vec<String> {
+    vec![DEFAULT_CORS_ALLOWED_ORIGIN.to_string()]
+}
+
 impl From<&Config> for serde_json::Value {
     fn from(c: &Config) -> Self {
+        // do not expose the credentials of the users
+        let mut c = c.clone();
+        for user in c.users.iter_mut() {
+            if user.password.is_some() {
+                user.password = Some(REDACTED.to_string());
+            }
+            if user.token.is_some() {
+                user.token = Some(REDACTED.to_string());
+            }
+        }
         serde_json::to_value(c).unwrap()
     }
 }
@@ -140,7 +238,10 @@ impl<'de> serde::de::Visitor<'de> for PathVisitor {
 
 #[cfg(test)]
 mod tests {
-    use super::{Config, DEFAULT_HTTP_INTERFACE};
+    use super::{
+        Config, DEFAULT_CORS_ALLOWED_ORIGIN, DEFAULT_HTTP_INTERFACE,
+        DEFAULT_SSE_HEARTBEAT_INTERVAL, DEFAULT_SSE_HISTORY, REDACTED,
+    };
 
     #[test]
     fn test_path_field() {
@@ -215,4 +316,58 @@ mod tests {
         assert_eq!(__path__, None);
         assert_eq!(__required__, None);
     }
+
+    #[test]
+    fn test_security_fields() {
+        let config = serde_json::from_str::<Config>(r#"{"http_port": 8080}"#).unwrap();
+        assert!(config.tls.is_none());
+        assert_eq!(
+            config.cors_allowed_origins,
+            vec![DEFAULT_CORS_ALLOWED_ORIGIN]
+        );
+        assert!(config.users.is_empty());
+
+        let config = serde_json::from_str::<Config>(
+            r#"{
+                "http_port": 8080,
+                "tls": {"server_certificate": "cert.pem", "server_private_key": "key.pem"},
+                "cors_allowed_origins": ["https://example.com"],
+                "users": [
+                    {"name": "alice", "password": "secret", "permissions": [{"key_expr": "demo/**", "methods": ["GET"]}]},
+                    {"name": "bob", "token": "abcd"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(config.tls.as_ref().unwrap().server_certificate, "cert.pem");
+        assert_eq!(config.cors_allowed_origins, vec!["https://example.com"]);
+        assert_eq!(config.users.len(), 2);
+        assert_eq!(config.users[0].permissions[0].key_expr, "demo/**");
+        assert!(config.users[1].permissions.is_empty());
+
+        // the credentials are not exposed
+        let value = serde_json::Value::from(&config);
+        assert_eq!(value["users"][0]["password"], REDACTED);
+        assert_eq!(value["users"][1]["token"], REDACTED);
+        assert!(value["users"][1]["password"].is_null());
+    }
+
+    #[test]
+    fn test_sse_field() {
+        let config = serde_json::from_str::<Config>(r#"{"http_port": 8080}"#).unwrap();
+        assert_eq!(
+            config.sse.heartbeat_interval,
+            DEFAULT_SSE_HEARTBEAT_INTERVAL
+        );
+        assert_eq!(config.sse.history, DEFAULT_SSE_HISTORY);
+
+        let config =
+            serde_json::from_str::<Config>(r#"{"http_port": 8080, "sse": {"history": 10}}"#)
+                .unwrap();
+        assert_eq!(
+            config.sse.heartbeat_interval,
+            DEFAULT_SSE_HEARTBEAT_INTERVAL
+        );
+        assert_eq!(config.sse.history, 10);
+    }
 }
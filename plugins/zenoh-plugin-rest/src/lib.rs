@@ -20,25 +20,34 @@
 use async_std::prelude::FutureExt;
 use base64::{engine::general_purpose::STANDARD as b64_std_engine, Engine};
 use futures::StreamExt;
+use http_types::headers::{HeaderName, HeaderValue};
 use http_types::Method;
 use std::convert::TryFrom;
 use std::str::FromStr;
 use std::sync::Arc;
 use tide::http::Mime;
-use tide::sse::Sender;
 use tide::{Request, Response, Server, StatusCode};
 use zenoh::plugins::{RunningPluginTrait, ZenohPlugin};
 use zenoh::prelude::r#async::*;
 use zenoh::properties::Properties;
 use zenoh::query::{QueryConsolidation, Reply};
 use zenoh::runtime::Runtime;
+use zenoh::sample::Attachment;
 use zenoh::selector::TIME_RANGE_KEY;
 use zenoh::Session;
 use zenoh_plugin_trait::{plugin_long_version, plugin_version, Plugin, PluginControl};
 use zenoh_result::{bail, zerror, ZResult};
 
+use crate::options::Options;
+
+mod auth;
 mod config;
 pub use config::Config;
+mod openapi;
+mod options;
+mod sse;
+mod tls;
+mod ws;
 
 const GIT_VERSION: &str = git_version::git_version!(prefix = "v", cargo_prefix = "v");
 lazy_static::lazy_static! {
@@ -46,64 +55,97 @@ lazy_static::lazy_static! {
 }
 const RAW_KEY: &str = "_raw";
 
-fn value_to_json(value: Value) -> String {
+// The JSON representation of a value, if it has one
+fn json_repr(value: &Value) -> Option<serde_json::Value> {
     // @TODO: transcode to JSON when implemented in Value
     match &value.encoding {
         p if p.starts_with(KnownEncoding::TextPlain)
             || p.starts_with(KnownEncoding::AppXWwwFormUrlencoded) =>
         {
-            // convert to Json string for special characters escaping
-            serde_json::json!(value.to_string()).to_string()
+            Some(serde_json::json!(value.to_string()))
         }
         p if p.starts_with(KnownEncoding::AppProperties) => {
-            // convert to Json string for special characters escaping
-            serde_json::json!(*Properties::from(value.to_string())).to_string()
+            Some(serde_json::json!(*Properties::from(value.to_string())))
         }
         p if p.starts_with(KnownEncoding::AppJson)
             || p.starts_with(KnownEncoding::AppInteger)
             || p.starts_with(KnownEncoding::AppFloat) =>
         {
-            value.to_string()
+            serde_json::from_slice(&value.payload.contiguous()).ok()
         }
-        _ => {
-            format!(r#""{}""#, b64_std_engine.encode(value.payload.contiguous()))
+        _ => None,
+    }
+}
+
+fn value_to_json(value: Value) -> serde_json::Value {
+    match json_repr(&value) {
+        Some(json) => json,
+        // an invalid JSON payload is kept as a string
+        None if value.encoding.starts_with(KnownEncoding::AppJson)
+            || value.encoding.starts_with(KnownEncoding::AppInteger)
+            || value.encoding.starts_with(KnownEncoding::AppFloat) =>
+        {
+            serde_json::json!(value.to_string())
         }
+        None => serde_json::json!(b64_std_engine.encode(value.payload.contiguous())),
+    }
+}
+
+fn attachment_to_json(attachment: Option<&Attachment>) -> serde_json::Value {
+    match attachment {
+        Some(attachment) => attachment
+            .iter()
+            .map(|(k, v)| {
+                (
+                    String::from_utf8_lossy(&k).into_owned(),
+                    String::from_utf8_lossy(&v).into(),
+                )
+            })
+            .collect::<serde_json::Map<_, _>>()
+            .into(),
+        None => serde_json::Value::Null,
     }
 }
 
+fn sample_fields_to_json(sample: Sample) -> serde_json::Value {
+    serde_json::json!({
+        "key": sample.key_expr.as_str(),
+        "encoding": sample.value.encoding.to_string(),
+        "time": sample
+            .timestamp
+            .map_or_else(|| "None".to_string(), |ts| ts.to_string()),
+        "source_id": sample.source_info.source_id.map(|id| id.to_string()),
+        "source_sn": sample.source_info.source_sn,
+        "attachment": attachment_to_json(sample.attachment()),
+        "value": value_to_json(sample.value),
+    })
+}
+
 fn sample_to_json(sample: Sample) -> String {
-    let encoding = sample.value.encoding.to_string();
-    format!(
-        r#"{{ "key": "{}", "value": {}, "encoding": "{}", "time": "{}" }}"#,
-        sample.key_expr.as_str(),
-        value_to_json(sample.value),
-        encoding,
-        if let Some(ts) = sample.timestamp {
-            ts.to_string()
-        } else {
-            "None".to_string()
-        }
-    )
+    sample_fields_to_json(sample).to_string()
 }
 
-fn result_to_json(sample: Result<Sample, Value>) -> String {
-    match sample {
-        Ok(sample) => sample_to_json(sample),
-        Err(err) => {
-            let encoding = err.encoding.to_string();
-            format!(
-                r#"{{ "key": "ERROR", "value": {}, "encoding": "{}"}}"#,
-                value_to_json(err),
-                encoding,
-            )
+fn reply_to_json(reply: Reply) -> String {
+    match reply.sample {
+        Ok(sample) => {
+            let mut json = sample_fields_to_json(sample);
+            json["replier_id"] = reply.replier_id.to_string().into();
+            json.to_string()
         }
+        Err(err) => serde_json::json!({
+            "key": "ERROR",
+            "encoding": err.encoding.to_string(),
+            "replier_id": reply.replier_id.to_string(),
+            "value": value_to_json(err),
+        })
+        .to_string(),
     }
 }
 
 async fn to_json(results: flume::Receiver<Reply>) -> String {
     let values = results
         .stream()
-        .filter_map(move |reply| async move { Some(result_to_json(reply.sample)) })
+        .filter_map(move |reply| async move { Some(reply_to_json(reply)) })
         .collect::<Vec<String>>()
         .await
         .join(",\n");
@@ -118,21 +160,47 @@ async fn to_json_response(results: flume::Receiver<Reply>) -> Response {
     )
 }
 
-fn sample_to_html(sample: Sample) -> String {
+fn sample_to_html(sample: Sample, replier_id: ZenohId) -> String {
+    let mut metadata = format!(
+        "encoding: {}, time: {}, replier: {}",
+        sample.value.encoding,
+        sample
+            .timestamp
+            .map_or_else(|| "None".to_string(), |ts| ts.to_string()),
+        replier_id
+    );
+    if let Some(source_id) = sample.source_info.source_id {
+        metadata.push_str(&format!(", source: {source_id}"));
+        if let Some(source_sn) = sample.source_info.source_sn {
+            metadata.push_str(&format!("#{source_sn}"));
+        }
+    }
+    if let Some(attachment) = sample.attachment() {
+        for (k, v) in attachment.iter() {
+            metadata.push_str(&format!(
+                ", {}: {}",
+                String::from_utf8_lossy(&k),
+                String::from_utf8_lossy(&v)
+            ));
+        }
+    }
     format!(
-        "<dt>{}</dt>\n<dd>{}</dd>\n",
+        "<dt>{}</dt>\n<dd>{}</dd>\n<dd><small>{}</small></dd>\n",
         sample.key_expr.as_str(),
-        String::from_utf8_lossy(&sample.payload.contiguous())
+        String::from_utf8_lossy(&sample.payload.contiguous()),
+        metadata
     )
 }
 
-fn result_to_html(sample: Result<Sample, Value>) -> String {
-    match sample {
-        Ok(sample) => sample_to_html(sample),
+fn reply_to_html(reply: Reply) -> String {
+    match reply.sample {
+        Ok(sample) => sample_to_html(sample, reply.replier_id),
         Err(err) => {
             format!(
-                "<dt>ERROR</dt>\n<dd>{}</dd>\n",
-                String::from_utf8_lossy(&err.payload.contiguous())
+                "<dt>ERROR</dt>\n<dd>{}</dd>\n<dd><small>encoding: {}, replier: {}</small></dd>\n",
+                String::from_utf8_lossy(&err.payload.contiguous()),
+                err.encoding,
+                reply.replier_id
             )
         }
     }
@@ -141,7 +209,7 @@ fn result_to_html(sample: Result<Sample, Value>) -> String {
 async fn to_html(results: flume::Receiver<Reply>) -> String {
     let values = results
         .stream()
-        .filter_map(move |reply| async move { Some(result_to_html(reply.sample)) })
+        .filter_map(move |reply| async move { Some(reply_to_html(reply)) })
         .collect::<Vec<String>>()
         .await
         .join("\n");
@@ -155,11 +223,26 @@ async fn to_html_response(results: flume::Receiver<Reply>) -> Response {
 async fn to_raw_response(results: flume::Receiver<Reply>) -> Response {
     match results.recv_async().await {
         Ok(reply) => match reply.sample {
-            Ok(sample) => response(
-                StatusCode::Ok,
-                sample.value.encoding.to_string().as_ref(),
-                String::from_utf8_lossy(&sample.payload.contiguous()).as_ref(),
-            ),
+            Ok(sample) => {
+                let mut response = response(
+                    StatusCode::Ok,
+                    sample.value.encoding.to_string().as_ref(),
+                    String::from_utf8_lossy(&sample.payload.contiguous()).as_ref(),
+                );
+                // the attachment is returned as headers, skipping the entries not valid as headers
+                if let Some(attachment) = sample.attachment() {
+                    for (k, v) in attachment.iter() {
+                        let name = HeaderName::from_bytes(
+                            [options::ATTACHMENT_HEADER_PREFIX.as_bytes(), &k].concat(),
+                        );
+                        let value = HeaderValue::from_bytes(v.to_vec());
+                        if let (Ok(name), Ok(value)) = (name, value) {
+                            response.insert_header(name, value);
+                        }
+                    }
+                }
+                response
+            }
             Err(value) => response(
                 StatusCode::Ok,
                 value.encoding.to_string().as_ref(),
@@ -181,7 +264,6 @@ fn method_to_kind(method: Method) -> SampleKind {
 fn response(status: StatusCode, content_type: impl TryInto<Mime>, body: &str) -> Response {
     let mut builder = Response::builder(status)
         .header("content-length", body.len().to_string())
-        .header("Access-Control-Allow-Origin", "*")
         .body(body);
     if let Ok(mime) = content_type.try_into() {
         builder = builder.content_type(mime);
@@ -301,6 +383,10 @@ fn with_extended_string<R, F: FnMut(&mut String) -> R>(
 async fn query(mut req: Request<(Arc<Session>, String)>) -> tide::Result<Response> {
     log::trace!("Incoming GET request: {:?}", req);
 
+    if ws::is_upgrade(&req) {
+        return ws::upgrade(req).await;
+    }
+
     let first_accept = match req.header("accept") {
         Some(accept) => accept[0]
             .to_string()
@@ -314,67 +400,20 @@ async fn query(mut req: Request<(Arc<Session>, String)>) -> tide::Result<Respons
         None => "application/json".to_string(),
     };
     if first_accept == "text/event-stream" {
-        Ok(tide::sse::upgrade(
-            req,
-            move |req: Request<(Arc<Session>, String)>, sender: Sender| async move {
-                let key_expr = match path_to_key_expr(req.url().path(), &req.state().1) {
-                    Ok(ke) => ke.into_owned(),
-                    Err(e) => {
-                        return Err(tide::Error::new(
-                            tide::StatusCode::BadRequest,
-                            anyhow::anyhow!("{}", e),
-                        ))
-                    }
-                };
-                async_std::task::spawn(async move {
-                    log::debug!(
-                        "Subscribe to {} for SSE stream (task {})",
-                        key_expr,
-                        async_std::task::current().id()
-                    );
-                    let sender = &sender;
-                    let sub = req
-                        .state()
-                        .0
-                        .declare_subscriber(&key_expr)
-                        .res()
-                        .await
-                        .unwrap();
-                    loop {
-                        let sample = sub.recv_async().await.unwrap();
-                        match sender
-                            .send(&sample.kind.to_string(), sample_to_json(sample), None)
-                            .timeout(std::time::Duration::new(10, 0))
-                            .await
-                        {
-                            Ok(Ok(_)) => {}
-                            Ok(Err(e)) => {
-                                log::debug!(
-                                    "SSE error ({})! Unsubscribe and terminate (task {})",
-                                    e,
-                                    async_std::task::current().id()
-                                );
-                                if let Err(e) = sub.undeclare().res().await {
-                                    log::error!("Error undeclaring subscriber: {}", e);
-                                }
-                                break;
-                            }
-                            Err(_) => {
-                                log::debug!(
-                                    "SSE timeout! Unsubscribe and terminate (task {})",
-                                    async_std::task::current().id()
-                                );
-                                if let Err(e) = sub.undeclare().res().await {
-                                    log::error!("Error undeclaring subscriber: {}", e);
-                                }
-                                break;
-                            }
-                        }
-                    }
-                });
-                Ok(())
-            },
-        ))
+        let key_expr = match path_to_key_expr(req.url().path(), &req.state().1) {
+            Ok(ke) => OwnedKeyExpr::from(ke),
+            Err(e) => {
+                return Ok(response(
+                    StatusCode::BadRequest,
+                    "text/plain",
+                    &e.to_string(),
+                ))
+            }
+        };
+        if let Err(response) = auth::authorize(&req, Method::Get, &key_expr) {
+            return Ok(response);
+        }
+        Ok(sse::upgrade(req, key_expr).await)
     } else {
         let body = req.body_bytes().await.unwrap_or_default();
         let url = req.url();
@@ -388,24 +427,51 @@ async fn query(mut req: Request<(Arc<Session>, String)>) -> tide::Result<Respons
                 ))
             }
         };
+        if let Err(response) = auth::authorize(&req, req.method(), &key_expr) {
+            return Ok(response);
+        }
+        let options = match Options::from_request(&req) {
+            Ok(options) => options,
+            Err(e) => {
+                return Ok(response(
+                    StatusCode::BadRequest,
+                    "text/plain",
+                    &e.to_string(),
+                ))
+            }
+        };
         let query_part = url.query();
         let selector = if let Some(q) = query_part {
-            Selector::from(key_expr).with_parameters(q)
+            let mut selector = Selector::from(key_expr);
+            selector.set_parameters(options::strip_reserved_parameters(q));
+            selector
         } else {
             key_expr.into()
         };
-        let consolidation = if selector.decode().any(|(k, _)| k.as_ref() == TIME_RANGE_KEY) {
-            QueryConsolidation::from(zenoh::query::ConsolidationMode::None)
-        } else {
-            QueryConsolidation::from(zenoh::query::ConsolidationMode::Latest)
-        };
+        let consolidation = options.consolidation(
+            if selector.decode().any(|(k, _)| k.as_ref() == TIME_RANGE_KEY) {
+                QueryConsolidation::from(zenoh::query::ConsolidationMode::None)
+            } else {
+                QueryConsolidation::from(zenoh::query::ConsolidationMode::Latest)
+            },
+        );
         let raw = selector.decode().any(|(k, _)| k.as_ref() == RAW_KEY);
-        let mut query = req.state().0.get(&selector).consolidation(consolidation);
+        let query = req.state().0.get(&selector).consolidation(consolidation);
+        let mut query = match options.apply_to_get(query) {
+            Ok(query) => query,
+            Err(e) => {
+                return Ok(response(
+                    StatusCode::BadRequest,
+                    "text/plain",
+                    &e.to_string(),
+                ))
+            }
+        };
         if !body.is_empty() {
-            let encoding: Encoding = req
-                .content_type()
-                .map(|m| m.to_string().into())
-                .unwrap_or_default();
+            let encoding = match content_encoding(&req) {
+                Ok(encoding) => encoding,
+                Err(response) => return Ok(response),
+            };
             query = query.with_value(Value::from(body).encoding(encoding));
         }
         match query.res().await {
@@ -427,6 +493,15 @@ async fn query(mut req: Request<(Arc<Session>, String)>) -> tide::Result<Respons
     }
 }
 
+/// The encoding of the body of the request, given by its content type.
+fn content_encoding<State>(req: &Request<State>) -> Result<Encoding, Response> {
+    match req.content_type() {
+        Some(mime) => Encoding::from_str(&mime.to_string())
+            .map_err(|e| response(StatusCode::BadRequest, "text/plain", &e.to_string())),
+        None => Ok(Encoding::default()),
+    }
+}
+
 async fn write(mut req: Request<(Arc<Session>, String)>) -> tide::Result<Response> {
     log::trace!("Incoming PUT request: {:?}", req);
     match req.body_bytes().await {
@@ -441,21 +516,31 @@ async fn write(mut req: Request<(Arc<Session>, String)>) -> tide::Result<Respons
                     ))
                 }
             };
-            let encoding: Encoding = req
-                .content_type()
-                .map(|m| m.to_string().into())
-                .unwrap_or_default();
+            if let Err(response) = auth::authorize(&req, req.method(), &key_expr) {
+                return Ok(response);
+            }
+            let encoding = match content_encoding(&req) {
+                Ok(encoding) => encoding,
+                Err(response) => return Ok(response),
+            };
 
-            // @TODO: Define the right congestion control value
-            match req
+            let put = req
                 .state()
                 .0
                 .put(&key_expr, bytes)
                 .encoding(encoding)
-                .kind(method_to_kind(req.method()))
-                .res()
-                .await
-            {
+                .kind(method_to_kind(req.method()));
+            let put = match Options::from_request(&req).and_then(|o| o.apply_to_put(put)) {
+                Ok(put) => put,
+                Err(e) => {
+                    return Ok(response(
+                        StatusCode::BadRequest,
+                        "text/plain",
+                        &e.to_string(),
+                    ))
+                }
+            };
+            match put.res().await {
                 Ok(_) => Ok(Response::new(StatusCode::Ok)),
                 Err(e) => Ok(response(
                     StatusCode::InternalServerError,
@@ -478,10 +563,20 @@ pub async fn run(runtime: Runtime, conf: Config) -> ZResult<()> {
     // But cannot be done twice in case of static link.
     let _ = env_logger::try_init();
 
+    let tls_config = conf.tls.as_ref().map(tls::load_server_config).transpose()?;
+    let authenticator = if conf.users.is_empty() {
+        None
+    } else {
+        Some(auth::Authenticator::new(&conf.users)?)
+    };
+
     let zid = runtime.zid().to_string();
-    let session = zenoh::init(runtime).res().await.unwrap();
+    let session = Arc::new(zenoh::init(runtime).res().await.unwrap());
+    let sse_streams = Arc::new(sse::SseStreams::new(session.clone(), conf.sse.clone()));
 
-    let mut app = Server::with_state((Arc::new(session), zid));
+    // also checked on the WebSocket handshakes, which the CORS middleware doesn't cover
+    let allowed_origins = tide::security::Origin::from(conf.cors_allowed_origins.clone());
+    let mut app = Server::with_state((session, zid));
     app.with(
         tide::security::CorsMiddleware::new()
             .allow_methods(
@@ -489,9 +584,40 @@ pub async fn run(runtime: Runtime, conf: Config) -> ZResult<()> {
                     .parse::<http_types::headers::HeaderValue>()
                     .unwrap(),
             )
-            .allow_origin(tide::security::Origin::from("*"))
+            // the Authorization header is not covered by the wildcard
+            .allow_headers(
+                "*, Authorization"
+                    .parse::<http_types::headers::HeaderValue>()
+                    .unwrap(),
+            )
+            .allow_origin(allowed_origins.clone())
             .allow_credentials(false),
     );
+    if let Some(authenticator) = authenticator {
+        app.with(authenticator);
+    }
+    app.with(tide::utils::Before(
+        move |mut req: Request<(Arc<Session>, String)>| {
+            let sse_streams = sse_streams.clone();
+            let allowed_origins = allowed_origins.clone();
+            async move {
+                req.set_ext(sse_streams);
+                req.set_ext(allowed_origins);
+                req
+            }
+        },
+    ));
+
+    let openapi = openapi::document(&conf, GIT_VERSION).to_string();
+    app.at(openapi::OPENAPI_PATH).get(move |_| {
+        let openapi = openapi.clone();
+        async move { Ok(response(StatusCode::Ok, "application/json", &openapi)) }
+    });
+    let schemas = openapi::schemas().to_string();
+    app.at(openapi::SCHEMA_PATH).get(move |_| {
+        let schemas = schemas.clone();
+        async move { Ok(response(StatusCode::Ok, "application/json", &schemas)) }
+    });
 
     app.at("/")
         .get(query)
@@ -506,7 +632,11 @@ pub async fn run(runtime: Runtime, conf: Config) -> ZResult<()> {
         .patch(write)
         .delete(write);
 
-    if let Err(e) = app.listen(conf.http_port).await {
+    let result = match tls_config {
+        Some(tls_config) => tls::listen(app, &conf.http_port, tls_config).await,
+        None => app.listen(conf.http_port).await,
+    };
+    if let Err(e) = result {
         log::error!("Unable to start http server for REST: {:?}", e);
         return Err(e.into());
     }
@@ -523,3 +653,27 @@ fn path_to_key_expr<'a>(path: &'a str, zid: &str) -> ZResult<KeyExpr<'a>> {
         KeyExpr::try_from(path)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{sample_to_json, value_to_json};
+    use zenoh::prelude::*;
+
+    #[test]
+    fn test_sample_to_json() {
+        let sample = Sample::new(
+            KeyExpr::try_from("demo/example").unwrap(),
+            Value::from(r#"quoted "text""#),
+        );
+        let json: serde_json::Value = serde_json::from_str(&sample_to_json(sample)).unwrap();
+        assert_eq!(json["key"], "demo/example");
+        assert_eq!(json["value"], r#"quoted "text""#);
+        assert_eq!(json["encoding"], "text/plain");
+
+        // the invalid JSON payloads are kept as strings
+        let value = Value::from(r#"{"invalid""#).encoding(KnownEncoding::AppJson.into());
+        assert_eq!(value_to_json(value), r#"{"invalid""#);
+        let value = Value::from(r#"{"valid": 1}"#).encoding(KnownEncoding::AppJson.into());
+        assert_eq!(value_to_json(value), serde_json::json!({"valid": 1}));
+    }
+}
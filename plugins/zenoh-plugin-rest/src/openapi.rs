@@ -0,0 +1,363 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! The OpenAPI description of the HTTP API of the REST plugin.
+//!
+//! The document is generated when the server starts, with the JSON schemas of the
+//! bodies derived from the types below, and served on [`OPENAPI_PATH`]. The JSON
+//! schemas alone are served on [`SCHEMA_PATH`].
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::JsonSchema;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use crate::config::Config;
+use crate::options;
+
+pub(crate) const OPENAPI_PATH: &str = "/@/rest/openapi.json";
+pub(crate) const SCHEMA_PATH: &str = "/@/rest/schema.json";
+const OPENAPI_VERSION: &str = "3.0.3";
+
+/// A value received by a subscription, as sent in the Server-Sent Events.
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+struct Sample {
+    /// The key of the value.
+    key: String,
+    /// The value: a JSON value for the JSON, integer and float encodings, a string for the
+    /// text encodings, an object for the properties encoding, and the base64 of the payload otherwise.
+    value: Value,
+    /// The encoding of the value.
+    encoding: String,
+    /// The timestamp of the value, or "None".
+    time: String,
+    /// The zenoh id of the source of the value.
+    source_id: Option<String>,
+    /// The sequence number of the value from its source.
+    source_sn: Option<u64>,
+    /// The attachment of the value.
+    attachment: Option<HashMap<String, String>>,
+}
+
+/// A reply to a query.
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+struct Reply {
+    /// The key of the value, or "ERROR" for an error reply.
+    key: String,
+    /// The value, encoded as in a `Sample`.
+    value: Value,
+    /// The encoding of the value.
+    encoding: String,
+    /// The timestamp of the value, or "None". Absent for an error reply.
+    time: Option<String>,
+    /// The zenoh id of the source of the value.
+    source_id: Option<String>,
+    /// The sequence number of the value from its source.
+    source_sn: Option<u64>,
+    /// The attachment of the value.
+    attachment: Option<HashMap<String, String>>,
+    /// The zenoh id of the replier.
+    replier_id: String,
+}
+
+/// The replies to a query.
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+struct Replies(Vec<Reply>);
+
+fn schema_generator(settings: SchemaSettings) -> SchemaGenerator {
+    let mut generator = settings.into_generator();
+    generator.subschema_for::<Sample>();
+    generator.subschema_for::<Reply>();
+    generator.subschema_for::<Replies>();
+    generator.subschema_for::<Config>();
+    generator
+}
+
+// The JSON schemas of the bodies of the HTTP API, and of the configuration of the plugin
+pub(crate) fn schemas() -> Value {
+    let generator = schema_generator(SchemaSettings::draft07());
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "definitions": generator.definitions(),
+    })
+}
+
+fn parameter(name: &str, location: &str, description: &str, schema: Value) -> Value {
+    json!({
+        "name": name,
+        "in": location,
+        "description": description,
+        "required": location == "path",
+        "schema": schema,
+    })
+}
+
+// A query parameter without value
+fn flag(name: &str, description: &str) -> Value {
+    json!({
+        "name": name,
+        "in": "query",
+        "description": description,
+        "allowEmptyValue": true,
+        "schema": { "type": "boolean" },
+    })
+}
+
+fn enumeration(values: &[&str]) -> Value {
+    json!({ "type": "string", "enum": values })
+}
+
+fn error_responses(conf: &Config) -> Value {
+    let mut responses = json!({
+        "400": { "description": "Invalid key expression, selector or option.", "content": { "text/plain": { "schema": { "type": "string" } } } },
+        "500": { "description": "The zenoh operation failed.", "content": { "text/plain": { "schema": { "type": "string" } } } },
+    });
+    if !conf.users.is_empty() {
+        responses["401"] = json!({ "description": "Missing or invalid credentials." });
+        responses["403"] = json!({ "description": "The user is not allowed to perform the operation on the key expression." });
+    }
+    responses
+}
+
+fn common_options() -> Vec<Value> {
+    vec![
+        parameter(
+            options::PRIORITY.0,
+            "query",
+            "The priority of the zenoh messages, by name or from 1 (highest) to 7 (lowest).",
+            json!({ "type": "string", "example": "data" }),
+        ),
+        parameter(
+            options::PRIORITY.1,
+            "header",
+            "Same as the `_priority` parameter.",
+            json!({ "type": "string" }),
+        ),
+        parameter(
+            options::CONGESTION_CONTROL.0,
+            "query",
+            "The congestion control of the zenoh messages.",
+            enumeration(&["drop", "block"]),
+        ),
+        parameter(
+            options::CONGESTION_CONTROL.1,
+            "header",
+            "Same as the `_congestion_control` parameter.",
+            enumeration(&["drop", "block"]),
+        ),
+    ]
+}
+
+fn query_operation(conf: &Config, summary: &str, with_body: bool) -> Value {
+    let mut parameters = vec![
+        parameter(
+            "_time",
+            "query",
+            "Only the values stored within this time range, e.g. `[now(-2h)..now()]`. Disables the consolidation by default.",
+            json!({ "type": "string" }),
+        ),
+        flag(
+            "_raw",
+            "Return the payload of the first reply as is, with its encoding as content type, and its attachment as `x-zenoh-attachment-<name>` headers.",
+        ),
+        parameter(
+            "accept",
+            "header",
+            "The format of the replies: JSON (default), HTML, or a Server-Sent Events stream of the values published on the key expression. With `Upgrade: websocket`, the connection is upgraded to the WebSocket endpoint instead.",
+            enumeration(&["application/json", "text/html", "text/event-stream"]),
+        ),
+        parameter(
+            "last-event-id",
+            "header",
+            "With `Accept: text/event-stream`, resume the stream after the event with this id, if still in the history of the key expression.",
+            json!({ "type": "string" }),
+        ),
+        parameter(
+            options::TARGET.0,
+            "query",
+            "The queryables the query is routed to.",
+            enumeration(&["best_matching", "all", "all_complete"]),
+        ),
+        parameter(
+            options::TARGET.1,
+            "header",
+            "Same as the `_target` parameter.",
+            enumeration(&["best_matching", "all", "all_complete"]),
+        ),
+        parameter(
+            options::CONSOLIDATION.0,
+            "query",
+            "The consolidation of the replies.",
+            enumeration(&["auto", "none", "monotonic", "latest"]),
+        ),
+        parameter(
+            options::CONSOLIDATION.1,
+            "header",
+            "Same as the `_consolidation` parameter.",
+            enumeration(&["auto", "none", "monotonic", "latest"]),
+        ),
+        parameter(
+            options::TIMEOUT.0,
+            "query",
+            "The timeout of the query, in milliseconds.",
+            json!({ "type": "integer", "minimum": 0 }),
+        ),
+        parameter(
+            options::TIMEOUT.1,
+            "header",
+            "Same as the `_timeout` parameter.",
+            json!({ "type": "integer", "minimum": 0 }),
+        ),
+    ];
+    parameters.extend(common_options());
+    let mut responses = error_responses(conf);
+    responses["200"] = json!({
+        "description": "The replies to the query, or the stream of the values published on the key expression: events named after the kind of the sample (PUT or DELETE), with a `Sample` as data and `<history id>-<sequence number>` as id. A `heartbeat` event is sent on idle streams, and a `lagged` event with the number of `missed` events when the client fell behind the history of the key expression.",
+        "content": {
+            "application/json": { "schema": { "$ref": "#/components/schemas/Replies" } },
+            "text/html": { "schema": { "type": "string" } },
+            "text/event-stream": { "schema": { "type": "string" } },
+        },
+    });
+    responses["101"] = json!({ "description": "Upgraded to the WebSocket endpoint." });
+    let mut operation = json!({
+        "summary": summary,
+        "description": "Any other parameter is forwarded to the queryables with the selector. The `x-zenoh-attachment-<name>` headers make up the attachment of the query.",
+        "parameters": parameters,
+        "responses": responses,
+    });
+    if with_body {
+        operation["requestBody"] = json!({
+            "description": "The value of the query, with its content type as encoding.",
+            "required": false,
+            "content": { "*/*": { "schema": {} } },
+        });
+    }
+    operation
+}
+
+fn write_operation(conf: &Config, summary: &str, with_body: bool) -> Value {
+    let mut parameters = common_options();
+    parameters.push(parameter(
+        options::EXPRESS.0,
+        "query",
+        "Do not batch the zenoh message.",
+        json!({ "type": "boolean" }),
+    ));
+    parameters.push(parameter(
+        options::EXPRESS.1,
+        "header",
+        "Same as the `_express` parameter.",
+        json!({ "type": "boolean" }),
+    ));
+    let mut responses = error_responses(conf);
+    responses["200"] = json!({ "description": "The value was published." });
+    let mut operation = json!({
+        "summary": summary,
+        "description": "The `x-zenoh-attachment-<name>` headers make up the attachment of the publication.",
+        "parameters": parameters,
+        "responses": responses,
+    });
+    if with_body {
+        operation["requestBody"] = json!({
+            "description": "The value to publish, with its content type as encoding.",
+            "required": true,
+            "content": { "*/*": { "schema": {} } },
+        });
+    }
+    operation
+}
+
+fn path_item(conf: &Config, key_expr_description: &str) -> Value {
+    json!({
+        "parameters": [parameter("key_expr", "path", key_expr_description, json!({ "type": "string" }))],
+        "get": query_operation(conf, "Query the key expression, or subscribe to it", false),
+        "post": query_operation(conf, "Query the key expression with a value", true),
+        "put": write_operation(conf, "Put a value on the key expression", true),
+        "patch": write_operation(conf, "Put a value on the key expression", true),
+        "delete": write_operation(conf, "Delete the key expression", false),
+    })
+}
+
+// The OpenAPI document of the HTTP API served with the configuration `conf`
+pub(crate) fn document(conf: &Config, version: &str) -> Value {
+    let mut generator = schema_generator(SchemaSettings::openapi3());
+    let mut document = json!({
+        "openapi": OPENAPI_VERSION,
+        "info": {
+            "title": "Zenoh REST API",
+            "description": "Maps the HTTP methods on the paths to the zenoh operations on the key expressions: GET and POST query the key expression, PUT and PATCH put a value on it, DELETE deletes it.",
+            "version": version,
+        },
+        "paths": {
+            "/{key_expr}": path_item(conf, "The key expression, made of the whole path (including its `/` separators)."),
+            "/@/router/local/{key_expr}": path_item(conf, "The key expression in the admin space of the router serving the requests, made of the whole path after `@/router/local/` (alias of `@/router/<zid>/`)."),
+        },
+        "components": {
+            "schemas": generator.take_definitions(),
+        },
+    });
+    if !conf.users.is_empty() {
+        document["components"]["securitySchemes"] = json!({
+            "basic": { "type": "http", "scheme": "basic" },
+            "bearer": { "type": "http", "scheme": "bearer" },
+        });
+        document["security"] = json!([{ "basic": [] }, { "bearer": [] }]);
+    }
+    document
+}
+
+#[cfg(test)]
+mod tests {
+    use super::document;
+    use crate::config::Config;
+
+    #[test]
+    fn test_document() {
+        let config = serde_json::from_str::<Config>(r#"{"http_port": 8080}"#).unwrap();
+        let document = document(&config, "v0");
+        assert_eq!(document["info"]["version"], "v0");
+        for path in ["/{key_expr}", "/@/router/local/{key_expr}"] {
+            for method in ["get", "post", "put", "patch", "delete"] {
+                assert!(document["paths"][path][method].is_object());
+            }
+        }
+        assert!(document["paths"]["/{key_expr}"]["get"]["responses"]["401"].is_null());
+        assert_eq!(
+            document["paths"]["/{key_expr}"]["get"]["responses"]["200"]["content"]
+                ["application/json"]["schema"]["$ref"],
+            "#/components/schemas/Replies"
+        );
+        let schemas = &document["components"]["schemas"];
+        for schema in ["Sample", "Reply", "Replies", "Config"] {
+            assert!(schemas[schema].is_object());
+        }
+        assert!(document["security"].is_null());
+        assert!(super::schemas()["definitions"]["Replies"]["items"]["$ref"]
+            .as_str()
+            .unwrap()
+            .starts_with("#/definitions/"));
+
+        // the security schemes are described when users are configured
+        let config = serde_json::from_str::<Config>(
+            r#"{"http_port": 8080, "users": [{"name": "alice", "token": "abcd"}]}"#,
+        )
+        .unwrap();
+        let document = super::document(&config, "v0");
+        assert!(document["components"]["securitySchemes"]["bearer"].is_object());
+        assert!(document["paths"]["/{key_expr}"]["put"]["responses"]["403"].is_object());
+    }
+}
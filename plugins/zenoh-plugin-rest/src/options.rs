@@ -0,0 +1,236 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! The options of the zenoh operations performed for an HTTP request.
+//!
+//! Each option is given either by a header or by a reserved parameter of the URL, the parameter
+//! taking precedence over the header. The reserved parameters are not forwarded to the queryables.
+//!
+//! | parameter             | header                       | values                                                  |
+//! |-----------------------|------------------------------|---------------------------------------------------------|
+//! | `_target`             | `X-Zenoh-Target`             | `best_matching`, `all`, `all_complete`                  |
+//! | `_consolidation`      | `X-Zenoh-Consolidation`      | `auto`, `none`, `monotonic`, `latest`                   |
+//! | `_timeout`            | `X-Zenoh-Timeout`            | a duration in milliseconds                              |
+//! | `_priority`           | `X-Zenoh-Priority`           | `real_time`, `interactive_high`, `interactive_low`, `data_high`, `data`, `data_low`, `background`, or 1 to 7 |
+//! | `_congestion_control` | `X-Zenoh-Congestion-Control` | `drop`, `block`                                         |
+//! | `_express`            | `X-Zenoh-Express`            | `true`, `false` (for `PUT`, `PATCH` and `DELETE` only)  |
+//!
+//! The `X-Zenoh-Attachment-<name>: <value>` headers make up the attachment of the operation,
+//! with the lowercase `<name>` as key.
+use std::time::Duration;
+use tide::Request;
+use zenoh::prelude::r#async::*;
+use zenoh::publication::PutBuilder;
+use zenoh::query::{GetBuilder, QueryConsolidation, QueryTarget};
+use zenoh::sample::{Attachment, AttachmentBuilder};
+use zenoh_result::{bail, zerror, ZResult};
+
+pub(crate) const TARGET: (&str, &str) = ("_target", "x-zenoh-target");
+pub(crate) const CONSOLIDATION: (&str, &str) = ("_consolidation", "x-zenoh-consolidation");
+pub(crate) const TIMEOUT: (&str, &str) = ("_timeout", "x-zenoh-timeout");
+pub(crate) const PRIORITY: (&str, &str) = ("_priority", "x-zenoh-priority");
+pub(crate) const CONGESTION_CONTROL: (&str, &str) =
+    ("_congestion_control", "x-zenoh-congestion-control");
+pub(crate) const EXPRESS: (&str, &str) = ("_express", "x-zenoh-express");
+const RESERVED_PARAMETERS: [&str; 6] = [
+    TARGET.0,
+    CONSOLIDATION.0,
+    TIMEOUT.0,
+    PRIORITY.0,
+    CONGESTION_CONTROL.0,
+    EXPRESS.0,
+];
+pub(crate) const ATTACHMENT_HEADER_PREFIX: &str = "x-zenoh-attachment-";
+
+#[derive(Default, Debug)]
+pub(crate) struct Options {
+    target: Option<QueryTarget>,
+    consolidation: Option<QueryConsolidation>,
+    timeout: Option<Duration>,
+    priority: Option<Priority>,
+    congestion_control: Option<CongestionControl>,
+    express: Option<bool>,
+    attachment: Option<Attachment>,
+}
+
+impl Options {
+    pub(crate) fn from_request<State>(req: &Request<State>) -> ZResult<Self> {
+        let get = |(parameter, header): (&str, &str)| -> Option<String> {
+            req.url()
+                .query_pairs()
+                .find(|(k, _)| k == parameter)
+                .map(|(_, v)| v.into_owned())
+                .or_else(|| req.header(header).map(|v| v.last().as_str().to_string()))
+        };
+        let mut options = Options::default();
+        if let Some(target) = get(TARGET) {
+            options.target = Some(match target.as_str() {
+                "best_matching" => QueryTarget::BestMatching,
+                "all" => QueryTarget::All,
+                "all_complete" => QueryTarget::AllComplete,
+                _ => bail!("Invalid query target `{}`", target),
+            });
+        }
+        if let Some(consolidation) = get(CONSOLIDATION) {
+            options.consolidation = Some(match consolidation.as_str() {
+                "auto" => QueryConsolidation::AUTO,
+                "none" => ConsolidationMode::None.into(),
+                "monotonic" => ConsolidationMode::Monotonic.into(),
+                "latest" => ConsolidationMode::Latest.into(),
+                _ => bail!("Invalid query consolidation `{}`", consolidation),
+            });
+        }
+        if let Some(timeout) = get(TIMEOUT) {
+            let millis = timeout
+                .parse::<u64>()
+                .map_err(|_| zerror!("Invalid timeout `{}`", timeout))?;
+            options.timeout = Some(Duration::from_millis(millis));
+        }
+        if let Some(priority) = get(PRIORITY) {
+            options.priority = Some(parse_priority(&priority)?);
+        }
+        if let Some(congestion_control) = get(CONGESTION_CONTROL) {
+            options.congestion_control = Some(match congestion_control.as_str() {
+                "drop" => CongestionControl::Drop,
+                "block" => CongestionControl::Block,
+                _ => bail!("Invalid congestion control `{}`", congestion_control),
+            });
+        }
+        if let Some(express) = get(EXPRESS) {
+            options.express = Some(
+                express
+                    .parse::<bool>()
+                    .map_err(|_| zerror!("Invalid express `{}`", express))?,
+            );
+        }
+        let mut attachment = AttachmentBuilder::new();
+        let mut has_attachment = false;
+        for (name, values) in req.iter() {
+            if let Some(key) = name.as_str().strip_prefix(ATTACHMENT_HEADER_PREFIX) {
+                attachment.insert(key, values.last().as_str());
+                has_attachment = true;
+            }
+        }
+        if has_attachment {
+            options.attachment = Some(attachment.build());
+        }
+        Ok(options)
+    }
+
+    // The query consolidation, defaulting to `default`
+    pub(crate) fn consolidation(&self, default: QueryConsolidation) -> QueryConsolidation {
+        self.consolidation.unwrap_or(default)
+    }
+
+    pub(crate) fn apply_to_get<'a, 'b, Handler>(
+        &self,
+        mut get: GetBuilder<'a, 'b, Handler>,
+    ) -> ZResult<GetBuilder<'a, 'b, Handler>> {
+        if self.express.is_some() {
+            bail!("The express option only applies to PUT, PATCH and DELETE requests")
+        }
+        if let Some(target) = self.target {
+            get = get.target(target);
+        }
+        if let Some(timeout) = self.timeout {
+            get = get.timeout(timeout);
+        }
+        if let Some(priority) = self.priority {
+            get = get.priority(priority);
+        }
+        if let Some(congestion_control) = self.congestion_control {
+            get = get.congestion_control(congestion_control);
+        }
+        if let Some(attachment) = &self.attachment {
+            get = get.with_attachment(attachment.clone());
+        }
+        Ok(get)
+    }
+
+    pub(crate) fn apply_to_put<'a, 'b>(
+        &self,
+        mut put: PutBuilder<'a, 'b>,
+    ) -> ZResult<PutBuilder<'a, 'b>> {
+        if self.target.is_some() || self.consolidation.is_some() || self.timeout.is_some() {
+            bail!(
+                "The target, consolidation and timeout options only apply to GET and POST requests"
+            )
+        }
+        if let Some(priority) = self.priority {
+            put = put.priority(priority);
+        }
+        if let Some(congestion_control) = self.congestion_control {
+            put = put.congestion_control(congestion_control);
+        }
+        if let Some(express) = self.express {
+            put = put.express(express);
+        }
+        if let Some(attachment) = &self.attachment {
+            put = put.with_attachment(attachment.clone());
+        }
+        Ok(put)
+    }
+}
+
+// Removes the reserved parameters from the parameters of a selector
+pub(crate) fn strip_reserved_parameters(parameters: &str) -> String {
+    parameters
+        .split('&')
+        .filter(|p| {
+            let name = p.split_once('=').map_or(*p, |(name, _)| name);
+            !name.is_empty() && !RESERVED_PARAMETERS.contains(&name)
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn parse_priority(priority: &str) -> ZResult<Priority> {
+    Ok(match priority {
+        "real_time" => Priority::RealTime,
+        "interactive_high" => Priority::InteractiveHigh,
+        "interactive_low" => Priority::InteractiveLow,
+        "data_high" => Priority::DataHigh,
+        "data" => Priority::Data,
+        "data_low" => Priority::DataLow,
+        "background" => Priority::Background,
+        _ => match priority.parse::<u8>() {
+            Ok(p) => Priority::try_from(p)?,
+            Err(_) => bail!("Invalid priority `{}`", priority),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_priority, strip_reserved_parameters};
+    use zenoh::prelude::Priority;
+
+    #[test]
+    fn test_strip_reserved_parameters() {
+        assert_eq!(
+            strip_reserved_parameters("_target=all&x=1&_raw&_timeout=100&_time=[..]"),
+            "x=1&_raw&_time=[..]"
+        );
+        assert_eq!(strip_reserved_parameters("_express=true"), "");
+        assert_eq!(strip_reserved_parameters("a=1&&b"), "a=1&b");
+    }
+
+    #[test]
+    fn test_parse_priority() {
+        assert_eq!(parse_priority("real_time").unwrap(), Priority::RealTime);
+        assert_eq!(parse_priority("6").unwrap(), Priority::DataLow);
+        assert!(parse_priority("0").is_err());
+        assert!(parse_priority("urgent").is_err());
+    }
+}
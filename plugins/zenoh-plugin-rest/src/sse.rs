@@ -0,0 +1,311 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! The Server-Sent Events streams of the REST plugin.
+//!
+//! The streams on a key expression share a subscriber, which appends the samples to a bounded
+//! history. Each stream sends the events of the history at its own pace: a stream falling behind
+//! the history skips the dropped events, notified by a `lagged` event, rather than being closed.
+//!
+//! The events are named after the kind of the sample, and their id is `<history id>-<sequence number>`,
+//! so that a client reconnecting with a `Last-Event-ID` header resumes after the last event it
+//! received. A `heartbeat` event is sent on the idle streams. The history is dropped, and the
+//! subscriber undeclared, when no stream used it for the configured linger time.
+use async_std::prelude::FutureExt;
+use async_std::sync::Mutex as AsyncMutex;
+use flume::TrySendError;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tide::sse::Sender;
+use tide::{Request, Response, StatusCode};
+use zenoh::prelude::r#async::*;
+use zenoh::subscriber::Subscriber;
+use zenoh::time::new_reception_timestamp;
+use zenoh::Session;
+use zenoh_result::ZResult;
+
+use crate::config::SseConfig;
+
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+const LAGGED_EVENT: &str = "lagged";
+const HEARTBEAT_EVENT: &str = "heartbeat";
+
+// The latest events of a key expression, numbered by increasing sequence numbers
+struct Events<T> {
+    buffer: VecDeque<(u64, T)>,
+    next_seq: u64,
+    capacity: usize,
+    // the streams waiting for a new event
+    listeners: Vec<flume::Sender<()>>,
+}
+
+impl<T: Clone> Events<T> {
+    fn new(capacity: usize) -> Self {
+        Events {
+            buffer: VecDeque::new(),
+            next_seq: 0,
+            capacity: capacity.max(1),
+            listeners: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, event: T) {
+        while self.buffer.len() >= self.capacity {
+            self.buffer.pop_front();
+        }
+        self.buffer.push_back((self.next_seq, event));
+        self.next_seq += 1;
+        self.listeners
+            .retain(|l| !matches!(l.try_send(()), Err(TrySendError::Disconnected(_))));
+    }
+
+    fn first_seq(&self) -> u64 {
+        self.buffer.front().map_or(self.next_seq, |(seq, _)| *seq)
+    }
+
+    // The sequence number a stream starts from, given the id of the last event its client received
+    fn start(&self, history_id: &str, last_event_id: Option<&str>) -> u64 {
+        match last_event_id.and_then(|id| id.rsplit_once('-')) {
+            Some((id, seq)) if id == history_id => seq
+                .parse::<u64>()
+                .map_or(self.next_seq, |seq| (seq + 1).min(self.next_seq)),
+            // the events were received from a previous history: replay the whole current one
+            Some(_) => self.first_seq(),
+            None => self.next_seq,
+        }
+    }
+
+    // The number of events from `from` that were dropped from the buffer, and the events after them
+    fn since(&self, from: u64) -> (u64, Vec<(u64, T)>) {
+        let lagged = self.first_seq().saturating_sub(from);
+        let events = self
+            .buffer
+            .iter()
+            .filter(|(seq, _)| *seq >= from)
+            .cloned()
+            .collect();
+        (lagged, events)
+    }
+}
+
+struct History {
+    id: String,
+    events: Arc<Mutex<Events<Sample>>>,
+    _subscriber: Subscriber<'static, ()>,
+}
+
+// The histories of the key expressions with Server-Sent Events streams, attached to every request
+pub(crate) struct SseStreams {
+    session: Arc<Session>,
+    config: SseConfig,
+    histories: AsyncMutex<HashMap<OwnedKeyExpr, Arc<History>>>,
+}
+
+impl SseStreams {
+    pub(crate) fn new(session: Arc<Session>, config: SseConfig) -> Self {
+        SseStreams {
+            session,
+            config,
+            histories: AsyncMutex::new(HashMap::new()),
+        }
+    }
+
+    async fn history(&self, key_expr: &OwnedKeyExpr) -> ZResult<Arc<History>> {
+        let mut histories = self.histories.lock().await;
+        if let Some(history) = histories.get(key_expr) {
+            return Ok(history.clone());
+        }
+        log::debug!("Subscribe to {} for SSE streams", key_expr);
+        let events = Arc::new(Mutex::new(Events::new(self.config.history)));
+        let subscriber = self
+            .session
+            .declare_subscriber(key_expr.clone())
+            .callback({
+                let events = events.clone();
+                move |mut sample| {
+                    if sample.timestamp.is_none() {
+                        sample = sample.with_timestamp(new_reception_timestamp());
+                    }
+                    if let Ok(mut events) = events.lock() {
+                        events.push(sample);
+                    }
+                }
+            })
+            .res()
+            .await?;
+        let id = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let history = Arc::new(History {
+            id: format!("{id:x}"),
+            events,
+            _subscriber: subscriber,
+        });
+        histories.insert(key_expr.clone(), history.clone());
+        Ok(history)
+    }
+
+    // Drops the history of `key_expr` if no stream uses it after the linger time
+    fn release(self: Arc<Self>, key_expr: OwnedKeyExpr) {
+        async_std::task::spawn(async move {
+            async_std::task::sleep(Duration::from_secs(self.config.linger)).await;
+            let mut histories = self.histories.lock().await;
+            if matches!(histories.get(&key_expr), Some(history) if Arc::strong_count(history) == 1)
+            {
+                log::debug!("Unsubscribe from {} for SSE streams", key_expr);
+                histories.remove(&key_expr);
+            }
+        });
+    }
+
+    async fn serve(&self, history: &History, sender: Sender, last_event_id: Option<String>) {
+        let heartbeat_interval = Duration::from_secs(self.config.heartbeat_interval.max(1));
+        let (notify_tx, notify_rx) = flume::bounded(1);
+        let mut next = match history.events.lock() {
+            Ok(mut events) => {
+                events.listeners.push(notify_tx);
+                events.start(&history.id, last_event_id.as_deref())
+            }
+            Err(_) => return,
+        };
+        loop {
+            let (lagged, events) = match history.events.lock() {
+                Ok(events) => events.since(next),
+                Err(_) => return,
+            };
+            if lagged > 0 {
+                let data = serde_json::json!({ "missed": lagged }).to_string();
+                if let Err(e) = sender.send(LAGGED_EVENT, data, None).await {
+                    log::debug!("SSE error ({}), close stream", e);
+                    return;
+                }
+                next += lagged;
+            }
+            for (seq, sample) in events {
+                let id = format!("{}-{}", history.id, seq);
+                let kind = sample.kind.to_string();
+                if let Err(e) = sender
+                    .send(&kind, super::sample_to_json(sample), Some(&id))
+                    .await
+                {
+                    log::debug!("SSE error ({}), close stream", e);
+                    return;
+                }
+                next = seq + 1;
+            }
+            match notify_rx.recv_async().timeout(heartbeat_interval).await {
+                Ok(Ok(())) => {}
+                Ok(Err(_)) => return,
+                Err(_) => {
+                    let data = serde_json::json!({ "time": new_reception_timestamp().to_string() });
+                    if let Err(e) = sender.send(HEARTBEAT_EVENT, data.to_string(), None).await {
+                        log::debug!("SSE error ({}), close stream", e);
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Upgrades the request to a Server-Sent Events stream of the samples published on `key_expr`
+pub(crate) async fn upgrade<State: Clone + Send + Sync + 'static>(
+    req: Request<State>,
+    key_expr: OwnedKeyExpr,
+) -> Response {
+    let streams = match req.ext::<Arc<SseStreams>>() {
+        Some(streams) => streams.clone(),
+        None => {
+            return super::response(
+                StatusCode::InternalServerError,
+                "text/plain",
+                "SSE streams are not available",
+            )
+        }
+    };
+    let history = match streams.history(&key_expr).await {
+        Ok(history) => history,
+        Err(e) => {
+            return super::response(
+                StatusCode::InternalServerError,
+                "text/plain",
+                &e.to_string(),
+            )
+        }
+    };
+    let last_event_id = req
+        .header(LAST_EVENT_ID_HEADER)
+        .map(|v| v.last().as_str().to_string());
+    // the history must be released once the stream is closed, so it is taken out of the handler
+    let history = Mutex::new(Some(history));
+    tide::sse::upgrade(req, move |_req, sender| {
+        let history = history.lock().ok().and_then(|mut h| h.take());
+        let streams = streams.clone();
+        let key_expr = key_expr.clone();
+        let last_event_id = last_event_id.clone();
+        async move {
+            if let Some(history) = history {
+                streams.serve(&history, sender, last_event_id).await;
+                drop(history);
+                streams.release(key_expr);
+            }
+            Ok(())
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Events;
+
+    #[test]
+    fn test_events() {
+        let mut events = Events::new(3);
+        for i in 0..5 {
+            events.push(i);
+        }
+        // the 2 first events were dropped
+        assert_eq!(events.first_seq(), 2);
+        assert_eq!(events.since(0), (2, vec![(2, 2), (3, 3), (4, 4)]));
+        assert_eq!(events.since(3), (0, vec![(3, 3), (4, 4)]));
+        assert_eq!(events.since(5), (0, vec![]));
+
+        // a listener is notified once of the pending events, and removed once gone
+        let (tx, rx) = flume::bounded(1);
+        events.listeners.push(tx);
+        events.push(5);
+        events.push(6);
+        assert_eq!(rx.drain().count(), 1);
+        drop(rx);
+        events.push(7);
+        assert!(events.listeners.is_empty());
+    }
+
+    #[test]
+    fn test_start() {
+        let mut events = Events::new(10);
+        for i in 0..5 {
+            events.push(i);
+        }
+        assert_eq!(events.start("a1", None), 5);
+        assert_eq!(events.start("a1", Some("a1-2")), 3);
+        assert_eq!(events.start("a1", Some("a1-9")), 5);
+        assert_eq!(events.start("a1", Some("a1-x")), 5);
+        // an id from another history replays the whole history
+        assert_eq!(events.start("a1", Some("b2-2")), 0);
+        assert_eq!(events.start("a1", Some("garbage")), 5);
+    }
+}
@@ -0,0 +1,148 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! The HTTPS server of the REST plugin.
+use async_std::io;
+use async_std::net::{TcpListener, TcpStream};
+use futures::{AsyncRead, AsyncWrite};
+use futures_rustls::server::TlsStream;
+use futures_rustls::TlsAcceptor;
+use rustls::ServerConfig;
+use rustls_pki_types::{CertificateDer, PrivateKeyDer};
+use std::io::Cursor;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use tide::Server;
+use zenoh_result::{bail, zerror, ZResult};
+
+use crate::config::TlsConfig;
+
+pub(crate) fn load_server_config(config: &TlsConfig) -> ZResult<Arc<ServerConfig>> {
+    let certificate = std::fs::read(&config.server_certificate).map_err(|e| {
+        zerror!(
+            "Error reading server certificate `{}`: {}",
+            config.server_certificate,
+            e
+        )
+    })?;
+    let private_key = std::fs::read(&config.server_private_key).map_err(|e| {
+        zerror!(
+            "Error reading server private key `{}`: {}",
+            config.server_private_key,
+            e
+        )
+    })?;
+
+    let certs: Vec<CertificateDer> = rustls_pemfile::certs(&mut Cursor::new(&certificate))
+        .collect::<Result<_, _>>()
+        .map_err(|err| zerror!("Error processing server certificate: {err}."))?;
+    if certs.is_empty() {
+        bail!("No certificate found for HTTPS server.");
+    }
+    let key: PrivateKeyDer = match rustls_pemfile::private_key(&mut Cursor::new(&private_key))
+        .map_err(|err| zerror!("Error processing server key: {err}."))?
+    {
+        Some(key) => key,
+        None => bail!("No private key found for HTTPS server."),
+    };
+
+    let config = ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| zerror!(e))?;
+    Ok(Arc::new(config))
+}
+
+// Serves the requests of the HTTPS connections accepted on `addr`
+pub(crate) async fn listen<State: Clone + Send + Sync + 'static>(
+    app: Server<State>,
+    addr: &str,
+    config: Arc<ServerConfig>,
+) -> io::Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    let acceptor = TlsAcceptor::from(config);
+    log::info!(
+        "REST server listening on https://{}",
+        listener.local_addr()?
+    );
+    loop {
+        let (stream, peer_addr) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                log::warn!("Error accepting HTTPS connection: {}", e);
+                continue;
+            }
+        };
+        let app = app.clone();
+        let acceptor = acceptor.clone();
+        async_std::task::spawn(async move {
+            let local_addr = stream.local_addr().ok();
+            let stream = match acceptor.accept(stream).await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    log::debug!("TLS handshake with {} failed: {}", peer_addr, e);
+                    return;
+                }
+            };
+            let result = async_h1::accept(TlsConnection::new(stream), |mut req| async {
+                req.set_local_addr(local_addr);
+                req.set_peer_addr(Some(peer_addr));
+                app.respond(req).await
+            })
+            .await;
+            if let Err(e) = result {
+                log::debug!("HTTPS connection with {} failed: {}", peer_addr, e);
+            }
+        });
+    }
+}
+
+// The HTTP server requires a clonable connection, to be handed over on protocol upgrades
+#[derive(Clone)]
+struct TlsConnection(Arc<Mutex<TlsStream<TcpStream>>>);
+
+impl TlsConnection {
+    fn new(stream: TlsStream<TcpStream>) -> Self {
+        TlsConnection(Arc::new(Mutex::new(stream)))
+    }
+}
+
+impl AsyncRead for TlsConnection {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut *self.0.lock().unwrap()).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for TlsConnection {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut *self.0.lock().unwrap()).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut *self.0.lock().unwrap()).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut *self.0.lock().unwrap()).poll_close(cx)
+    }
+}
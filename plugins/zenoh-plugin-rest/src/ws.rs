@@ -0,0 +1,614 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! The WebSocket endpoint of the REST plugin.
+//!
+//! A WebSocket can be opened on any path. The messages are JSON objects with an `op` field, in text frames,
+//! or in binary frames made of the JSON object, a `0` byte and the raw payload of the value.
+//!
+//! Messages from the client:
+//! - `{"op": "subscribe", "id": <id>, "key_expr": <key_expr>}`
+//! - `{"op": "unsubscribe", "id": <id>}`
+//! - `{"op": "put", "key_expr": <key_expr>, "value": <value>, "encoding": <encoding>}`
+//! - `{"op": "delete", "key_expr": <key_expr>}`
+//! - `{"op": "get", "id": <id>, "selector": <selector>, "value": <value>, "encoding": <encoding>}`
+//! - `{"op": "declare_queryable", "id": <id>, "key_expr": <key_expr>, "complete": <bool>}`
+//! - `{"op": "undeclare_queryable", "id": <id>}`
+//! - `{"op": "reply", "query_id": <query_id>, "key_expr": <key_expr>, "value": <value>, "encoding": <encoding>}`
+//! - `{"op": "reply_error", "query_id": <query_id>, "value": <value>, "encoding": <encoding>}`
+//! - `{"op": "reply_final", "query_id": <query_id>}`
+//!
+//! Messages to the client:
+//! - `{"op": "sample", "id": <subscription id>, "kind": <PUT|DELETE>, "key": <key>, "value": <value>, "encoding": <encoding>, "time": <timestamp>}`
+//! - `{"op": "reply", "id": <get id>, "key": <key>, "value": <value>, "encoding": <encoding>, "time": <timestamp>}`
+//!   (with `"key": "ERROR"` for an error reply)
+//! - `{"op": "reply_final", "id": <get id>}`
+//! - `{"op": "query", "id": <queryable id>, "query_id": <query_id>, "selector": <selector>, "value": <value>, "encoding": <encoding>}`
+//! - `{"op": "error", "id": <id>, "message": <message>}`
+//!
+//! A string `value` is sent as is, and any other JSON `value` is serialized (with `application/json` as default encoding).
+//! The values which have no JSON representation are sent in binary frames.
+//!
+//! When users are configured, the operations are checked against the permissions of the user authenticated
+//! by the handshake request: `subscribe` and `get` require `GET`, `put` requires `PUT`, `delete` requires `DELETE`,
+//! and `declare_queryable` requires `PUT` on its key expression.
+//!
+//! The handshake requests with an `Origin` header which is not in `cors_allowed_origins` are rejected.
+//! The samples and queries are dropped when the client doesn't read the messages fast enough.
+//! The queries not finalized by the client are dropped after the `queries_default_timeout` of the configuration.
+use async_std::sync::Arc;
+use async_tungstenite::tungstenite::protocol::Role;
+use async_tungstenite::tungstenite::Message;
+use async_tungstenite::WebSocketStream;
+use base64::{engine::general_purpose::STANDARD as b64_std_engine, Engine};
+use flume::TrySendError;
+use futures::{SinkExt, StreamExt};
+use http_types::Method;
+use serde::Deserialize;
+use serde_json::json;
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tide::security::Origin;
+use tide::{Request, Response, StatusCode};
+use zenoh::prelude::r#async::*;
+use zenoh::query::{QueryConsolidation, Reply};
+use zenoh::queryable::{Query, Queryable};
+use zenoh::selector::TIME_RANGE_KEY;
+use zenoh::subscriber::Subscriber;
+use zenoh::Session;
+use zenoh_result::{bail, ZResult};
+
+use super::auth::{is_allowed, User};
+use super::{json_repr, path_to_key_expr};
+
+// The GUID appended to the key of the client to accept the WebSocket handshake (RFC 6455)
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+// The maximum number of messages waiting to be sent to the client of a WebSocket
+const WEBSOCKET_CHANNEL_CAPACITY: usize = 256;
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum ClientMessage {
+    Subscribe {
+        id: u64,
+        key_expr: String,
+    },
+    Unsubscribe {
+        id: u64,
+    },
+    Put {
+        key_expr: String,
+        value: Option<serde_json::Value>,
+        encoding: Option<String>,
+    },
+    Delete {
+        key_expr: String,
+    },
+    Get {
+        id: u64,
+        selector: String,
+        value: Option<serde_json::Value>,
+        encoding: Option<String>,
+    },
+    DeclareQueryable {
+        id: u64,
+        key_expr: String,
+        #[serde(default)]
+        complete: bool,
+    },
+    UndeclareQueryable {
+        id: u64,
+    },
+    Reply {
+        query_id: u64,
+        key_expr: String,
+        value: Option<serde_json::Value>,
+        encoding: Option<String>,
+    },
+    ReplyError {
+        query_id: u64,
+        value: Option<serde_json::Value>,
+        encoding: Option<String>,
+    },
+    ReplyFinal {
+        query_id: u64,
+    },
+}
+
+impl ClientMessage {
+    fn id(&self) -> Option<u64> {
+        match self {
+            ClientMessage::Subscribe { id, .. }
+            | ClientMessage::Unsubscribe { id }
+            | ClientMessage::Get { id, .. }
+            | ClientMessage::DeclareQueryable { id, .. }
+            | ClientMessage::UndeclareQueryable { id } => Some(*id),
+            ClientMessage::Reply { query_id, .. }
+            | ClientMessage::ReplyError { query_id, .. }
+            | ClientMessage::ReplyFinal { query_id } => Some(*query_id),
+            ClientMessage::Put { .. } | ClientMessage::Delete { .. } => None,
+        }
+    }
+}
+
+pub(crate) fn is_upgrade<State>(req: &Request<State>) -> bool {
+    req.header("upgrade").map_or(false, |upgrade| {
+        upgrade.as_str().eq_ignore_ascii_case("websocket")
+    })
+}
+
+// Whether a handshake request from `origin` is allowed by the configured CORS origins
+fn is_allowed_origin(allowed_origins: &Origin, origin: &str) -> bool {
+    match allowed_origins {
+        Origin::Any => true,
+        Origin::Exact(allowed) => allowed == origin,
+        Origin::List(allowed) => allowed.iter().any(|allowed| allowed == origin),
+    }
+}
+
+// Accepts the WebSocket handshake, and serves the WebSocket once the connection is upgraded
+pub(crate) async fn upgrade(req: Request<(Arc<Session>, String)>) -> tide::Result<Response> {
+    // the CORS middleware doesn't apply to WebSockets: browsers open them from any origin
+    if let (Some(origin), Some(allowed_origins)) = (req.header("origin"), req.ext::<Origin>()) {
+        if !is_allowed_origin(allowed_origins, origin.as_str()) {
+            return Ok(super::response(
+                StatusCode::Forbidden,
+                "text/plain",
+                &format!("Origin '{}' is not allowed", origin.as_str()),
+            ));
+        }
+    }
+    let key = match req.header("sec-websocket-key") {
+        Some(key) => key.as_str().to_string(),
+        None => {
+            return Ok(super::response(
+                StatusCode::BadRequest,
+                "text/plain",
+                "Missing Sec-WebSocket-Key header",
+            ))
+        }
+    };
+    let accept = b64_std_engine.encode(
+        Sha1::new()
+            .chain_update(key.as_bytes())
+            .chain_update(WEBSOCKET_GUID.as_bytes())
+            .finalize(),
+    );
+    let mut response = Response::new(StatusCode::SwitchingProtocols);
+    response.insert_header("upgrade", "websocket");
+    response.insert_header("connection", "Upgrade");
+    response.insert_header("sec-websocket-accept", accept);
+    let http_response: &mut http_types::Response = response.as_mut();
+    let upgrade_receiver = http_response.recv_upgrade().await;
+    let (session, zid) = req.state().clone();
+    let user = req.ext::<Arc<User>>().cloned();
+    async_std::task::spawn(async move {
+        if let Some(connection) = upgrade_receiver.await {
+            let stream = WebSocketStream::from_raw_socket(connection, Role::Server, None).await;
+            serve(stream, session, zid, user).await;
+        }
+    });
+    Ok(response)
+}
+
+// The declarations and the pending queries of a WebSocket
+struct WsState {
+    session: Arc<Session>,
+    zid: String,
+    user: Option<Arc<User>>,
+    tx: flume::Sender<Message>,
+    subscribers: HashMap<u64, Subscriber<'static, ()>>,
+    queryables: HashMap<u64, Queryable<'static, ()>>,
+    queries: Arc<Mutex<HashMap<u64, Query>>>,
+    query_counter: Arc<AtomicU64>,
+    // the delay after which the pending queries are dropped
+    query_timeout: Duration,
+}
+
+async fn serve<S>(
+    stream: WebSocketStream<S>,
+    session: Arc<Session>,
+    zid: String,
+    user: Option<Arc<User>>,
+) where
+    S: futures::AsyncRead + futures::AsyncWrite + Unpin + Send + 'static,
+{
+    log::debug!(
+        "WebSocket opened (task {})",
+        async_std::task::current().id()
+    );
+    let (mut sink, mut stream) = stream.split();
+    let (tx, rx) = flume::bounded::<Message>(WEBSOCKET_CHANNEL_CAPACITY);
+    let writer = async_std::task::spawn(async move {
+        while let Ok(message) = rx.recv_async().await {
+            if let Err(e) = sink.send(message).await {
+                log::debug!("WebSocket error: {}", e);
+                break;
+            }
+        }
+    });
+    let query_timeout = Duration::from_millis(
+        session
+            .config()
+            .lock()
+            .queries_default_timeout()
+            .unwrap_or(config::defaults::queries_default_timeout),
+    );
+    let mut state = WsState {
+        session,
+        zid,
+        user,
+        tx,
+        subscribers: HashMap::new(),
+        queryables: HashMap::new(),
+        queries: Arc::new(Mutex::new(HashMap::new())),
+        query_counter: Arc::new(AtomicU64::new(0)),
+        query_timeout,
+    };
+    while let Some(message) = stream.next().await {
+        let (message, payload) = match message {
+            Ok(Message::Text(text)) => (text.into_bytes(), None),
+            Ok(Message::Binary(mut bytes)) => match bytes.iter().position(|b| *b == 0) {
+                Some(i) => {
+                    let payload = bytes.split_off(i + 1);
+                    bytes.pop();
+                    (bytes, Some(payload))
+                }
+                None => (bytes, None),
+            },
+            Ok(Message::Close(_)) => break,
+            Ok(_) => continue,
+            Err(e) => {
+                log::debug!("WebSocket error: {}", e);
+                break;
+            }
+        };
+        let message: ClientMessage = match serde_json::from_slice(&message) {
+            Ok(message) => message,
+            Err(e) => {
+                state
+                    .send_error(None, format!("Invalid message: {e}"))
+                    .await;
+                continue;
+            }
+        };
+        log::trace!("Incoming WebSocket message: {:?}", message);
+        let id = message.id();
+        if let Err(e) = state.handle(message, payload).await {
+            state.send_error(id, e.to_string()).await;
+        }
+    }
+    log::debug!(
+        "WebSocket closed (task {})",
+        async_std::task::current().id()
+    );
+    // undeclare the subscribers and queryables, and finalize the pending queries
+    drop(state);
+    writer.await;
+}
+
+impl WsState {
+    async fn handle(&mut self, message: ClientMessage, payload: Option<Vec<u8>>) -> ZResult<()> {
+        match message {
+            ClientMessage::Subscribe { id, key_expr } => {
+                let key_expr = path_to_key_expr(&key_expr, &self.zid)?.into_owned();
+                is_allowed(self.user.as_ref(), Method::Get, &key_expr)?;
+                let tx = self.tx.clone();
+                let subscriber = self
+                    .session
+                    .declare_subscriber(key_expr)
+                    .callback(move |sample: Sample| {
+                        let header = json!({
+                            "op": "sample",
+                            "id": id,
+                            "kind": sample.kind.to_string(),
+                            "key": sample.key_expr.as_str(),
+                            "encoding": sample.value.encoding.to_string(),
+                            "time": sample.timestamp.map(|ts| ts.to_string()),
+                        });
+                        if let Err(TrySendError::Full(_)) =
+                            tx.try_send(to_message(header, Some(&sample.value)))
+                        {
+                            log::warn!("WebSocket too slow: sample dropped (subscription {})", id);
+                        }
+                    })
+                    .res()
+                    .await?;
+                self.subscribers.insert(id, subscriber);
+            }
+            ClientMessage::Unsubscribe { id } => match self.subscribers.remove(&id) {
+                Some(subscriber) => subscriber.undeclare().res().await?,
+                None => bail!("Unknown subscription {}", id),
+            },
+            ClientMessage::Put {
+                key_expr,
+                value,
+                encoding,
+            } => {
+                let key_expr = path_to_key_expr(&key_expr, &self.zid)?;
+                is_allowed(self.user.as_ref(), Method::Put, &key_expr)?;
+                self.session
+                    .put(&key_expr, to_value(value, encoding, payload)?)
+                    .res()
+                    .await?;
+            }
+            ClientMessage::Delete { key_expr } => {
+                let key_expr = path_to_key_expr(&key_expr, &self.zid)?;
+                is_allowed(self.user.as_ref(), Method::Delete, &key_expr)?;
+                self.session.delete(&key_expr).res().await?;
+            }
+            ClientMessage::Get {
+                id,
+                selector,
+                value,
+                encoding,
+            } => {
+                let (key_expr, parameters) = match selector.split_once('?') {
+                    Some((key_expr, parameters)) => (key_expr, Some(parameters)),
+                    None => (selector.as_str(), None),
+                };
+                let key_expr = path_to_key_expr(key_expr, &self.zid)?;
+                is_allowed(self.user.as_ref(), Method::Get, &key_expr)?;
+                let selector = match parameters {
+                    Some(parameters) => Selector::from(key_expr).with_parameters(parameters),
+                    None => key_expr.into(),
+                };
+                let consolidation = if selector.decode().any(|(k, _)| k.as_ref() == TIME_RANGE_KEY)
+                {
+                    QueryConsolidation::from(zenoh::query::ConsolidationMode::None)
+                } else {
+                    QueryConsolidation::from(zenoh::query::ConsolidationMode::Latest)
+                };
+                let mut query = self.session.get(&selector).consolidation(consolidation);
+                if value.is_some() || payload.is_some() {
+                    query = query.with_value(to_value(value, encoding, payload)?);
+                }
+                let replies = query.res().await?;
+                let tx = self.tx.clone();
+                async_std::task::spawn(async move {
+                    while let Ok(reply) = replies.recv_async().await {
+                        let _ = tx.send_async(reply_to_message(id, reply)).await;
+                    }
+                    let _ = tx
+                        .send_async(Message::Text(
+                            json!({"op": "reply_final", "id": id}).to_string(),
+                        ))
+                        .await;
+                });
+            }
+            ClientMessage::DeclareQueryable {
+                id,
+                key_expr,
+                complete,
+            } => {
+                let key_expr = path_to_key_expr(&key_expr, &self.zid)?.into_owned();
+                is_allowed(self.user.as_ref(), Method::Put, &key_expr)?;
+                let tx = self.tx.clone();
+                let queries = self.queries.clone();
+                let query_counter = self.query_counter.clone();
+                let query_timeout = self.query_timeout;
+                let queryable = self
+                    .session
+                    .declare_queryable(key_expr)
+                    .complete(complete)
+                    .callback(move |query: Query| {
+                        let query_id = query_counter.fetch_add(1, Ordering::Relaxed);
+                        let mut header = json!({
+                            "op": "query",
+                            "id": id,
+                            "query_id": query_id,
+                            "selector": query.selector().to_string(),
+                        });
+                        if let Some(value) = query.value() {
+                            header["encoding"] = value.encoding.to_string().into();
+                        }
+                        let message = to_message(header, query.value());
+                        queries.lock().unwrap().insert(query_id, query);
+                        if let Err(TrySendError::Full(_)) = tx.try_send(message) {
+                            // the query is finalized once dropped
+                            queries.lock().unwrap().remove(&query_id);
+                            log::warn!("WebSocket too slow: query dropped (queryable {})", id);
+                            return;
+                        }
+                        // the query is dropped once timed out, unless the WebSocket is closed before
+                        let queries = Arc::downgrade(&queries);
+                        async_std::task::spawn(async move {
+                            async_std::task::sleep(query_timeout).await;
+                            if let Some(queries) = queries.upgrade() {
+                                if queries.lock().unwrap().remove(&query_id).is_some() {
+                                    log::debug!("WebSocket query {} timed out", query_id);
+                                }
+                            }
+                        });
+                    })
+                    .res()
+                    .await?;
+                self.queryables.insert(id, queryable);
+            }
+            ClientMessage::UndeclareQueryable { id } => match self.queryables.remove(&id) {
+                Some(queryable) => queryable.undeclare().res().await?,
+                None => bail!("Unknown queryable {}", id),
+            },
+            ClientMessage::Reply {
+                query_id,
+                key_expr,
+                value,
+                encoding,
+            } => {
+                let query = self.pending_query(query_id)?;
+                let key_expr = path_to_key_expr(&key_expr, &self.zid)?.into_owned();
+                let sample = Sample::new(key_expr, to_value(value, encoding, payload)?);
+                query.reply(Ok(sample)).res().await?;
+            }
+            ClientMessage::ReplyError {
+                query_id,
+                value,
+                encoding,
+            } => {
+                let query = self.pending_query(query_id)?;
+                query
+                    .reply(Err(to_value(value, encoding, payload)?))
+                    .res()
+                    .await?;
+            }
+            ClientMessage::ReplyFinal { query_id } => {
+                // the query is finalized once dropped
+                if self.queries.lock().unwrap().remove(&query_id).is_none() {
+                    bail!("Unknown query {}", query_id)
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn pending_query(&self, query_id: u64) -> ZResult<Query> {
+        match self.queries.lock().unwrap().get(&query_id) {
+            Some(query) => Ok(query.clone()),
+            None => bail!("Unknown query {}", query_id),
+        }
+    }
+
+    async fn send_error(&self, id: Option<u64>, message: String) {
+        log::debug!("WebSocket request failed: {}", message);
+        let _ = self
+            .tx
+            .send_async(Message::Text(
+                json!({"op": "error", "id": id, "message": message}).to_string(),
+            ))
+            .await;
+    }
+}
+
+// The value of a message from the client: its raw payload (if sent in a binary frame),
+// or its `value` field, as is for a string, serialized for any other JSON value
+fn to_value(
+    value: Option<serde_json::Value>,
+    encoding: Option<String>,
+    payload: Option<Vec<u8>>,
+) -> ZResult<Value> {
+    let (value, default_encoding) = match (payload, value) {
+        (Some(payload), _) => (Value::from(payload), KnownEncoding::AppOctetStream),
+        (None, Some(serde_json::Value::String(s))) => (Value::from(s), KnownEncoding::TextPlain),
+        (None, Some(json)) => (Value::from(json.to_string()), KnownEncoding::AppJson),
+        (None, None) => (Value::empty(), KnownEncoding::Empty),
+    };
+    Ok(match encoding {
+        Some(encoding) => value.encoding(encoding.parse()?),
+        None => value.encoding(default_encoding.into()),
+    })
+}
+
+// A message with the value in its `value` field if it has a JSON representation, else in a binary frame
+fn to_message(mut header: serde_json::Value, value: Option<&Value>) -> Message {
+    let value = match value {
+        Some(value) => value,
+        None => return Message::Text(header.to_string()),
+    };
+    match json_repr(value) {
+        Some(json) => {
+            header["value"] = json;
+            Message::Text(header.to_string())
+        }
+        None => {
+            let mut bytes = header.to_string().into_bytes();
+            bytes.push(0);
+            bytes.extend_from_slice(&value.payload.contiguous());
+            Message::Binary(bytes)
+        }
+    }
+}
+
+fn reply_to_message(id: u64, reply: Reply) -> Message {
+    match reply.sample {
+        Ok(sample) => {
+            let header = json!({
+                "op": "reply",
+                "id": id,
+                "key": sample.key_expr.as_str(),
+                "encoding": sample.value.encoding.to_string(),
+                "time": sample.timestamp.map(|ts| ts.to_string()),
+            });
+            to_message(header, Some(&sample.value))
+        }
+        Err(value) => {
+            let header = json!({
+                "op": "reply",
+                "id": id,
+                "key": "ERROR",
+                "encoding": value.encoding.to_string(),
+            });
+            to_message(header, Some(&value))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allowed_origins() {
+        assert!(is_allowed_origin(&Origin::Any, "https://example.com"));
+
+        let allowed = Origin::from(vec!["https://example.com".to_string()]);
+        assert!(is_allowed_origin(&allowed, "https://example.com"));
+        assert!(!is_allowed_origin(&allowed, "https://attacker.com"));
+
+        let allowed = Origin::from(vec![
+            "https://example.com".to_string(),
+            "https://example.org".to_string(),
+        ]);
+        assert!(is_allowed_origin(&allowed, "https://example.org"));
+        assert!(!is_allowed_origin(&allowed, "https://example.net"));
+    }
+
+    #[test]
+    fn pending_queries_timeout() {
+        async_std::task::block_on(async {
+            let mut config = zenoh::config::peer();
+            config.scouting.multicast.set_enabled(Some(false)).unwrap();
+            let session = Arc::new(zenoh::open(config).res().await.unwrap());
+            let (tx, rx) = flume::bounded(WEBSOCKET_CHANNEL_CAPACITY);
+            let mut state = WsState {
+                session: session.clone(),
+                zid: session.zid().to_string(),
+                user: None,
+                tx,
+                subscribers: HashMap::new(),
+                queryables: HashMap::new(),
+                queries: Arc::new(Mutex::new(HashMap::new())),
+                query_counter: Arc::new(AtomicU64::new(0)),
+                query_timeout: Duration::from_millis(200),
+            };
+            let queryable = ClientMessage::DeclareQueryable {
+                id: 0,
+                key_expr: "test/ws/queries".to_string(),
+                complete: true,
+            };
+            state.handle(queryable, None).await.unwrap();
+
+            // the query is pending until the client finalizes it, or until it times out
+            let replies = session.get("test/ws/queries").res().await.unwrap();
+            assert!(rx.recv_async().await.is_ok());
+            assert_eq!(state.queries.lock().unwrap().len(), 1);
+            async_std::task::sleep(Duration::from_millis(500)).await;
+            assert!(state.queries.lock().unwrap().is_empty());
+            // the dropped query is finalized, long before the timeout of the get
+            assert!(replies.recv_async().await.is_err());
+        });
+    }
+}
@@ -16,15 +16,16 @@ use flume::Sender;
 use std::sync::Arc;
 use zenoh::prelude::r#async::*;
 use zenoh::Session;
-use zenoh_backend_traits::config::StorageConfig;
-use zenoh_backend_traits::{Capability, VolumeInstance};
-use zenoh_result::ZResult;
+use zenoh_backend_traits::config::{ConflictResolution, StorageConfig};
+use zenoh_backend_traits::{Capability, MergeFunction, VolumeInstance};
+use zenoh_result::{bail, ZResult};
 
 pub struct StoreIntercept {
     pub storage: Box<dyn zenoh_backend_traits::Storage>,
     pub capability: Capability,
     pub in_interceptor: Option<Arc<dyn Fn(Sample) -> Sample + Send + Sync>>,
     pub out_interceptor: Option<Arc<dyn Fn(Sample) -> Sample + Send + Sync>>,
+    pub merge_function: Option<MergeFunction>,
 }
 
 pub(crate) async fn create_and_start_storage(
@@ -36,13 +37,24 @@ pub(crate) async fn create_and_start_storage(
     zenoh: Arc<Session>,
 ) -> ZResult<Sender<StorageMessage>> {
     log::trace!("Create storage '{}'", &admin_key);
-    let capability = backend.get_capability();
+    let merge_function = backend.merge_function();
+    if config.conflict_resolution == ConflictResolution::Merge && merge_function.is_none() {
+        bail!(
+            "Storage '{}' is configured with the `merge` conflict resolution, but its volume '{}' has no merge function",
+            config.name,
+            config.volume_id
+        );
+    }
     let storage = backend.create_storage(config.clone()).await?;
+    let capability = storage
+        .get_capability()
+        .unwrap_or_else(|| backend.get_capability());
     let store_intercept = StoreIntercept {
         storage,
         capability,
         in_interceptor,
         out_interceptor,
+        merge_function,
     };
 
     start_storage(store_intercept, config, admin_key, zenoh).await
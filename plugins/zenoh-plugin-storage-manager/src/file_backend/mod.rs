@@ -0,0 +1,547 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use async_trait::async_trait;
+use std::cmp::Ordering;
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use zenoh::buffers::ZBuf;
+use zenoh::prelude::r#async::*;
+use zenoh::serialization::{self, ZDeserializer};
+use zenoh::time::Timestamp;
+use zenoh_backend_traits::config::{StorageConfig, VolumeConfig};
+use zenoh_backend_traits::*;
+use zenoh_plugin_trait::{plugin_long_version, plugin_version, Plugin};
+use zenoh_result::{bail, zerror, ZResult};
+use zenoh_util::zenoh_home;
+
+use crate::FILE_BACKEND_NAME;
+
+/// The name of the log file in the directory of each storage.
+const LOG_FILENAME: &str = "log";
+/// The name of the log file being written during a compaction.
+const COMPACTED_LOG_FILENAME: &str = "log.compacted";
+/// The name of the file locked by the storage using a directory.
+const LOCK_FILENAME: &str = "lock";
+/// The default directory of the storages, in the zenoh home.
+const DEFAULT_BASE_DIR: &str = "zenoh_backend_file";
+/// The default minimal number of records in a log before it is compacted.
+const DEFAULT_COMPACTION_THRESHOLD: usize = 1000;
+
+/// A record of the log: the key (possibly a wildcard key expression), the timestamp and, for a put,
+/// the encoding and the payload of the value.
+type Record = (Option<String>, String, Option<(String, ZBuf)>);
+
+/// A stored key, ordered as the pages of [`Storage::get_entries_page`].
+#[derive(Clone, PartialEq, Eq)]
+struct Key(Option<OwnedKeyExpr>);
+
+impl Ord for Key {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_keys(&self.0, &other.0)
+    }
+}
+
+impl PartialOrd for Key {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+pub struct FileBackend {
+    config: VolumeConfig,
+    base_dir: PathBuf,
+    compaction_threshold: usize,
+}
+
+impl Plugin for FileBackend {
+    type StartArgs = VolumeConfig;
+    type Instance = VolumeInstance;
+
+    const DEFAULT_NAME: &'static str = FILE_BACKEND_NAME;
+    const PLUGIN_VERSION: &'static str = plugin_version!();
+    const PLUGIN_LONG_VERSION: &'static str = plugin_long_version!();
+
+    fn start(_: &str, args: &VolumeConfig) -> ZResult<VolumeInstance> {
+        let base_dir = match args.rest.get("base_dir") {
+            Some(serde_json::Value::String(dir)) => PathBuf::from(dir),
+            None => zenoh_home().join(DEFAULT_BASE_DIR),
+            _ => bail!(
+                "Invalid type for field `base_dir` of volume `{}`. Only strings are accepted.",
+                args.name()
+            ),
+        };
+        let compaction_threshold = match args.rest.get("compaction_threshold") {
+            Some(threshold) => match threshold.as_u64() {
+                Some(threshold) => threshold as usize,
+                None => bail!("Invalid type for field `compaction_threshold` of volume `{}`. Only integer values are accepted.", args.name()),
+            },
+            None => DEFAULT_COMPACTION_THRESHOLD,
+        };
+        Ok(Box::new(FileBackend {
+            config: args.clone(),
+            base_dir,
+            compaction_threshold,
+        }))
+    }
+}
+
+#[async_trait]
+impl Volume for FileBackend {
+    fn get_admin_status(&self) -> serde_json::Value {
+        self.config.to_json_value()
+    }
+
+    fn get_capability(&self) -> Capability {
+        Capability {
+            persistence: Persistence::Durable,
+            history: History::Latest,
+            read_cost: 0,
+        }
+    }
+
+    async fn create_storage(&self, properties: StorageConfig) -> ZResult<Box<dyn Storage>> {
+        log::debug!("Create File Storage with configuration: {:?}", properties);
+        let dir = match properties.volume_cfg.get("dir") {
+            Some(serde_json::Value::String(dir)) => self.base_dir.join(dir),
+            None => self.base_dir.join(&properties.name),
+            _ => bail!(
+                "Invalid type for field `dir` of storage `{}`. Only strings are accepted.",
+                properties.name
+            ),
+        };
+        Ok(Box::new(FileStorage::open(
+            properties,
+            &dir,
+            self.compaction_threshold,
+        )?))
+    }
+
+    fn incoming_data_interceptor(&self) -> Option<Arc<dyn Fn(Sample) -> Sample + Send + Sync>> {
+        None
+    }
+
+    fn outgoing_data_interceptor(&self) -> Option<Arc<dyn Fn(Sample) -> Sample + Send + Sync>> {
+        None
+    }
+}
+
+/// A storage keeping the latest value of each key in memory, and every update in an append-only log.
+/// The log is replayed when the storage is opened, and compacted when it mostly contains outdated records.
+/// Each update (or batch of updates) is synced to disk before being acknowledged.
+struct FileStorage {
+    config: StorageConfig,
+    dir: PathBuf,
+    // locked as long as the storage is open
+    _lock: File,
+    log: File,
+    // the number of records in the log
+    records: usize,
+    compaction_threshold: usize,
+    map: BTreeMap<Key, StoredData>,
+}
+
+impl FileStorage {
+    fn open(config: StorageConfig, dir: &Path, compaction_threshold: usize) -> ZResult<Self> {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| zerror!("Cannot create directory {}: {}", dir.display(), e))?;
+        let lock = lock(dir)?;
+        let path = dir.join(LOG_FILENAME);
+        let bytes = match std::fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == ErrorKind::NotFound => Vec::new(),
+            Err(e) => bail!("Cannot read {}: {}", path.display(), e),
+        };
+        let len = bytes.len();
+        let zbuf = ZBuf::from(bytes);
+        let mut deserializer = ZDeserializer::new(&zbuf);
+        let mut map = BTreeMap::new();
+        let (mut records, mut valid_len) = (0, 0);
+        while !deserializer.done() {
+            let record = deserializer
+                .deserialize::<ZBuf>()
+                .and_then(|record| serialization::deserialize::<Record>(&record));
+            match record.and_then(|record| apply(&mut map, record)) {
+                Ok(()) => {
+                    records += 1;
+                    valid_len = len - deserializer.remaining();
+                }
+                Err(e) => {
+                    // the last record may have been partially written
+                    log::warn!(
+                        "Truncating {} after {} records: {}",
+                        path.display(),
+                        records,
+                        e
+                    );
+                    break;
+                }
+            }
+        }
+        let log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| zerror!("Cannot open {}: {}", path.display(), e))?;
+        if valid_len < len {
+            log.set_len(valid_len as u64)?;
+        }
+        log::debug!(
+            "Opened {} with {} records for {} keys",
+            path.display(),
+            records,
+            map.len()
+        );
+        let mut storage = FileStorage {
+            config,
+            dir: dir.to_path_buf(),
+            _lock: lock,
+            log,
+            records,
+            compaction_threshold,
+            map,
+        };
+        storage.compact_if_needed()?;
+        Ok(storage)
+    }
+
+    // Appends a record to the log, without syncing it
+    fn append(&mut self, record: &Record) -> ZResult<()> {
+        let frame = serialization::serialize(&serialization::serialize(record));
+        self.log.write_all(&frame.contiguous())?;
+        self.records += 1;
+        Ok(())
+    }
+
+    // Makes the appended records durable
+    fn sync(&self) -> ZResult<()> {
+        self.log
+            .sync_data()
+            .map_err(|e| zerror!("Cannot sync the log of {}: {}", self.dir.display(), e).into())
+    }
+
+    fn insert(
+        &mut self,
+        key: Option<OwnedKeyExpr>,
+        value: Value,
+        timestamp: Timestamp,
+    ) -> ZResult<StorageInsertionResult> {
+        self.append(&to_record(&key, &value, &timestamp))?;
+        let result = match self.map.entry(Key(key)) {
+            Entry::Occupied(mut e) => {
+                e.insert(StoredData { value, timestamp });
+                StorageInsertionResult::Replaced
+            }
+            Entry::Vacant(e) => {
+                e.insert(StoredData { value, timestamp });
+                StorageInsertionResult::Inserted
+            }
+        };
+        self.compact_if_needed()?;
+        Ok(result)
+    }
+
+    fn remove(
+        &mut self,
+        key: Option<OwnedKeyExpr>,
+        timestamp: Timestamp,
+    ) -> ZResult<StorageInsertionResult> {
+        self.append(&(
+            key.as_ref().map(|k| k.to_string()),
+            timestamp.to_string(),
+            None,
+        ))?;
+        self.map.remove(&Key(key));
+        self.compact_if_needed()?;
+        Ok(StorageInsertionResult::Deleted)
+    }
+
+    // Syncs the records appended for a batch: none of them is stored if it fails
+    fn sync_batch(
+        &self,
+        results: Vec<ZResult<StorageInsertionResult>>,
+    ) -> Vec<ZResult<StorageInsertionResult>> {
+        match self.sync() {
+            Ok(()) => results,
+            Err(e) => results
+                .into_iter()
+                .map(|result| result.and(Err(zerror!("{}", e).into())))
+                .collect(),
+        }
+    }
+
+    fn compact_if_needed(&mut self) -> ZResult<()> {
+        if self.records < self.compaction_threshold || self.records < 2 * self.map.len() {
+            return Ok(());
+        }
+        log::debug!(
+            "Compacting {} records for {} keys in {}",
+            self.records,
+            self.map.len(),
+            self.dir.display()
+        );
+        // write the records of the current values in a new log, then replace the old one
+        let path = self.dir.join(COMPACTED_LOG_FILENAME);
+        let mut log = File::create(&path)?;
+        for (key, data) in self.map.iter() {
+            let record = to_record(&key.0, &data.value, &data.timestamp);
+            let frame = serialization::serialize(&serialization::serialize(&record));
+            log.write_all(&frame.contiguous())?;
+        }
+        log.sync_all()?;
+        std::fs::rename(&path, self.dir.join(LOG_FILENAME))?;
+        self.log = OpenOptions::new()
+            .append(true)
+            .open(self.dir.join(LOG_FILENAME))?;
+        self.records = self.map.len();
+        Ok(())
+    }
+}
+
+/// Takes the lock file of a storage directory, failing if another storage uses it.
+/// The lock is released when the returned file is closed, including when the process exits.
+fn lock(dir: &Path) -> ZResult<File> {
+    let path = dir.join(LOCK_FILENAME);
+    let mut options = OpenOptions::new();
+    options.create(true).write(true);
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::OpenOptionsExt;
+        // no other handle can open the file while this one is open
+        options.share_mode(0);
+    }
+    let file = options.open(&path).map_err(|e| {
+        zerror!(
+            "Cannot open {}: {} (is the directory used by another storage?)",
+            path.display(),
+            e
+        )
+    })?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::io::AsRawFd;
+        nix::fcntl::flock(
+            file.as_raw_fd(),
+            nix::fcntl::FlockArg::LockExclusiveNonblock,
+        )
+        .map_err(|e| {
+            zerror!(
+                "Cannot lock {}: {} (is the directory used by another storage?)",
+                path.display(),
+                e
+            )
+        })?;
+    }
+    Ok(file)
+}
+
+fn to_record(key: &Option<OwnedKeyExpr>, value: &Value, timestamp: &Timestamp) -> Record {
+    (
+        key.as_ref().map(|k| k.to_string()),
+        timestamp.to_string(),
+        Some((value.encoding.to_string(), value.payload.clone())),
+    )
+}
+
+fn apply(map: &mut BTreeMap<Key, StoredData>, (key, timestamp, value): Record) -> ZResult<()> {
+    let key = key.map(OwnedKeyExpr::try_from).transpose()?;
+    let timestamp = Timestamp::from_str(&timestamp)
+        .map_err(|e| zerror!("Invalid timestamp {}: {:?}", timestamp, e))?;
+    let value = value
+        .map(|(encoding, payload)| -> ZResult<Value> {
+            Ok(Value::new(payload).encoding(Encoding::from_str(&encoding)?))
+        })
+        .transpose()?;
+    match key {
+        Some(key_expr) if key_expr.is_wild() => {
+            apply_wildcard(map, &key_expr, value, timestamp);
+        }
+        key => apply_key(map, key, value, timestamp),
+    }
+    Ok(())
+}
+
+fn apply_key(
+    map: &mut BTreeMap<Key, StoredData>,
+    key: Option<OwnedKeyExpr>,
+    value: Option<Value>,
+    timestamp: Timestamp,
+) {
+    match value {
+        Some(value) => {
+            map.insert(Key(key), StoredData { value, timestamp });
+        }
+        None => {
+            map.remove(&Key(key));
+        }
+    }
+}
+
+/// Applies a wildcard update to the stored keys it includes and that are older than it.
+/// Returns the updated keys.
+fn apply_wildcard(
+    map: &mut BTreeMap<Key, StoredData>,
+    key_expr: &keyexpr,
+    value: Option<Value>,
+    timestamp: Timestamp,
+) -> Vec<Option<OwnedKeyExpr>> {
+    let updated: Vec<_> = map
+        .iter()
+        .filter(|(Key(key), data)| {
+            data.timestamp < timestamp
+                && match key {
+                    Some(key) => key_expr.includes(key),
+                    None => key_expr.as_str() == "**",
+                }
+        })
+        .map(|(Key(key), _)| key.clone())
+        .collect();
+    for key in updated.iter() {
+        apply_key(map, key.clone(), value.clone(), timestamp);
+    }
+    updated
+}
+
+#[async_trait]
+impl Storage for FileStorage {
+    fn get_admin_status(&self) -> serde_json::Value {
+        self.config.to_json_value()
+    }
+
+    async fn put(
+        &mut self,
+        key: Option<OwnedKeyExpr>,
+        value: Value,
+        timestamp: Timestamp,
+    ) -> ZResult<StorageInsertionResult> {
+        log::trace!("put for {:?}", key);
+        let result = self.insert(key, value, timestamp)?;
+        self.sync()?;
+        Ok(result)
+    }
+
+    async fn delete(
+        &mut self,
+        key: Option<OwnedKeyExpr>,
+        timestamp: Timestamp,
+    ) -> ZResult<StorageInsertionResult> {
+        log::trace!("delete for {:?}", key);
+        let result = self.remove(key, timestamp)?;
+        self.sync()?;
+        Ok(result)
+    }
+
+    async fn put_batch(
+        &mut self,
+        entries: Vec<(Option<OwnedKeyExpr>, Value, Timestamp)>,
+    ) -> Vec<ZResult<StorageInsertionResult>> {
+        log::trace!("put_batch of {} entries", entries.len());
+        let results = entries
+            .into_iter()
+            .map(|(key, value, timestamp)| self.insert(key, value, timestamp))
+            .collect();
+        self.sync_batch(results)
+    }
+
+    async fn delete_batch(
+        &mut self,
+        entries: Vec<(Option<OwnedKeyExpr>, Timestamp)>,
+    ) -> Vec<ZResult<StorageInsertionResult>> {
+        log::trace!("delete_batch of {} entries", entries.len());
+        let results = entries
+            .into_iter()
+            .map(|(key, timestamp)| self.remove(key, timestamp))
+            .collect();
+        self.sync_batch(results)
+    }
+
+    async fn put_wildcard(
+        &mut self,
+        key_expr: OwnedKeyExpr,
+        value: Value,
+        timestamp: Timestamp,
+    ) -> ZResult<Option<Vec<Option<OwnedKeyExpr>>>> {
+        log::trace!("put_wildcard for {}", key_expr);
+        // a single record, applied to the matching keys when replayed
+        self.append(&to_record(&Some(key_expr.clone()), &value, &timestamp))?;
+        let updated = apply_wildcard(&mut self.map, &key_expr, Some(value), timestamp);
+        self.compact_if_needed()?;
+        self.sync()?;
+        Ok(Some(updated))
+    }
+
+    async fn delete_wildcard(
+        &mut self,
+        key_expr: OwnedKeyExpr,
+        timestamp: Timestamp,
+    ) -> ZResult<Option<Vec<Option<OwnedKeyExpr>>>> {
+        log::trace!("delete_wildcard for {}", key_expr);
+        self.append(&(Some(key_expr.to_string()), timestamp.to_string(), None))?;
+        let updated = apply_wildcard(&mut self.map, &key_expr, None, timestamp);
+        self.compact_if_needed()?;
+        self.sync()?;
+        Ok(Some(updated))
+    }
+
+    async fn get(
+        &mut self,
+        key: Option<OwnedKeyExpr>,
+        _parameters: &str,
+    ) -> ZResult<Vec<StoredData>> {
+        log::trace!("get for {:?}", key);
+        match self.map.get(&Key(key.clone())) {
+            Some(v) => Ok(vec![v.clone()]),
+            None => Err(format!("Key {:?} is not present", key).into()),
+        }
+    }
+
+    async fn get_all_entries(&self) -> ZResult<Vec<(Option<OwnedKeyExpr>, Timestamp)>> {
+        Ok(self
+            .map
+            .iter()
+            .map(|(Key(k), v)| (k.clone(), v.timestamp))
+            .collect())
+    }
+
+    async fn get_entries_page(
+        &self,
+        after: Option<Option<OwnedKeyExpr>>,
+        limit: usize,
+    ) -> ZResult<Option<Vec<(Option<OwnedKeyExpr>, Timestamp)>>> {
+        let start = match after {
+            Some(after) => Bound::Excluded(Key(after)),
+            None => Bound::Unbounded,
+        };
+        Ok(Some(
+            self.map
+                .range((start, Bound::Unbounded))
+                .take(limit)
+                .map(|(Key(k), v)| (k.clone(), v.timestamp))
+                .collect(),
+        ))
+    }
+}
+
+impl Drop for FileStorage {
+    fn drop(&mut self) {
+        if let Err(e) = self.log.sync_all() {
+            log::warn!("Cannot sync the log of {}: {}", self.dir.display(), e);
+        }
+    }
+}
@@ -20,6 +20,7 @@
 #![recursion_limit = "512"]
 
 use async_std::task;
+use file_backend::FileBackend;
 use flume::Sender;
 use memory_backend::MemoryBackend;
 use std::collections::HashMap;
@@ -48,6 +49,7 @@ use zenoh_util::LibLoader;
 
 mod backends_mgt;
 use backends_mgt::*;
+mod file_backend;
 mod memory_backend;
 mod replica;
 mod storages_mgt;
@@ -112,7 +114,8 @@ impl StorageRuntimeInner {
             .unwrap_or_default();
 
         let plugins_manager = PluginsManager::dynamic(lib_loader.clone(), BACKEND_LIB_PREFIX)
-            .declare_static_plugin::<MemoryBackend>();
+            .declare_static_plugin::<MemoryBackend>()
+            .declare_static_plugin::<FileBackend>();
 
         let session = Arc::new(zenoh::init(runtime.clone()).res_sync()?);
 
@@ -369,6 +372,7 @@ impl RunningPluginTrait for StorageRuntime {
 
 const BACKEND_LIB_PREFIX: &str = "zenoh_backend_";
 const MEMORY_BACKEND_NAME: &str = "memory";
+const FILE_BACKEND_NAME: &str = "file";
 
 fn with_extended_string<R, F: FnMut(&mut String) -> R>(
     prefix: &mut String,
@@ -13,14 +13,15 @@
 //
 use async_std::sync::RwLock;
 use async_trait::async_trait;
-use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use zenoh::prelude::r#async::*;
 use zenoh::time::Timestamp;
 use zenoh_backend_traits::config::{StorageConfig, VolumeConfig};
 use zenoh_backend_traits::*;
 use zenoh_plugin_trait::{plugin_long_version, plugin_version, Plugin};
-use zenoh_result::ZResult;
+use zenoh_result::{bail, ZResult};
 
 use crate::MEMORY_BACKEND_NAME;
 
@@ -90,18 +91,76 @@ impl Drop for MemoryBackend {
     }
 }
 
+/// Default maximum number of versions kept per key by a memory storage with `history: "all"`.
+const DEFAULT_MAX_VERSIONS: usize = 1024;
+
+/// The versions stored for a key, ordered by timestamp. A `None` value is a deletion.
+type Versions = BTreeMap<Timestamp, Option<Value>>;
+
 struct MemoryStorage {
     config: StorageConfig,
-    map: Arc<RwLock<HashMap<Option<OwnedKeyExpr>, StoredData>>>,
+    history: History,
+    max_versions: usize,
+    map: Arc<RwLock<HashMap<Option<OwnedKeyExpr>, Versions>>>,
 }
 
 impl MemoryStorage {
     async fn new(properties: StorageConfig) -> ZResult<MemoryStorage> {
+        let history = match properties.volume_cfg.get("history") {
+            None => History::Latest,
+            Some(serde_json::Value::String(s)) if s == "latest" => History::Latest,
+            Some(serde_json::Value::String(s)) if s == "all" => History::All,
+            Some(v) => bail!(
+                "Invalid `history` for memory storage `{}`: {}. Only \"latest\" and \"all\" are accepted.",
+                properties.name,
+                v
+            ),
+        };
+        let max_versions = match (&history, properties.volume_cfg.get("max_versions")) {
+            (History::Latest, None) => 1,
+            (History::All, None) => DEFAULT_MAX_VERSIONS,
+            (History::All, Some(v)) => match v.as_u64() {
+                Some(n) if n > 0 => n as usize,
+                _ => bail!(
+                    "Invalid `max_versions` for memory storage `{}`: {}. A strictly positive integer is expected.",
+                    properties.name,
+                    v
+                ),
+            },
+            (History::Latest, Some(_)) => bail!(
+                "`max_versions` for memory storage `{}` requires `history: \"all\"`",
+                properties.name
+            ),
+        };
         Ok(MemoryStorage {
             config: properties,
+            history,
+            max_versions,
             map: Arc::new(RwLock::new(HashMap::new())),
         })
     }
+
+    /// Inserts a version for `key`, dropping the oldest versions beyond `max_versions`.
+    async fn insert(
+        &self,
+        key: Option<OwnedKeyExpr>,
+        value: Option<Value>,
+        timestamp: Timestamp,
+    ) -> StorageInsertionResult {
+        let mut map = self.map.write().await;
+        let (versions, result) = match map.entry(key) {
+            Entry::Occupied(e) => (e.into_mut(), StorageInsertionResult::Replaced),
+            Entry::Vacant(e) => (e.insert(Versions::new()), StorageInsertionResult::Inserted),
+        };
+        if self.history == History::Latest {
+            versions.clear();
+        }
+        versions.insert(timestamp, value);
+        while versions.len() > self.max_versions {
+            versions.pop_first();
+        }
+        result
+    }
 }
 
 #[async_trait]
@@ -110,6 +169,14 @@ impl Storage for MemoryStorage {
         self.config.to_json_value()
     }
 
+    fn get_capability(&self) -> Option<Capability> {
+        Some(Capability {
+            persistence: Persistence::Volatile,
+            history: self.history.clone(),
+            read_cost: 0,
+        })
+    }
+
     async fn put(
         &mut self,
         key: Option<OwnedKeyExpr>,
@@ -117,50 +184,96 @@ impl Storage for MemoryStorage {
         timestamp: Timestamp,
     ) -> ZResult<StorageInsertionResult> {
         log::trace!("put for {:?}", key);
-        let mut map = self.map.write().await;
-        match map.entry(key) {
-            std::collections::hash_map::Entry::Occupied(mut e) => {
-                e.insert(StoredData { value, timestamp });
-                return Ok(StorageInsertionResult::Replaced);
-            }
-            std::collections::hash_map::Entry::Vacant(e) => {
-                e.insert(StoredData { value, timestamp });
-                return Ok(StorageInsertionResult::Inserted);
-            }
-        }
+        Ok(self.insert(key, Some(value), timestamp).await)
     }
 
     async fn delete(
         &mut self,
         key: Option<OwnedKeyExpr>,
-        _timestamp: Timestamp,
+        timestamp: Timestamp,
     ) -> ZResult<StorageInsertionResult> {
         log::trace!("delete for {:?}", key);
-        self.map.write().await.remove_entry(&key);
+        match self.history {
+            History::Latest => {
+                self.map.write().await.remove_entry(&key);
+            }
+            // keep the deletion as a version, so that the history before it remains available
+            History::All => {
+                self.insert(key, None, timestamp).await;
+            }
+        }
         return Ok(StorageInsertionResult::Deleted);
     }
 
     async fn get(
         &mut self,
         key: Option<OwnedKeyExpr>,
-        _parameters: &str,
+        parameters: &str,
     ) -> ZResult<Vec<StoredData>> {
         log::trace!("get for {:?}", key);
-        // @TODO: use parameters???
-        match self.map.read().await.get(&key) {
-            Some(v) => Ok(vec![v.clone()]),
-            None => Err(format!("Key {:?} is not present", key).into()),
+        let map = self.map.read().await;
+        let versions = match map.get(&key) {
+            Some(versions) => versions,
+            None => bail!("Key {:?} is not present", key),
+        };
+        match parameters.time_range()? {
+            Some(time_range) if self.history == History::All => {
+                let time_range = time_range.resolve();
+                Ok(versions
+                    .iter()
+                    .filter(|(ts, _)| time_range.contains(ts.get_time().to_system_time()))
+                    .filter_map(|(ts, value)| {
+                        value.as_ref().map(|value| StoredData {
+                            value: value.clone(),
+                            timestamp: *ts,
+                        })
+                    })
+                    .collect())
+            }
+            _ => match versions.last_key_value() {
+                Some((ts, Some(value))) => Ok(vec![StoredData {
+                    value: value.clone(),
+                    timestamp: *ts,
+                }]),
+                _ => bail!("Key {:?} is not present", key),
+            },
         }
     }
 
     async fn get_all_entries(&self) -> ZResult<Vec<(Option<OwnedKeyExpr>, Timestamp)>> {
         let map = self.map.read().await;
         let mut result = Vec::with_capacity(map.len());
-        for (k, v) in map.iter() {
-            result.push((k.clone(), v.timestamp));
+        for (k, versions) in map.iter() {
+            if let Some(ts) = versions.keys().next_back() {
+                result.push((k.clone(), *ts));
+            }
         }
         Ok(result)
     }
+
+    async fn get_entries_page(
+        &self,
+        after: Option<Option<OwnedKeyExpr>>,
+        limit: usize,
+    ) -> ZResult<Option<Vec<(Option<OwnedKeyExpr>, Timestamp)>>> {
+        let map = self.map.read().await;
+        let mut result: Vec<_> = map
+            .iter()
+            .filter(|(k, _)| {
+                after
+                    .as_ref()
+                    .map_or(true, |after| cmp_keys(k, after).is_gt())
+            })
+            .filter_map(|(k, versions)| versions.keys().next_back().map(|ts| (k.clone(), *ts)))
+            .collect();
+        // only sort the entries of the page
+        if result.len() > limit && limit > 0 {
+            result.select_nth_unstable_by(limit - 1, |(a, _), (b, _)| cmp_keys(a, b));
+        }
+        result.truncate(limit);
+        result.sort_unstable_by(|(a, _), (b, _)| cmp_keys(a, b));
+        Ok(Some(result))
+    }
 }
 
 impl Drop for MemoryStorage {
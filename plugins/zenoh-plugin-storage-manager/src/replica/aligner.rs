@@ -16,20 +16,33 @@ use super::{Digest, EraType, LogEntry, Snapshotter};
 use super::{CONTENTS, ERA, INTERVALS, SUBINTERVALS};
 use async_std::sync::{Arc, RwLock};
 use flume::{Receiver, Sender};
+use futures::{select, FutureExt};
 use std::collections::{HashMap, HashSet};
 use std::str;
+use std::time::SystemTime;
 use zenoh::key_expr::{KeyExpr, OwnedKeyExpr};
 use zenoh::prelude::r#async::*;
 use zenoh::time::Timestamp;
 use zenoh::Session;
 
+// Statistics of the alignment of a replica with the other ones
+#[derive(Default)]
+pub struct AlignmentStats {
+    // the time at which the latest alignment ended
+    pub last_alignment: Option<SystemTime>,
+    // the number of entries pulled from each replica
+    pub pulled_entries: HashMap<String, usize>,
+}
+
 pub struct Aligner {
     session: Arc<Session>,
     digest_key: OwnedKeyExpr,
     snapshotter: Arc<Snapshotter>,
     rx_digest: Receiver<(String, Digest)>,
+    rx_full_alignment: Receiver<(String, Digest)>,
     tx_sample: Sender<Sample>,
     digests_processed: RwLock<HashSet<u64>>,
+    stats: Arc<RwLock<AlignmentStats>>,
 }
 
 impl Aligner {
@@ -37,43 +50,67 @@ impl Aligner {
         session: Arc<Session>,
         digest_key: OwnedKeyExpr,
         rx_digest: Receiver<(String, Digest)>,
+        rx_full_alignment: Receiver<(String, Digest)>,
         tx_sample: Sender<Sample>,
         snapshotter: Arc<Snapshotter>,
+        stats: Arc<RwLock<AlignmentStats>>,
     ) {
         let aligner = Aligner {
             session,
             digest_key,
             snapshotter,
             rx_digest,
+            rx_full_alignment,
             tx_sample,
             digests_processed: RwLock::new(HashSet::new()),
+            stats,
         };
         aligner.start().await;
     }
 
     pub async fn start(&self) {
-        while let Ok((from, incoming_digest)) = self.rx_digest.recv_async().await {
-            if self.in_processed(incoming_digest.checksum).await {
-                log::trace!(
-                    "[ALIGNER]Skipping already processed digest: {}",
-                    incoming_digest.checksum
-                );
-                continue;
-            } else if self.snapshotter.get_digest().await.checksum == incoming_digest.checksum {
-                log::trace!(
-                    "[ALIGNER]Skipping matching digest: {}",
-                    incoming_digest.checksum
-                );
-                continue;
-            } else {
-                // process this digest
-                log::debug!(
-                    "[ALIGNER]Processing digest: {:?} from {}",
-                    incoming_digest,
-                    from
-                );
-                self.process_incoming_digest(incoming_digest, &from).await;
-            }
+        loop {
+            select!(
+                digest = self.rx_digest.recv_async().fuse() => match digest {
+                    Ok((from, incoming_digest)) => self.align(incoming_digest, &from).await,
+                    Err(_) => return,
+                },
+                // the alignment with all the content of a replica, requested from the admin space
+                digest = self.rx_full_alignment.recv_async().fuse() => match digest {
+                    Ok((from, incoming_digest)) => {
+                        log::debug!("[ALIGNER]Processing full alignment with {}", from);
+                        let this = Digest::empty(incoming_digest.timestamp, incoming_digest.config.clone());
+                        self.process_incoming_digest(&this, incoming_digest, &from).await;
+                    }
+                    Err(_) => return,
+                },
+            )
+        }
+    }
+
+    async fn align(&self, incoming_digest: Digest, from: &str) {
+        if self.in_processed(incoming_digest.checksum).await {
+            log::trace!(
+                "[ALIGNER]Skipping already processed digest: {}",
+                incoming_digest.checksum
+            );
+            return;
+        }
+        let this = self.snapshotter.get_digest().await;
+        if this.checksum == incoming_digest.checksum {
+            log::trace!(
+                "[ALIGNER]Skipping matching digest: {}",
+                incoming_digest.checksum
+            );
+        } else {
+            // process this digest
+            log::debug!(
+                "[ALIGNER]Processing digest: {:?} from {}",
+                incoming_digest,
+                from
+            );
+            self.process_incoming_digest(&this, incoming_digest, from)
+                .await;
         }
     }
 
@@ -83,10 +120,10 @@ impl Aligner {
     }
 
     //identify alignment requirements
-    async fn process_incoming_digest(&self, other: Digest, from: &str) {
+    async fn process_incoming_digest(&self, this: &Digest, other: Digest, from: &str) {
         let checksum = other.checksum;
         let timestamp = other.timestamp;
-        let (missing_content, no_content_err) = self.get_missing_content(&other, from).await;
+        let (missing_content, no_content_err) = self.get_missing_content(this, &other, from).await;
         log::debug!(
             "[ALIGNER] Missing {} entries; query corresponding samples",
             missing_content.len()
@@ -102,6 +139,13 @@ impl Aligner {
             // Missing data might be empty since some samples in digest might be outdated
             log::debug!("[ALIGNER] Received {} queried samples", missing_data.len());
             log::trace!("[ALIGNER] Received queried samples: {missing_data:?}");
+            *self
+                .stats
+                .write()
+                .await
+                .pulled_entries
+                .entry(from.to_string())
+                .or_default() += missing_data.len();
 
             for (key, (ts, value)) in missing_data {
                 let sample = Sample::new(key, value).with_timestamp(ts);
@@ -116,6 +160,7 @@ impl Aligner {
                 (*processed).insert(checksum);
             }
         }
+        self.stats.write().await.last_alignment = Some(SystemTime::now());
     }
 
     async fn get_missing_data(
@@ -142,10 +187,13 @@ impl Aligner {
         (result, no_err)
     }
 
-    async fn get_missing_content(&self, other: &Digest, from: &str) -> (Vec<LogEntry>, bool) {
+    async fn get_missing_content(
+        &self,
+        this: &Digest,
+        other: &Digest,
+        from: &str,
+    ) -> (Vec<LogEntry>, bool) {
         log::debug!("[ALIGNER] Get missing content from {from} ...");
-        // get my digest
-        let this = &self.snapshotter.get_digest().await;
 
         let cold_alignment =
             self.perform_era_alignment(&EraType::Cold, this, from.to_string(), other);
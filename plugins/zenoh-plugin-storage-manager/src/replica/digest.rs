@@ -17,6 +17,7 @@ use derive_new::new;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::convert::TryFrom;
+use std::fmt;
 use std::str::FromStr;
 use std::string::ParseError;
 use std::time::Duration;
@@ -96,6 +97,16 @@ impl FromStr for EraType {
     }
 }
 
+impl fmt::Display for EraType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EraType::Hot => write!(f, "hot"),
+            EraType::Warm => write!(f, "warm"),
+            EraType::Cold => write!(f, "cold"),
+        }
+    }
+}
+
 trait Checksum {
     fn format_content(&self) -> String;
 }
@@ -129,6 +140,18 @@ impl Checksum for u64 {
 
 //functions for digest creation and update
 impl Digest {
+    // Creates a digest without content, to align with all the content of another digest
+    pub fn empty(timestamp: Timestamp, config: DigestConfig) -> Digest {
+        Digest {
+            timestamp,
+            config,
+            checksum: 0,
+            eras: HashMap::new(),
+            intervals: HashMap::new(),
+            subintervals: HashMap::new(),
+        }
+    }
+
     // Creates a digest from scratch when initializing the replica
     pub fn create_digest(
         timestamp: Timestamp,
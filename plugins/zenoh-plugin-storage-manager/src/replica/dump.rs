@@ -0,0 +1,130 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+// The portable format of the contents of a storage, exported and imported through its admin space:
+// a JSON document with the stored values (with their key, encoding and timestamp, and their payload in base64)
+// and the tombstones of the deleted keys
+
+use base64::{engine::general_purpose::STANDARD as b64_std_engine, Engine};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use zenoh::buffers::ZBuf;
+use zenoh::prelude::r#async::*;
+use zenoh::time::Timestamp;
+use zenoh_result::{bail, zerror, ZResult};
+
+// The version of the format, to be increased on incompatible changes
+pub const DUMP_FORMAT_VERSION: u64 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct StorageDump {
+    pub version: u64,
+    // the key expression of the storage exporting the dump
+    pub key_expr: String,
+    pub entries: Vec<DumpEntry>,
+    pub tombstones: Vec<DumpTombstone>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DumpEntry {
+    pub key: String,
+    pub timestamp: String,
+    pub encoding: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DumpTombstone {
+    pub key: String,
+    pub timestamp: String,
+}
+
+impl StorageDump {
+    pub fn new(key_expr: &OwnedKeyExpr) -> Self {
+        StorageDump {
+            version: DUMP_FORMAT_VERSION,
+            key_expr: key_expr.to_string(),
+            entries: Vec::new(),
+            tombstones: Vec::new(),
+        }
+    }
+
+    pub fn from_value(value: &Value) -> ZResult<Self> {
+        let dump: StorageDump = serde_json::from_slice(&value.payload.contiguous())
+            .map_err(|e| zerror!("Invalid storage dump: {}", e))?;
+        if dump.version != DUMP_FORMAT_VERSION {
+            bail!(
+                "Unsupported version {} of storage dump (expected {})",
+                dump.version,
+                DUMP_FORMAT_VERSION
+            );
+        }
+        Ok(dump)
+    }
+
+    pub fn to_value(&self) -> Value {
+        Value::from(serde_json::to_string(self).unwrap()).encoding(KnownEncoding::AppJson.into())
+    }
+
+    pub fn push_entry(&mut self, key: &OwnedKeyExpr, value: &Value, timestamp: &Timestamp) {
+        self.entries.push(DumpEntry {
+            key: key.to_string(),
+            timestamp: timestamp.to_string(),
+            encoding: value.encoding.to_string(),
+            value: b64_std_engine.encode(value.payload.contiguous()),
+        });
+    }
+
+    pub fn push_tombstone(&mut self, key: &OwnedKeyExpr, timestamp: &Timestamp) {
+        self.tombstones.push(DumpTombstone {
+            key: key.to_string(),
+            timestamp: timestamp.to_string(),
+        });
+    }
+
+    // Returns the samples recreating the contents of the dump: puts for the entries, deletes for the tombstones
+    pub fn into_samples(self) -> ZResult<Vec<Sample>> {
+        let mut samples = Vec::with_capacity(self.entries.len() + self.tombstones.len());
+        for entry in self.entries {
+            let payload = b64_std_engine
+                .decode(&entry.value)
+                .map_err(|e| zerror!("Invalid value of `{}` in storage dump: {}", entry.key, e))?;
+            let encoding = Encoding::from_str(&entry.encoding).map_err(|e| {
+                zerror!("Invalid encoding of `{}` in storage dump: {}", entry.key, e)
+            })?;
+            let value = Value::new(ZBuf::from(payload)).encoding(encoding);
+            samples.push(
+                Sample::new(parse_key(&entry.key)?, value)
+                    .with_timestamp(parse_timestamp(&entry.timestamp)?),
+            );
+        }
+        for tombstone in self.tombstones {
+            let mut sample = Sample::new(parse_key(&tombstone.key)?, Value::empty())
+                .with_timestamp(parse_timestamp(&tombstone.timestamp)?);
+            sample.kind = SampleKind::Delete;
+            samples.push(sample);
+        }
+        Ok(samples)
+    }
+}
+
+fn parse_key(key: &str) -> ZResult<OwnedKeyExpr> {
+    OwnedKeyExpr::from_str(key)
+        .map_err(|e| zerror!("Invalid key `{}` in storage dump: {}", key, e).into())
+}
+
+fn parse_timestamp(timestamp: &str) -> ZResult<Timestamp> {
+    Timestamp::from_str(timestamp)
+        .map_err(|e| zerror!("Invalid timestamp `{}` in storage dump: {:?}", timestamp, e).into())
+}
@@ -24,7 +24,7 @@ use futures::{pin_mut, select, FutureExt};
 use std::collections::{HashMap, HashSet};
 use std::str;
 use std::str::FromStr;
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 use urlencoding::encode;
 use zenoh::prelude::r#async::*;
 use zenoh::time::Timestamp;
@@ -34,13 +34,16 @@ use zenoh_backend_traits::config::{ReplicaConfig, StorageConfig};
 pub mod align_queryable;
 pub mod aligner;
 pub mod digest;
+pub mod dump;
 pub mod snapshotter;
+pub mod stamper;
 pub mod storage;
 
 pub use align_queryable::AlignQueryable;
-pub use aligner::Aligner;
+pub use aligner::{Aligner, AlignmentStats};
 pub use digest::{Digest, DigestConfig, EraType, LogEntry};
 pub use snapshotter::Snapshotter;
+pub use stamper::Stamper;
 pub use storage::{ReplicationService, StorageService};
 
 const ERA: &str = "era";
@@ -50,6 +53,9 @@ const CONTENTS: &str = "contents";
 pub const EPOCH_START: SystemTime = SystemTime::UNIX_EPOCH;
 
 pub const ALIGN_PREFIX: &str = "@-digest";
+pub const STAMP_PREFIX: &str = "@-stamped";
+// The suffix of the admin key of a storage to request a full alignment with another replica
+pub const ALIGN_REQUEST_SUFFIX: &str = "align";
 pub const SUBINTERVAL_CHUNKS: usize = 10;
 
 // A replica consists of a storage service and services required for anti-entropy
@@ -61,6 +67,8 @@ pub const SUBINTERVAL_CHUNKS: usize = 10;
 // The `Aligner` identifies mismatches in the contents of the storage with respect to the other storage
 // `Aligner` generates a list of missing updates that is then send to the `StorageService`
 // When a `StorageService` receives an update, it sends a log to the `Snapshotter`
+// A full alignment with another replica can be requested by a put on <storage_admin_key>/align/<replica_name>
+// When a `StorageService` receives a sample without timestamp, the `Stamper` ensures it gets the same timestamp on all replicas
 
 pub struct Replica {
     // TODO: Discuss if we need to add -<storage_type> for uniqueness
@@ -69,6 +77,8 @@ pub struct Replica {
     key_expr: OwnedKeyExpr,
     replica_config: ReplicaConfig,
     digests_published: RwLock<HashSet<u64>>, // checksum of all digests generated and published by this replica
+    replicas: Arc<RwLock<HashMap<String, Instant>>>, // time of the latest digest received from each remote replica
+    digests: RwLock<HashMap<String, Digest>>, // latest digest received from each remote replica
 }
 
 impl Replica {
@@ -78,6 +88,7 @@ impl Replica {
         store_intercept: StoreIntercept,
         storage_config: StorageConfig,
         name: &str,
+        admin_key: &str,
         rx: Receiver<StorageMessage>,
     ) {
         log::trace!("[REPLICA] Opening session...");
@@ -115,6 +126,8 @@ impl Replica {
             key_expr: storage_config.key_expr.clone(),
             replica_config: storage_config.replica_config.clone().unwrap(),
             digests_published: RwLock::new(HashSet::new()),
+            replicas: Arc::new(RwLock::new(HashMap::new())),
+            digests: RwLock::new(HashMap::new()),
         };
 
         // Create channels for communication between components
@@ -124,12 +137,19 @@ impl Replica {
         let (tx_sample, rx_sample) = flume::unbounded();
         // channel for storage to send logging information back
         let (tx_log, rx_log) = flume::unbounded();
+        // channel to queue digests to be fully aligned with
+        let (tx_full_alignment, rx_full_alignment) = flume::unbounded();
+        let alignment_stats = Arc::new(RwLock::new(AlignmentStats::default()));
 
         let config = replica.replica_config.clone();
         // snapshotter
         let snapshotter = Arc::new(Snapshotter::new(rx_log, &startup_entries, &config).await);
         // digest sub
         let digest_sub = replica.start_digest_sub(tx_digest).fuse();
+        // requests of full alignment
+        let align_request_sub = replica
+            .start_align_request_sub(admin_key, tx_full_alignment)
+            .fuse();
         // queryable for alignment
         let digest_key = Replica::get_digest_key(&replica.key_expr, ALIGN_PREFIX);
         let align_q = AlignQueryable::start_align_queryable(
@@ -144,8 +164,10 @@ impl Replica {
             replica.session.clone(),
             digest_key,
             rx_digest,
+            rx_full_alignment,
             tx_sample,
             snapshotter.clone(),
+            alignment_stats.clone(),
         )
         .fuse();
         // digest pub
@@ -154,17 +176,30 @@ impl Replica {
         //updating snapshot time
         let snapshot_task = snapshotter.start().fuse();
 
+        // stamper of the samples without timestamp
+        let stamper = Stamper::new(
+            replica.session.clone(),
+            &replica.name,
+            Replica::get_digest_key(&replica.key_expr, STAMP_PREFIX),
+            replica.replicas.clone(),
+            &config,
+        );
+
         //actual storage
         let replication = ReplicationService {
             empty_start: startup_entries.is_empty(),
             aligner_updates: rx_sample,
             log_propagation: tx_log,
+            stamper,
+            snapshotter: snapshotter.clone(),
+            alignment_stats,
         };
         // channel to pipe the receiver to storage
         let storage_task = StorageService::start(
             replica.session.clone(),
             storage_config,
             &replica.name,
+            admin_key,
             store_intercept,
             rx,
             Some(replication),
@@ -173,6 +208,7 @@ impl Replica {
 
         pin_mut!(
             digest_sub,
+            align_request_sub,
             align_q,
             aligner,
             digest_pub,
@@ -182,6 +218,7 @@ impl Replica {
 
         select!(
             () = digest_sub => log::trace!("[REPLICA] Exiting digest subscriber"),
+            () = align_request_sub => log::trace!("[REPLICA] Exiting align request subscriber"),
             () = align_q => log::trace!("[REPLICA] Exiting align queryable"),
             () = aligner => log::trace!("[REPLICA] Exiting aligner"),
             () = digest_pub => log::trace!("[REPLICA] Exiting digest publisher"),
@@ -235,6 +272,14 @@ impl Replica {
                     continue;
                 }
             };
+            self.replicas
+                .write()
+                .await
+                .insert(from.to_string(), Instant::now());
+            self.digests
+                .write()
+                .await
+                .insert(from.to_string(), digest.clone());
             let ts = digest.timestamp;
             let to_be_processed = self
                 .processing_needed(
@@ -256,6 +301,55 @@ impl Replica {
         }
     }
 
+    // Create a subscriber to requests of full alignment with another replica
+    // Subscribe on <storage_admin_key>/align/**
+    pub async fn start_align_request_sub(&self, admin_key: &str, tx: Sender<(String, Digest)>) {
+        let request_key = format!("{admin_key}/{ALIGN_REQUEST_SUFFIX}/");
+
+        log::debug!(
+            "[ALIGN_REQUEST_SUB] Declaring Subscriber named {} on '{}**'",
+            self.name,
+            request_key
+        );
+        let subscriber = self
+            .session
+            .declare_subscriber(format!("{request_key}**"))
+            .res()
+            .await
+            .unwrap();
+        loop {
+            let sample = match subscriber.recv_async().await {
+                Ok(sample) => sample,
+                Err(e) => {
+                    log::error!("[ALIGN_REQUEST_SUB] Error receiving sample: {}", e);
+                    continue;
+                }
+            };
+            // the request is a write on the admin space, not routed through the admin space checks
+            if !self.session.config().lock().adminspace.permissions().write {
+                log::error!(
+                    "[ALIGN_REQUEST_SUB] Received PUT on '{}' but adminspace.permissions.write=false in configuration",
+                    sample.key_expr
+                );
+                continue;
+            }
+            let from = &sample.key_expr.as_str()[request_key.len()..];
+            let digest = self.digests.read().await.get(from).cloned();
+            match digest {
+                Some(digest) => {
+                    log::debug!("[ALIGN_REQUEST_SUB] Requesting full alignment with {}", from);
+                    if let Err(e) = tx.send_async((from.to_string(), digest)).await {
+                        log::error!("[ALIGN_REQUEST_SUB] Error sending digest to aligner: {}", e)
+                    }
+                }
+                None => log::error!(
+                    "[ALIGN_REQUEST_SUB] Cannot align with {}: no digest received from this replica",
+                    from
+                ),
+            }
+        }
+    }
+
     // Create a publisher to periodically publish digests from the snapshotter
     // Publish on <align_prefix>/<encoded_key_expr>/<replica_name>
     pub async fn start_digest_pub(&self, snapshotter: Arc<Snapshotter>) {
@@ -0,0 +1,270 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use async_std::sync::{Arc, Mutex, RwLock};
+use std::collections::{HashMap, VecDeque};
+use std::str::{self, FromStr};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use zenoh::prelude::r#async::*;
+use zenoh::sample::{Attachment, AttachmentBuilder};
+use zenoh::time::{Timestamp, TimestampId, NTP64};
+use zenoh::Session;
+use zenoh_backend_traits::config::ReplicaConfig;
+use zenoh_result::{bail, zerror, ZResult};
+
+// The attachments of a stamped sample giving its key and its timestamp
+const KEY_ATTACHMENT_KEY: &str = "@key";
+const TIMESTAMP_ATTACHMENT_KEY: &str = "@timestamp";
+
+// The replicas of a storage stamp the samples published without timestamp in a convergent way:
+// the replica with the lowest name among the live ones stamps each sample with the HLC of its router,
+// and propagates the stamped sample to the other replicas on <stamp_prefix>/<encoded_key_expr>/<replica_name>
+// The other replicas hold the samples they received without timestamp until they receive them stamped,
+// and stamp them themselves if they are not received in time (e.g. the stamping replica missed them)
+// The replicas are known by the digests they publish
+pub struct Stamper {
+    session: Arc<Session>,
+    name: String,
+    stamp_key: OwnedKeyExpr,
+    // the replicas having published a digest, with the time of their latest digest
+    replicas: Arc<RwLock<HashMap<String, Instant>>>,
+    // the time after which a replica not publishing digests is considered as gone
+    liveliness: Duration,
+    // the time to wait for a sample stamped by another replica
+    grace_period: Duration,
+    // the latest timestamp generated, when the router has no HLC
+    latest: Mutex<NTP64>,
+    pending: Mutex<Pending>,
+}
+
+#[derive(Default)]
+struct Pending {
+    // the samples received without timestamp, waiting to be stamped by another replica
+    unstamped: VecDeque<(Instant, Sample)>,
+    // the samples stamped by another replica, received before their unstamped version
+    stamped: VecDeque<(Instant, Sample)>,
+}
+
+impl Stamper {
+    pub fn new(
+        session: Arc<Session>,
+        name: &str,
+        stamp_key: OwnedKeyExpr,
+        replicas: Arc<RwLock<HashMap<String, Instant>>>,
+        config: &ReplicaConfig,
+    ) -> Self {
+        Stamper {
+            session,
+            name: name.to_string(),
+            stamp_key,
+            replicas,
+            liveliness: config.publication_interval * 3,
+            grace_period: config.propagation_delay * 2,
+            latest: Mutex::new(NTP64::default()),
+            pending: Mutex::new(Pending::default()),
+        }
+    }
+
+    // The key expression of the samples stamped by the other replicas
+    pub fn subscription_key(&self) -> OwnedKeyExpr {
+        self.stamp_key.join("**").unwrap()
+    }
+
+    // Returns the sample stamped if this replica is the one stamping it, else holds it
+    pub async fn stamp(&self, sample: Sample) -> Option<Sample> {
+        let mut pending = self.pending.lock().await;
+        if let Some(i) = pending
+            .stamped
+            .iter()
+            .position(|(_, s)| is_same_sample(s, &sample))
+        {
+            // already stored with the timestamp of another replica
+            pending.stamped.remove(i);
+            return None;
+        }
+        if self.is_stamping().await {
+            drop(pending);
+            Some(self.stamp_and_propagate(sample).await)
+        } else {
+            log::trace!(
+                "[STAMPER] Waiting for sample {} to be stamped by another replica",
+                sample
+            );
+            pending.unstamped.push_back((Instant::now(), sample));
+            None
+        }
+    }
+
+    // Returns the sample stamped by another replica, to be stored with its timestamp
+    pub async fn receive_stamped(&self, sample: Sample) -> ZResult<Sample> {
+        let sample = decode(sample)?;
+        log::trace!("[STAMPER] Received stamped sample {}", sample);
+        let mut pending = self.pending.lock().await;
+        match pending
+            .unstamped
+            .iter()
+            .position(|(_, s)| is_same_sample(s, &sample))
+        {
+            Some(i) => {
+                pending.unstamped.remove(i);
+            }
+            None => {
+                if !self.is_stamping().await {
+                    pending.stamped.push_back((Instant::now(), sample.clone()));
+                }
+            }
+        }
+        Ok(sample)
+    }
+
+    // Waits until the grace period of the oldest held sample is over
+    pub async fn pending_timeout(&self) {
+        let pending = self.pending.lock().await;
+        let oldest = pending
+            .unstamped
+            .front()
+            .into_iter()
+            .chain(pending.stamped.front())
+            .map(|(since, _)| *since)
+            .min();
+        drop(pending);
+        match oldest {
+            Some(since) => {
+                async_std::task::sleep(self.grace_period.saturating_sub(since.elapsed())).await
+            }
+            None => futures::future::pending().await,
+        }
+    }
+
+    // Returns the held samples not stamped by another replica in time, stamped by this one
+    pub async fn stamp_overdue(&self) -> Vec<Sample> {
+        let mut overdue = Vec::new();
+        let mut pending = self.pending.lock().await;
+        while matches!(pending.unstamped.front(), Some((since, _)) if since.elapsed() >= self.grace_period)
+        {
+            overdue.push(pending.unstamped.pop_front().unwrap().1);
+        }
+        while matches!(pending.stamped.front(), Some((since, _)) if since.elapsed() >= self.grace_period)
+        {
+            pending.stamped.pop_front();
+        }
+        drop(pending);
+        let mut stamped = Vec::with_capacity(overdue.len());
+        for sample in overdue {
+            log::debug!(
+                "[STAMPER] Sample {} was not stamped by another replica in time",
+                sample
+            );
+            stamped.push(self.stamp_and_propagate(sample).await);
+        }
+        stamped
+    }
+
+    // This replica stamps the samples if no live replica has a lower name
+    async fn is_stamping(&self) -> bool {
+        let replicas = self.replicas.read().await;
+        !replicas
+            .iter()
+            .any(|(name, last_seen)| *name < self.name && last_seen.elapsed() < self.liveliness)
+    }
+
+    async fn stamp_and_propagate(&self, sample: Sample) -> Sample {
+        let timestamp = self.new_timestamp().await;
+        let sample = sample.with_timestamp(timestamp);
+        log::trace!("[STAMPER] Propagating stamped sample {}", sample);
+        let mut attachment = AttachmentBuilder::new();
+        if let Some(original) = sample.attachment() {
+            for (key, value) in original.iter() {
+                attachment.insert(key.as_slice(), value.as_slice());
+            }
+        }
+        attachment.insert(KEY_ATTACHMENT_KEY, sample.key_expr.as_str());
+        attachment.insert(TIMESTAMP_ATTACHMENT_KEY, &timestamp.to_string());
+        let key = self.stamp_key.join(&self.name).unwrap();
+        if let Err(e) = self
+            .session
+            .put(key, sample.value.clone())
+            .kind(sample.kind)
+            .with_attachment(attachment.build())
+            .res()
+            .await
+        {
+            log::error!(
+                "[STAMPER] Error propagating stamped sample {}: {}",
+                sample,
+                e
+            );
+        }
+        sample
+    }
+
+    async fn new_timestamp(&self) -> Timestamp {
+        if let Some(hlc) = self.session.hlc() {
+            return hlc.new_timestamp();
+        }
+        // without HLC, generate monotonic timestamps with the id of the router
+        let mut latest = self.latest.lock().await;
+        let now: NTP64 = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().into();
+        *latest = if now > *latest {
+            now
+        } else {
+            NTP64(latest.as_u64() + 1)
+        };
+        Timestamp::new(*latest, TimestampId::from(&self.session.zid()))
+    }
+}
+
+// Rebuilds the sample stamped by another replica from its attachments
+fn decode(sample: Sample) -> ZResult<Sample> {
+    let mut key = None;
+    let mut timestamp = None;
+    let mut attachment = AttachmentBuilder::new();
+    let mut has_attachment = false;
+    if let Some(original) = sample.attachment() {
+        for (k, v) in original.iter() {
+            match k.as_slice() {
+                k if k == KEY_ATTACHMENT_KEY.as_bytes() => {
+                    key = Some(KeyExpr::try_from(str::from_utf8(&v)?.to_string())?)
+                }
+                k if k == TIMESTAMP_ATTACHMENT_KEY.as_bytes() => {
+                    timestamp = Some(
+                        Timestamp::from_str(str::from_utf8(&v)?)
+                            .map_err(|e| zerror!("Invalid timestamp: {:?}", e))?,
+                    )
+                }
+                _ => {
+                    attachment.insert(k.as_slice(), v.as_slice());
+                    has_attachment = true;
+                }
+            }
+        }
+    }
+    let (key, timestamp) = match (key, timestamp) {
+        (Some(key), Some(timestamp)) => (key, timestamp),
+        _ => bail!("Stamped sample {} lacks its key or its timestamp", sample),
+    };
+    let mut stamped = Sample::new(key, sample.value).with_timestamp(timestamp);
+    stamped.kind = sample.kind;
+    if has_attachment {
+        stamped = stamped.with_attachment(Attachment::from(attachment));
+    }
+    Ok(stamped)
+}
+
+fn is_same_sample(a: &Sample, b: &Sample) -> bool {
+    a.key_expr.as_str() == b.key_expr.as_str()
+        && a.kind == b.kind
+        && a.value.encoding == b.value.encoding
+        && a.value.payload == b.value.payload
+}
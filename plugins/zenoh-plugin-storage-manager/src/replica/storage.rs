@@ -11,43 +11,177 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
+use super::dump::StorageDump;
+use super::{AlignmentStats, Snapshotter, Stamper};
 use crate::backends_mgt::StoreIntercept;
 use crate::storages_mgt::StorageMessage;
 use async_std::sync::Arc;
 use async_std::sync::{Mutex, RwLock};
 use async_trait::async_trait;
 use flume::{Receiver, Sender};
-use futures::select;
-use std::collections::{HashMap, HashSet};
+use futures::{select, FutureExt};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::str::{self, FromStr};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use zenoh::buffers::ZBuf;
 use zenoh::prelude::r#async::*;
 use zenoh::query::ConsolidationMode;
 use zenoh::time::{Timestamp, NTP64};
 use zenoh::{Result as ZResult, Session};
-use zenoh_backend_traits::config::{GarbageCollectionConfig, StorageConfig};
-use zenoh_backend_traits::{Capability, History, Persistence, StorageInsertionResult, StoredData};
+use zenoh_backend_traits::config::{
+    BatchingConfig, ConflictResolution, GarbageCollectionConfig, StorageConfig,
+};
+use zenoh_backend_traits::{
+    cmp_keys, Capability, History, MergeFunction, Persistence, StorageInsertionResult, StoredData,
+};
 use zenoh_keyexpr::key_expr::OwnedKeyExpr;
 use zenoh_keyexpr::keyexpr_tree::impls::KeyedSetProvider;
 use zenoh_keyexpr::keyexpr_tree::{support::NonWild, support::UnknownWildness, KeBoxTree};
 use zenoh_keyexpr::keyexpr_tree::{IKeyExprTree, IKeyExprTreeMut};
-use zenoh_result::bail;
+use zenoh_result::{bail, zerror};
+use zenoh_util::time_range::TimeExpr;
 use zenoh_util::{zenoh_home, Timed, TimedEvent, Timer};
 
 pub const WILDCARD_UPDATES_FILENAME: &str = "wildcard_updates";
 pub const TOMBSTONE_FILENAME: &str = "tombstones";
 
+// The selector parameters to page through the keys replying to a wildcard query
+pub const LIMIT_KEY: &str = "_limit";
+pub const OFFSET_KEY: &str = "_offset";
+pub const CURSOR_KEY: &str = "_cursor";
+pub const ORDER_KEY: &str = "_order";
+
+// The number of entries retrieved at once from the storage to reply to a wildcard query
+const ENTRIES_PAGE_SIZE: usize = 1000;
+
+// The suffixes of the admin keys of a storage to export its contents, and to import contents previously exported
+pub const EXPORT_SUFFIX: &str = "export";
+pub const IMPORT_SUFFIX: &str = "import";
+
+// The attachment of a sample giving its time-to-live in seconds, overriding the `ttl` of the storage
+pub const TTL_ATTACHMENT_KEY: &str = "ttl";
+
 #[derive(Clone)]
 struct Update {
     kind: SampleKind,
     data: StoredData,
 }
 
+// A write waiting in a batch, to be stored on the next flush
+struct BatchedWrite {
+    key: OwnedKeyExpr,
+    stripped_key: Option<OwnedKeyExpr>,
+    kind: SampleKind,
+    value: Value,
+    timestamp: Timestamp,
+}
+
+enum ReplyOrder {
+    Key,
+    Time,
+}
+
+// The paging of the keys replying to a wildcard query:
+// `_order` is either `key` (the default) or `time` (the timestamp of the latest update of the keys),
+// `_cursor` is the key after which the keys are replied (only when ordering by key),
+// `_offset` is the number of keys to skip, and `_limit` the maximum number of keys to reply.
+struct Paging {
+    limit: Option<usize>,
+    offset: usize,
+    cursor: Option<OwnedKeyExpr>,
+    order: ReplyOrder,
+}
+
+impl Paging {
+    fn from_parameters(parameters: &str) -> ZResult<Self> {
+        let [limit, offset, cursor, order] =
+            parameters.get_parameters([LIMIT_KEY, OFFSET_KEY, CURSOR_KEY, ORDER_KEY])?;
+        let limit = match limit {
+            Some(limit) => match limit.parse::<usize>() {
+                Ok(limit) => Some(limit),
+                Err(e) => bail!("Invalid `{}={}`: {}", LIMIT_KEY, limit, e),
+            },
+            None => None,
+        };
+        let offset = match offset {
+            Some(offset) => match offset.parse::<usize>() {
+                Ok(offset) => offset,
+                Err(e) => bail!("Invalid `{}={}`: {}", OFFSET_KEY, offset, e),
+            },
+            None => 0,
+        };
+        let cursor = match cursor {
+            Some(cursor) => match OwnedKeyExpr::new(cursor.as_ref()) {
+                Ok(cursor) if !cursor.is_wild() => Some(cursor),
+                _ => bail!("Invalid `{}={}`: not a key", CURSOR_KEY, cursor),
+            },
+            None => None,
+        };
+        let order = match order.as_deref() {
+            None | Some("key") => ReplyOrder::Key,
+            Some("time") => ReplyOrder::Time,
+            Some(order) => bail!(
+                "Invalid `{}={}`: only `key` and `time` are accepted",
+                ORDER_KEY,
+                order
+            ),
+        };
+        if cursor.is_some() && matches!(order, ReplyOrder::Time) {
+            bail!("`{}` requires `{}=key`", CURSOR_KEY, ORDER_KEY);
+        }
+        Ok(Paging {
+            limit,
+            offset,
+            cursor,
+            order,
+        })
+    }
+}
+
+#[derive(Default)]
+struct Expirations {
+    // the timestamp of the latest sample of each key to be deleted on expiry
+    latest: HashMap<OwnedKeyExpr, Timestamp>,
+    // the samples to be deleted, by the timestamp of their expiry
+    queue: BTreeMap<Timestamp, Vec<(OwnedKeyExpr, Timestamp)>>,
+}
+
+#[derive(Default)]
+struct WriteBatch {
+    // the time at which the first write of the batch was received
+    since: Option<Instant>,
+    writes: Vec<BatchedWrite>,
+}
+
 pub struct ReplicationService {
     pub empty_start: bool,
     pub aligner_updates: Receiver<Sample>,
     pub log_propagation: Sender<(OwnedKeyExpr, Timestamp)>,
+    pub stamper: Stamper,
+    pub snapshotter: Arc<Snapshotter>,
+    pub alignment_stats: Arc<RwLock<AlignmentStats>>,
+}
+
+impl ReplicationService {
+    // The status of the alignment of the replica, reported in the admin space of the storage
+    async fn get_admin_status(&self) -> serde_json::Value {
+        let digest = self.snapshotter.get_digest().await;
+        let intervals: serde_json::Map<String, serde_json::Value> = digest
+            .eras
+            .iter()
+            .map(|(era, interval)| (era.to_string(), interval.content.len().into()))
+            .collect();
+        let stats = self.alignment_stats.read().await;
+        serde_json::json!({
+            "digest": {
+                "checksum": digest.checksum,
+                "timestamp": digest.timestamp.to_string(),
+                "intervals": intervals,
+            },
+            "last_alignment": stats.last_alignment.map(|t| TimeExpr::Fixed(t).to_string()),
+            "pulled_entries": stats.pulled_entries,
+        })
+    }
 }
 
 pub struct StorageService {
@@ -55,6 +189,7 @@ pub struct StorageService {
     key_expr: OwnedKeyExpr,
     complete: bool,
     name: String,
+    admin_key: String,
     strip_prefix: Option<OwnedKeyExpr>,
     storage: Mutex<Box<dyn zenoh_backend_traits::Storage>>,
     capability: Capability,
@@ -62,7 +197,13 @@ pub struct StorageService {
     wildcard_updates: Arc<RwLock<KeBoxTree<Update, UnknownWildness, KeyedSetProvider>>>,
     in_interceptor: Option<Arc<dyn Fn(Sample) -> Sample + Send + Sync>>,
     out_interceptor: Option<Arc<dyn Fn(Sample) -> Sample + Send + Sync>>,
+    conflict_resolution: ConflictResolution,
+    merge_function: Option<MergeFunction>,
     replication: Option<ReplicationService>,
+    batching: Option<BatchingConfig>,
+    batch: Mutex<WriteBatch>,
+    ttl: Option<Duration>,
+    expirations: Mutex<Expirations>,
 }
 
 impl StorageService {
@@ -70,6 +211,7 @@ impl StorageService {
         session: Arc<Session>,
         config: StorageConfig,
         name: &str,
+        admin_key: &str,
         store_intercept: StoreIntercept,
         rx: Receiver<StorageMessage>,
         replication: Option<ReplicationService>,
@@ -80,6 +222,7 @@ impl StorageService {
             key_expr: config.key_expr,
             complete: config.complete,
             name: name.to_string(),
+            admin_key: admin_key.to_string(),
             strip_prefix: config.strip_prefix,
             storage: Mutex::new(store_intercept.storage),
             capability: store_intercept.capability,
@@ -87,7 +230,13 @@ impl StorageService {
             wildcard_updates: Arc::new(RwLock::new(KeBoxTree::default())),
             in_interceptor: store_intercept.in_interceptor,
             out_interceptor: store_intercept.out_interceptor,
+            conflict_resolution: config.conflict_resolution,
+            merge_function: store_intercept.merge_function,
             replication,
+            batching: config.batching_config,
+            batch: Mutex::new(WriteBatch::default()),
+            ttl: config.ttl,
+            expirations: Mutex::new(Expirations::default()),
         };
         if storage_service
             .capability
@@ -116,6 +265,9 @@ impl StorageService {
                 }
             }
         }
+        if let Some(ttl) = storage_service.ttl {
+            storage_service.expire_stored_values(ttl).await;
+        }
         storage_service
             .start_storage_queryable_subscriber(rx, config.garbage_collection_config)
             .await
@@ -164,8 +316,37 @@ impl StorageService {
             }
         };
 
+        // answer to export and import requests on the admin space of the storage
+        let admin_queryable = match self
+            .session
+            .declare_queryable(format!("{}/*", self.admin_key))
+            .res()
+            .await
+        {
+            Ok(admin_queryable) => admin_queryable,
+            Err(e) => {
+                log::error!("Error starting storage '{}': {}", self.name, e);
+                return;
+            }
+        };
+
         if self.replication.is_some() {
             let aligner_updates = &self.replication.as_ref().unwrap().aligner_updates;
+            let stamper = &self.replication.as_ref().unwrap().stamper;
+            // samples stamped by other replicas
+            let stamped_sub = match self
+                .session
+                .declare_subscriber(stamper.subscription_key())
+                .allowed_origin(Locality::Remote)
+                .res()
+                .await
+            {
+                Ok(stamped_sub) => stamped_sub,
+                Err(e) => {
+                    log::error!("Error starting storage '{}': {}", self.name, e);
+                    return;
+                }
+            };
             loop {
                 select!(
                     // on sample for key_expr
@@ -177,43 +358,82 @@ impl StorageService {
                                 continue;
                             }
                         };
-                        // log error if the sample is not timestamped
+                        // the sample is stamped by a single replica, so that all replicas store it with the same timestamp
                         // This is to reduce down the line inconsistencies of having duplicate samples stored
                         if sample.get_timestamp().is_none() {
-                            log::error!("Sample {} is not timestamped. Please timestamp samples meant for replicated storage.", sample);
+                            if let Some(sample) = stamper.stamp(sample).await {
+                                self.process_sample(sample).await;
+                            }
                         }
                         else {
                             self.process_sample(sample).await;
                         }
                     },
+                    // on sample stamped by another replica
+                    sample = stamped_sub.recv_async() => {
+                        let sample = match sample {
+                            Ok(sample) => sample,
+                            Err(e) => {
+                                log::error!("Error in sample: {}", e);
+                                continue;
+                            }
+                        };
+                        match stamper.receive_stamped(sample).await {
+                            Ok(sample) => self.process_sample(sample).await,
+                            Err(e) => log::error!("Error in stamped sample: {}", e),
+                        }
+                    },
+                    // on samples not stamped by another replica in time
+                    _ = stamper.pending_timeout().fuse() => {
+                        for sample in stamper.stamp_overdue().await {
+                            self.process_sample(sample).await;
+                        }
+                    },
                     // on query on key_expr
                     query = storage_queryable.recv_async() => {
                         self.reply_query(query).await;
                     },
+                    // on export or import request
+                    query = admin_queryable.recv_async() => {
+                        self.reply_admin_query(query).await;
+                    },
+                    // on batch timeout
+                    _ = self.batch_timeout().fuse() => {
+                        self.flush_batch().await;
+                    },
+                    // on expiry of a value
+                    _ = self.expiration_timeout().fuse() => {
+                        self.purge_expired().await;
+                    },
                     // on aligner update
                     update = aligner_updates.recv_async() => {
                         match update {
-                            Ok(sample) => self.process_sample(sample).await,
+                            Ok(sample) => self.process_aligned_sample(sample).await,
                             Err(e) => {
                                 log::error!("Error in receiving aligner update: {}", e);
                             }
                         }
                     },
                     // on storage handle drop
-                    message = rx.recv_async() => {
+                    // (fused, as the channel future is otherwise skipped once disconnected)
+                    message = rx.recv_async().fuse() => {
                         match message {
-                            Ok(StorageMessage::Stop) => {
+                            // the storage handle may also be dropped without stopping the storage
+                            Ok(StorageMessage::Stop) | Err(_) => {
                                 log::trace!("Dropping storage '{}'", self.name);
+                                self.flush_batch().await;
                                 return
                             },
                             Ok(StorageMessage::GetStatus(tx)) => {
-                                let storage = self.storage.lock().await;
-                                std::mem::drop(tx.send(storage.get_admin_status()).await);
-                                drop(storage);
+                                let mut status = self.storage.lock().await.get_admin_status();
+                                if let serde_json::Value::Object(status) = &mut status {
+                                    status.insert(
+                                        "replication".to_string(),
+                                        self.replication.as_ref().unwrap().get_admin_status().await,
+                                    );
+                                }
+                                std::mem::drop(tx.send(status).await);
                             }
-                            Err(e) => {
-                                log::error!("Storage Message Channel Error: {}", e);
-                            },
                         };
                     }
                 );
@@ -233,15 +453,30 @@ impl StorageService {
                         sample.ensure_timestamp();
                         self.process_sample(sample).await;
                     },
+                    // on batch timeout
+                    _ = self.batch_timeout().fuse() => {
+                        self.flush_batch().await;
+                    },
+                    // on expiry of a value
+                    _ = self.expiration_timeout().fuse() => {
+                        self.purge_expired().await;
+                    },
                     // on query on key_expr
                     query = storage_queryable.recv_async() => {
                         self.reply_query(query).await;
                     },
+                    // on export or import request
+                    query = admin_queryable.recv_async() => {
+                        self.reply_admin_query(query).await;
+                    },
                     // on storage handle drop
-                    message = rx.recv_async() => {
+                    // (fused, as the channel future is otherwise skipped once disconnected)
+                    message = rx.recv_async().fuse() => {
                         match message {
-                            Ok(StorageMessage::Stop) => {
+                            // the storage handle may also be dropped without stopping the storage
+                            Ok(StorageMessage::Stop) | Err(_) => {
                                 log::trace!("Dropping storage '{}'", self.name);
+                                self.flush_batch().await;
                                 return
                             },
                             Ok(StorageMessage::GetStatus(tx)) => {
@@ -249,9 +484,6 @@ impl StorageService {
                                 std::mem::drop(tx.send(storage.get_admin_status()).await);
                                 drop(storage);
                             }
-                            Err(e) => {
-                                log::error!("Storage Message Channel Error: {}", e);
-                            },
                         };
                     },
                 );
@@ -262,6 +494,21 @@ impl StorageService {
     // The storage should only simply save the key, sample pair while put and retrieve the same during get
     // the trimming during PUT and GET should be handled by the plugin
     async fn process_sample(&self, sample: Sample) {
+        self.process_sample_with(sample, self.conflict_resolution)
+            .await
+    }
+
+    // Aligned samples are writes already resolved by other replicas: with the merge conflict resolution,
+    // they are resolved as last-writer-wins, as merging them again would endlessly produce new writes to align
+    async fn process_aligned_sample(&self, sample: Sample) {
+        let conflict_resolution = match self.conflict_resolution {
+            ConflictResolution::Merge => ConflictResolution::LastWriterWins,
+            conflict_resolution => conflict_resolution,
+        };
+        self.process_sample_with(sample, conflict_resolution).await
+    }
+
+    async fn process_sample_with(&self, sample: Sample, conflict_resolution: ConflictResolution) {
         log::trace!("[STORAGE] Processing sample: {}", sample);
         // Call incoming data interceptor (if any)
         let sample = if let Some(ref interceptor) = self.in_interceptor {
@@ -270,11 +517,18 @@ impl StorageService {
             sample
         };
 
-        // if wildcard, update wildcard_updates
+        // if wildcard, update wildcard_updates (to apply it to the older writes received later), and let the storage apply it
         if sample.key_expr.is_wild() {
             self.register_wildcard_update(sample.clone()).await;
+            // the pending writes precede the wildcard update, and must be stored to match it
+            self.flush_batch().await;
+            if self.apply_wildcard_update(&sample).await {
+                return;
+            }
         }
 
+        let sample_ttl = self.sample_ttl(&sample);
+
         let matching_keys = if sample.key_expr.is_wild() {
             self.get_matching_keys(&sample.key_expr).await
         } else {
@@ -287,13 +541,22 @@ impl StorageService {
         );
 
         for k in matching_keys {
-            if !self
+            // with History::All, every version is stored, even if it is older than a deletion
+            let resolved = if self.capability.history.eq(&History::All) {
+                Some(StoredData {
+                    value: sample.value.clone(),
+                    timestamp: sample.timestamp.unwrap(),
+                })
+            } else if self
                 .is_deleted(&k.clone(), sample.get_timestamp().unwrap())
                 .await
-                && (self.capability.history.eq(&History::All)
-                    || (self.capability.history.eq(&History::Latest)
-                        && self.is_latest(&k, sample.get_timestamp().unwrap()).await))
             {
+                None
+            } else {
+                self.resolve_conflict(&k, &sample, conflict_resolution)
+                    .await
+            };
+            if let Some(resolved) = resolved {
                 log::trace!(
                     "Sample `{}` identified as neded processing for key {}",
                     sample,
@@ -301,10 +564,39 @@ impl StorageService {
                 );
                 // there might be the case that the actual update was outdated due to a wild card update, but not stored yet in the storage.
                 // get the relevant wild card entry and use that value and timestamp to update the storage
-                let sample_to_store = match self
-                    .ovderriding_wild_update(&k, sample.get_timestamp().unwrap())
-                    .await
-                {
+                // (with History::All, the outdated update is kept as a historical version instead)
+                let overriding_update = if self.capability.history.eq(&History::Latest) {
+                    self.ovderriding_wild_update(&k, sample.get_timestamp().unwrap())
+                        .await
+                } else {
+                    None
+                };
+                let overriding_update = match (conflict_resolution, overriding_update) {
+                    // a later wild card put doesn't override the first write
+                    (ConflictResolution::FirstWriterWins, Some(update))
+                        if update.kind == SampleKind::Put =>
+                    {
+                        None
+                    }
+                    // a later wild card put is merged with the write
+                    (ConflictResolution::Merge, Some(update)) if update.kind == SampleKind::Put => {
+                        let merge = self.merge_function.as_ref().unwrap();
+                        Some(Update {
+                            kind: SampleKind::Put,
+                            data: StoredData {
+                                value: merge(&k, &resolved, &update.data),
+                                timestamp: self.merged_timestamp(update.data.timestamp),
+                            },
+                        })
+                    }
+                    (_, update) => update,
+                };
+                let ttl = if overriding_update.is_some() {
+                    self.ttl
+                } else {
+                    sample_ttl
+                };
+                let sample_to_store = match overriding_update {
                     Some(overriding_update) => {
                         let mut sample_to_store =
                             Sample::new(KeyExpr::from(k.clone()), overriding_update.data.value)
@@ -314,8 +606,8 @@ impl StorageService {
                     }
                     None => {
                         let mut sample_to_store =
-                            Sample::new(KeyExpr::from(k.clone()), sample.value.clone())
-                                .with_timestamp(sample.timestamp.unwrap());
+                            Sample::new(KeyExpr::from(k.clone()), resolved.value)
+                                .with_timestamp(resolved.timestamp);
                         sample_to_store.kind = sample.kind;
                         sample_to_store
                     }
@@ -328,6 +620,29 @@ impl StorageService {
                         return;
                     }
                 };
+                self.update_expiration(
+                    &k,
+                    sample_to_store.kind,
+                    sample_to_store.timestamp.unwrap(),
+                    ttl,
+                )
+                .await;
+                if sample.kind == SampleKind::Delete {
+                    // register a tombstone
+                    self.mark_tombstone(&k, sample_to_store.timestamp.unwrap())
+                        .await;
+                }
+                if self.batching.is_some() {
+                    self.push_to_batch(BatchedWrite {
+                        key: k.clone(),
+                        stripped_key,
+                        kind: sample.kind,
+                        value: sample_to_store.value,
+                        timestamp: sample_to_store.timestamp.unwrap(),
+                    })
+                    .await;
+                    continue;
+                }
                 let mut storage = self.storage.lock().await;
                 let result = if sample.kind == SampleKind::Put {
                     storage
@@ -338,9 +653,6 @@ impl StorageService {
                         )
                         .await
                 } else if sample.kind == SampleKind::Delete {
-                    // register a tombstone
-                    self.mark_tombstone(&k, sample_to_store.timestamp.unwrap())
-                        .await;
                     storage
                         .delete(stripped_key, sample_to_store.timestamp.unwrap())
                         .await
@@ -348,27 +660,243 @@ impl StorageService {
                     Err("sample kind not implemented".into())
                 };
                 drop(storage);
-                if self.replication.is_some()
-                    && result.is_ok()
-                    && !matches!(result.unwrap(), StorageInsertionResult::Outdated)
-                {
-                    let sending = self
-                        .replication
-                        .as_ref()
-                        .unwrap()
-                        .log_propagation
-                        .send((k.clone(), *sample_to_store.get_timestamp().unwrap()));
-                    match sending {
-                        Ok(_) => (),
-                        Err(e) => {
-                            log::error!("Error in sending the sample to the log: {}", e);
-                        }
-                    }
+                if result.is_ok() && !matches!(result.unwrap(), StorageInsertionResult::Outdated) {
+                    self.log_update(&k, *sample_to_store.get_timestamp().unwrap());
                 }
             }
         }
     }
 
+    // The time-to-live of a sample: the one of its attachment (if any), or the one of the storage
+    fn sample_ttl(&self, sample: &Sample) -> Option<Duration> {
+        let ttl = match sample.attachment().and_then(|a| a.get(&TTL_ATTACHMENT_KEY)) {
+            Some(ttl) => ttl,
+            None => return self.ttl,
+        };
+        let parsed = str::from_utf8(&ttl)
+            .ok()
+            .and_then(|ttl| ttl.parse::<f64>().ok())
+            .and_then(|ttl| Duration::try_from_secs_f64(ttl).ok());
+        if parsed.is_none() {
+            log::warn!(
+                "Sample {} has an invalid `{}` attachment. Only durations in seconds are accepted.",
+                sample,
+                TTL_ATTACHMENT_KEY
+            );
+        }
+        parsed.or(self.ttl)
+    }
+
+    async fn update_expiration(
+        &self,
+        key: &OwnedKeyExpr,
+        kind: SampleKind,
+        timestamp: Timestamp,
+        ttl: Option<Duration>,
+    ) {
+        let mut expirations = self.expirations.lock().await;
+        if matches!(expirations.latest.get(key), Some(latest) if *latest > timestamp) {
+            return;
+        }
+        match (kind, ttl) {
+            (SampleKind::Put, Some(ttl)) => {
+                // the deletion is timestamped with the expiry of the value,
+                // so that all the replicas delete it identically
+                let expiry = Timestamp::new(
+                    *timestamp.get_time() + NTP64::from(ttl),
+                    *timestamp.get_id(),
+                );
+                expirations.latest.insert(key.clone(), timestamp);
+                expirations
+                    .queue
+                    .entry(expiry)
+                    .or_default()
+                    .push((key.clone(), timestamp));
+            }
+            _ => {
+                expirations.latest.remove(key);
+            }
+        }
+    }
+
+    // Schedules the expiry of the values stored before the start of the storage (e.g. if it is durable)
+    async fn expire_stored_values(&self, ttl: Duration) {
+        let entries = match self.storage.lock().await.get_all_entries().await {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::warn!(
+                    "Storage '{}' raised an error while retrieving keys: {}",
+                    self.name,
+                    e
+                );
+                return;
+            }
+        };
+        for (k, ts) in entries {
+            self.update_expiration(&self.get_full_key(k), SampleKind::Put, ts, Some(ttl))
+                .await;
+        }
+    }
+
+    // Completes when the earliest value to expire (if any) has to be deleted
+    async fn expiration_timeout(&self) {
+        let expiry = self.expirations.lock().await.queue.keys().next().copied();
+        match expiry {
+            Some(expiry) => {
+                let delay = expiry
+                    .get_time()
+                    .to_system_time()
+                    .duration_since(SystemTime::now())
+                    .unwrap_or_default();
+                async_std::task::sleep(delay).await
+            }
+            None => futures::future::pending().await,
+        }
+    }
+
+    // Deletes the expired values, unless they were updated since
+    async fn purge_expired(&self) {
+        let now = SystemTime::now();
+        let mut expired = Vec::new();
+        let mut expirations = self.expirations.lock().await;
+        while let Some(entry) = expirations.queue.first_entry() {
+            if entry.key().get_time().to_system_time() > now {
+                break;
+            }
+            let (expiry, samples) = entry.remove_entry();
+            for (key, timestamp) in samples {
+                if expirations.latest.get(&key) == Some(&timestamp) {
+                    expirations.latest.remove(&key);
+                    expired.push((key, expiry));
+                }
+            }
+        }
+        drop(expirations);
+        for (key, expiry) in expired {
+            log::trace!("[STORAGE] Value of {} expired", key);
+            let mut sample = Sample::new(KeyExpr::from(key), Value::empty()).with_timestamp(expiry);
+            sample.kind = SampleKind::Delete;
+            self.process_sample(sample).await;
+        }
+    }
+
+    // Sends a successful update of the storage to the replication log (if any)
+    fn log_update(&self, key: &OwnedKeyExpr, timestamp: Timestamp) {
+        if let Some(replication) = &self.replication {
+            if let Err(e) = replication.log_propagation.send((key.clone(), timestamp)) {
+                log::error!("Error in sending the sample to the log: {}", e);
+            }
+        }
+    }
+
+    async fn push_to_batch(&self, write: BatchedWrite) {
+        let mut batch = self.batch.lock().await;
+        if self.capability.history.eq(&History::Latest) {
+            // only the latest write per key needs to be stored
+            batch.writes.retain(|w| w.key != write.key);
+        }
+        batch.writes.push(write);
+        batch.since.get_or_insert_with(Instant::now);
+        let full = self
+            .batching
+            .as_ref()
+            .map_or(true, |batching| batch.writes.len() >= batching.max_size);
+        drop(batch);
+        if full {
+            self.flush_batch().await;
+        }
+    }
+
+    // Completes when the pending batch (if any) has to be written
+    async fn batch_timeout(&self) {
+        let since = self.batch.lock().await.since;
+        match (&self.batching, since) {
+            (Some(batching), Some(since)) => {
+                async_std::task::sleep(batching.max_delay.saturating_sub(since.elapsed())).await
+            }
+            _ => futures::future::pending().await,
+        }
+    }
+
+    // Writes the pending batch (if any) in the storage
+    async fn flush_batch(&self) {
+        let writes = {
+            let mut batch = self.batch.lock().await;
+            batch.since = None;
+            std::mem::take(&mut batch.writes)
+        };
+        if writes.is_empty() {
+            return;
+        }
+        log::trace!("[STORAGE] Writing a batch of {} samples", writes.len());
+        let (puts, deletes): (Vec<_>, Vec<_>) =
+            writes.into_iter().partition(|w| w.kind == SampleKind::Put);
+        let put_keys: Vec<_> = puts.iter().map(|w| (w.key.clone(), w.timestamp)).collect();
+        let delete_keys: Vec<_> = deletes
+            .iter()
+            .map(|w| (w.key.clone(), w.timestamp))
+            .collect();
+        let mut storage = self.storage.lock().await;
+        let put_results = if puts.is_empty() {
+            Vec::new()
+        } else {
+            storage
+                .put_batch(
+                    puts.into_iter()
+                        .map(|w| (w.stripped_key, w.value, w.timestamp))
+                        .collect(),
+                )
+                .await
+        };
+        let delete_results = if deletes.is_empty() {
+            Vec::new()
+        } else {
+            storage
+                .delete_batch(
+                    deletes
+                        .into_iter()
+                        .map(|w| (w.stripped_key, w.timestamp))
+                        .collect(),
+                )
+                .await
+        };
+        drop(storage);
+        for (keys, results) in [(put_keys, put_results), (delete_keys, delete_results)] {
+            if results.len() != keys.len() {
+                log::error!(
+                    "Storage '{}' returned {} results for a batch of {} samples",
+                    self.name,
+                    results.len(),
+                    keys.len()
+                );
+            }
+            for ((key, timestamp), result) in keys.iter().zip(results) {
+                match result {
+                    Ok(StorageInsertionResult::Outdated) => {}
+                    Ok(_) => self.log_update(key, *timestamp),
+                    Err(e) => log::error!(
+                        "Storage '{}' raised an error writing {} from a batch: {}",
+                        self.name,
+                        key,
+                        e
+                    ),
+                }
+            }
+        }
+    }
+
+    // Returns the timestamp of the latest write on `key_expr` pending in the batch (if any)
+    async fn batched_timestamp(&self, key_expr: &OwnedKeyExpr) -> Option<Timestamp> {
+        self.batch
+            .lock()
+            .await
+            .writes
+            .iter()
+            .filter(|w| &w.key == key_expr)
+            .map(|w| w.timestamp)
+            .max()
+    }
+
     async fn mark_tombstone(&self, key_expr: &OwnedKeyExpr, timestamp: Timestamp) {
         // @TODO: change into a better store that does incremental writes
         let mut tombstones = self.tombstones.write().await;
@@ -388,6 +916,58 @@ impl StorageService {
         }
     }
 
+    // Lets the storage apply a wildcard update on the keys it stores, if it supports it:
+    // returns false if the wildcard update is to be applied by the storage manager instead
+    async fn apply_wildcard_update(&self, sample: &Sample) -> bool {
+        // the storage applies the wildcard update as last-writer-wins
+        if self.conflict_resolution != ConflictResolution::LastWriterWins {
+            return false;
+        }
+        let stripped_key = match self.strip_prefix(&sample.key_expr) {
+            Ok(Some(stripped)) => stripped,
+            _ => return false,
+        };
+        let timestamp = sample.timestamp.unwrap();
+        let mut storage = self.storage.lock().await;
+        let result = if sample.kind == SampleKind::Put {
+            storage
+                .put_wildcard(stripped_key, sample.value.clone(), timestamp)
+                .await
+        } else {
+            storage.delete_wildcard(stripped_key, timestamp).await
+        };
+        drop(storage);
+        let updated_keys = match result {
+            Ok(Some(updated_keys)) => updated_keys,
+            Ok(None) => return false,
+            Err(e) => {
+                log::error!(
+                    "Storage '{}' raised an error applying wildcard update {}: {}",
+                    self.name,
+                    sample,
+                    e
+                );
+                return true;
+            }
+        };
+        log::trace!(
+            "Wildcard update `{}` applied by the storage to {} keys",
+            sample.key_expr,
+            updated_keys.len()
+        );
+        let ttl = self.sample_ttl(sample);
+        for key in updated_keys {
+            let k = self.get_full_key(key);
+            self.update_expiration(&k, sample.kind, timestamp, ttl)
+                .await;
+            if sample.kind == SampleKind::Delete {
+                self.mark_tombstone(&k, timestamp).await;
+            }
+            self.log_update(&k, timestamp);
+        }
+        true
+    }
+
     async fn register_wildcard_update(&self, sample: Sample) {
         // @TODO: change into a better store that does incremental writes
         let key = sample.clone().key_expr;
@@ -438,6 +1018,10 @@ impl StorageService {
             if weight.is_some() && weight.unwrap().data.timestamp > *ts {
                 // if the key matches a wild card update, check whether it was saved in storage
                 // remember that wild card updates change only existing keys
+                if matches!(self.batched_timestamp(key_expr).await, Some(batched) if batched > *ts)
+                {
+                    return None;
+                }
                 let stripped_key = match self.strip_prefix(&key_expr.into()) {
                     Ok(stripped) => stripped,
                     Err(e) => {
@@ -470,24 +1054,97 @@ impl StorageService {
         update
     }
 
-    async fn is_latest(&self, key_expr: &OwnedKeyExpr, timestamp: &Timestamp) -> bool {
-        // @TODO: if cache exists, read from there
-        let mut storage = self.storage.lock().await;
-        let stripped_key = match self.strip_prefix(&key_expr.into()) {
-            Ok(stripped) => stripped,
+    // Resolves the conflict between a sample and the latest write on `key_expr` with the given conflict resolution:
+    // returns the data to store for the sample, or None if the sample is discarded
+    async fn resolve_conflict(
+        &self,
+        key_expr: &OwnedKeyExpr,
+        sample: &Sample,
+        conflict_resolution: ConflictResolution,
+    ) -> Option<StoredData> {
+        let incoming = StoredData {
+            value: sample.value.clone(),
+            timestamp: sample.timestamp.unwrap(),
+        };
+        let (latest_kind, latest) = match self.latest_write(key_expr).await {
+            Ok(Some(latest)) => latest,
+            Ok(None) => return Some(incoming),
             Err(e) => {
                 log::error!("{}", e);
-                return false;
+                return None;
             }
         };
-        if let Ok(stored_data) = storage.get(stripped_key, "").await {
-            for entry in stored_data {
-                if entry.timestamp > *timestamp {
-                    return false;
+        // deletions, and writes following a deletion, are always resolved as last-writer-wins
+        if sample.kind == SampleKind::Delete || latest_kind == SampleKind::Delete {
+            return (latest.timestamp <= incoming.timestamp).then_some(incoming);
+        }
+        match conflict_resolution {
+            ConflictResolution::LastWriterWins => {
+                (latest.timestamp <= incoming.timestamp).then_some(incoming)
+            }
+            ConflictResolution::FirstWriterWins => {
+                (incoming.timestamp <= latest.timestamp).then_some(incoming)
+            }
+            ConflictResolution::Merge => {
+                let merge = self.merge_function.as_ref().unwrap();
+                Some(StoredData {
+                    value: merge(key_expr, &latest, &incoming),
+                    timestamp: self.merged_timestamp(latest.timestamp.max(incoming.timestamp)),
+                })
+            }
+        }
+    }
+
+    // Returns a new timestamp for merged data, later than the latest of the merged writes,
+    // so that the merged data overrides these writes on all the replicas
+    fn merged_timestamp(&self, latest: Timestamp) -> Timestamp {
+        match self.session.hlc() {
+            Some(hlc) => {
+                if let Err(e) = hlc.update_with_timestamp(&latest) {
+                    log::warn!("Error updating HLC with timestamp {}: {}", latest, e);
                 }
+                hlc.new_timestamp().max(latest)
             }
+            None => latest,
+        }
+    }
+
+    // Returns the latest write on `key_expr`, pending in the batch or stored in the storage (if any)
+    async fn latest_write(
+        &self,
+        key_expr: &OwnedKeyExpr,
+    ) -> ZResult<Option<(SampleKind, StoredData)>> {
+        let batched = self
+            .batch
+            .lock()
+            .await
+            .writes
+            .iter()
+            .filter(|w| &w.key == key_expr)
+            .max_by_key(|w| w.timestamp)
+            .map(|w| {
+                (
+                    w.kind,
+                    StoredData {
+                        value: w.value.clone(),
+                        timestamp: w.timestamp,
+                    },
+                )
+            });
+        if batched.is_some() {
+            return Ok(batched);
+        }
+        // @TODO: if cache exists, read from there
+        let stripped_key = self.strip_prefix(&key_expr.into())?;
+        let mut storage = self.storage.lock().await;
+        match storage.get(stripped_key, "").await {
+            Ok(stored_data) => Ok(stored_data
+                .into_iter()
+                .max_by_key(|data| data.timestamp)
+                .map(|data| (SampleKind::Put, data))),
+            // the key is considered as not stored
+            Err(_) => Ok(None),
         }
-        true
     }
 
     async fn reply_query(&self, query: Result<zenoh::queryable::Query, flume::RecvError>) {
@@ -499,43 +1156,32 @@ impl StorageService {
             }
         };
         log::trace!("[STORAGE] Processing query on key_expr: {}", q.key_expr());
+        // make the pending writes visible to the query
+        self.flush_batch().await;
         if q.key_expr().is_wild() {
-            // resolve key expr into individual keys
-            let matching_keys = self.get_matching_keys(q.key_expr()).await;
-            let mut storage = self.storage.lock().await;
-            for key in matching_keys {
-                let stripped_key = match self.strip_prefix(&key.clone().into()) {
-                    Ok(k) => k,
-                    Err(e) => {
-                        log::error!("{}", e);
-                        // @TODO: return error when it is supported
-                        return;
-                    }
-                };
-                match storage.get(stripped_key, q.parameters()).await {
-                    Ok(stored_data) => {
-                        for entry in stored_data {
-                            let sample = Sample::new(key.clone(), entry.value)
-                                .with_timestamp(entry.timestamp);
-                            // apply outgoing interceptor on results
-                            let sample = if let Some(ref interceptor) = self.out_interceptor {
-                                interceptor(sample)
-                            } else {
-                                sample
-                            };
-                            if let Err(e) = q.reply(Ok(sample)).res().await {
-                                log::warn!(
-                                    "Storage '{}' raised an error replying a query: {}",
-                                    self.name,
-                                    e
-                                )
-                            }
-                        }
+            let paging = match Paging::from_parameters(q.parameters()) {
+                Ok(paging) => paging,
+                Err(e) => {
+                    let err_message =
+                        format!("Storage '{}' received an invalid query: {}", self.name, e);
+                    self.reply_error(&q, err_message).await;
+                    return;
+                }
+            };
+            match paging.order {
+                ReplyOrder::Key => self.reply_query_by_key(&q, paging).await,
+                ReplyOrder::Time => {
+                    // the keys have to be sorted by timestamp before replying
+                    let mut entries = self.get_matching_entries(q.key_expr()).await;
+                    entries.sort_by_key(|(_, ts)| *ts);
+                    let entries = entries.into_iter().skip(paging.offset);
+                    let entries = entries.take(paging.limit.unwrap_or(usize::MAX));
+                    let mut storage = self.storage.lock().await;
+                    for (key, _) in entries {
+                        self.reply_key(&q, &mut storage, key).await;
                     }
-                    Err(e) => log::warn!("Storage'{}' raised an error on query: {}", self.name, e),
-                };
+                }
             }
-            drop(storage);
         } else {
             let stripped_key = match self.strip_prefix(q.key_expr()) {
                 Ok(k) => k,
@@ -569,8 +1215,222 @@ impl StorageService {
                 Err(e) => {
                     let err_message =
                         format!("Storage '{}' raised an error on query: {}", self.name, e);
-                    log::warn!("{}", err_message);
-                    if let Err(e) = q.reply(Err(err_message.into())).res().await {
+                    self.reply_error(&q, err_message).await;
+                }
+            };
+        }
+    }
+
+    // Replies to a query on the admin space of the storage:
+    // with the export of its contents, or with the result of the import of the contents in the value of the query
+    async fn reply_admin_query(&self, query: Result<zenoh::queryable::Query, flume::RecvError>) {
+        let q = match query {
+            Ok(q) => q,
+            Err(e) => {
+                log::error!("Error in query: {}", e);
+                return;
+            }
+        };
+        let export_key =
+            OwnedKeyExpr::try_from(format!("{}/{}", self.admin_key, EXPORT_SUFFIX)).unwrap();
+        let import_key =
+            OwnedKeyExpr::try_from(format!("{}/{}", self.admin_key, IMPORT_SUFFIX)).unwrap();
+        // the queries are not routed through the admin space checks
+        let (read, write) = {
+            let config = self.session.config().lock();
+            let permissions = config.adminspace.permissions();
+            (permissions.read, permissions.write)
+        };
+        // only the queries on the exact keys are answered, not the wildcard crawls of the admin space
+        let reply = match q.value() {
+            None if q.key_expr().as_str() == export_key.as_str() => {
+                if read {
+                    self.export()
+                        .await
+                        .map(|dump| Sample::new(export_key, dump.to_value()))
+                } else {
+                    Err(zerror!("adminspace.permissions.read=false in configuration").into())
+                }
+            }
+            Some(value) if q.key_expr().as_str() == import_key.as_str() => {
+                if write {
+                    self.import(value)
+                        .await
+                        .map(|count| Sample::new(import_key, format!("{count} samples imported")))
+                } else {
+                    Err(zerror!("adminspace.permissions.write=false in configuration").into())
+                }
+            }
+            _ => return,
+        };
+        match reply {
+            Ok(sample) => {
+                if let Err(e) = q.reply(Ok(sample)).res().await {
+                    log::warn!(
+                        "Storage '{}' raised an error replying a query: {}",
+                        self.name,
+                        e
+                    )
+                }
+            }
+            Err(e) => {
+                let err_message =
+                    format!("Storage '{}' failed to {}: {}", self.name, q.key_expr(), e);
+                self.reply_error(&q, err_message).await;
+            }
+        }
+    }
+
+    // Exports the values and the tombstones of the storage
+    async fn export(&self) -> ZResult<StorageDump> {
+        self.flush_batch().await;
+        let mut dump = StorageDump::new(&self.key_expr);
+        // with History::All, every version is exported
+        let parameters = if self.capability.history.eq(&History::All) {
+            "_time=[..]"
+        } else {
+            ""
+        };
+        let mut storage = self.storage.lock().await;
+        for (stripped_key, _) in storage.get_all_entries().await? {
+            let key = self.get_full_key(stripped_key.clone());
+            for data in storage.get(stripped_key, parameters).await? {
+                dump.push_entry(&key, &data.value, &data.timestamp);
+            }
+        }
+        drop(storage);
+        let tombstones = self.tombstones.read().await;
+        for (key, timestamp) in tombstones.key_value_pairs() {
+            dump.push_tombstone(&key, timestamp);
+        }
+        log::debug!(
+            "Storage '{}' exported {} values and {} tombstones",
+            self.name,
+            dump.entries.len(),
+            dump.tombstones.len()
+        );
+        Ok(dump)
+    }
+
+    // Imports contents previously exported, as timestamped samples:
+    // only the ones matching the key expression of the storage and newer than the stored ones are stored
+    async fn import(&self, value: &Value) -> ZResult<usize> {
+        let samples = StorageDump::from_value(value)?.into_samples()?;
+        let mut count = 0;
+        for sample in samples {
+            if self.key_expr.includes(&sample.key_expr) {
+                self.process_sample(sample).await;
+                count += 1;
+            }
+        }
+        log::debug!("Storage '{}' imported {} samples", self.name, count);
+        Ok(count)
+    }
+
+    async fn reply_error(&self, q: &zenoh::queryable::Query, err_message: String) {
+        log::warn!("{}", err_message);
+        if let Err(e) = q.reply(Err(err_message.into())).res().await {
+            log::warn!(
+                "Storage '{}' raised an error replying a query: {}",
+                self.name,
+                e
+            )
+        }
+    }
+
+    // Replies to a wildcard query with the matching keys in order,
+    // retrieving the storage content page by page
+    async fn reply_query_by_key(&self, q: &zenoh::queryable::Query, paging: Paging) {
+        let mut after = match &paging.cursor {
+            Some(cursor) => match self.strip_prefix(&cursor.into()) {
+                Ok(cursor) => Some(cursor),
+                Err(e) => {
+                    let err_message =
+                        format!("Storage '{}' received an invalid query: {}", self.name, e);
+                    self.reply_error(q, err_message).await;
+                    return;
+                }
+            },
+            None => None,
+        };
+        let limit = paging.limit.unwrap_or(usize::MAX);
+        let (mut skipped, mut replied) = (0, 0);
+        while replied < limit {
+            let mut storage = self.storage.lock().await;
+            let page = match storage
+                .get_entries_page(after.clone(), ENTRIES_PAGE_SIZE)
+                .await
+            {
+                Ok(Some(page)) => Ok((page.len() < ENTRIES_PAGE_SIZE, page)),
+                // the storage does not page its content: retrieve it all at once
+                Ok(None) => storage.get_all_entries().await.map(|mut entries| {
+                    if let Some(after) = &after {
+                        entries.retain(|(key, _)| cmp_keys(key, after).is_gt());
+                    }
+                    entries.sort_unstable_by(|(a, _), (b, _)| cmp_keys(a, b));
+                    (true, entries)
+                }),
+                Err(e) => Err(e),
+            };
+            let (last_page, page) = match page {
+                Ok(page) => page,
+                Err(e) => {
+                    log::warn!(
+                        "Storage '{}' raised an error while retrieving keys: {}",
+                        self.name,
+                        e
+                    );
+                    return;
+                }
+            };
+            after = page.last().map(|(k, _)| k.clone());
+            for (k, _ts) in page {
+                let key = self.get_full_key(k);
+                if !q.key_expr().intersects(&key) {
+                    continue;
+                }
+                if skipped < paging.offset {
+                    skipped += 1;
+                    continue;
+                }
+                self.reply_key(q, &mut storage, key).await;
+                replied += 1;
+                if replied == limit {
+                    break;
+                }
+            }
+            if last_page {
+                break;
+            }
+        }
+    }
+
+    async fn reply_key(
+        &self,
+        q: &zenoh::queryable::Query,
+        storage: &mut Box<dyn zenoh_backend_traits::Storage>,
+        key: OwnedKeyExpr,
+    ) {
+        let stripped_key = match self.strip_prefix(&key.clone().into()) {
+            Ok(k) => k,
+            Err(e) => {
+                log::error!("{}", e);
+                // @TODO: return error when it is supported
+                return;
+            }
+        };
+        match storage.get(stripped_key, q.parameters()).await {
+            Ok(stored_data) => {
+                for entry in stored_data {
+                    let sample =
+                        Sample::new(key.clone(), entry.value).with_timestamp(entry.timestamp);
+                    // apply outgoing interceptor on results
+                    let sample = if let Some(ref interceptor) = self.out_interceptor {
+                        interceptor(sample)
+                    } else {
+                        sample
+                    };
+                    if let Err(e) = q.reply(Ok(sample)).res().await {
                         log::warn!(
                             "Storage '{}' raised an error replying a query: {}",
                             self.name,
@@ -578,24 +1438,29 @@ impl StorageService {
                         )
                     }
                 }
-            };
-        }
+            }
+            Err(e) => log::warn!("Storage'{}' raised an error on query: {}", self.name, e),
+        };
     }
 
     async fn get_matching_keys(&self, key_expr: &KeyExpr<'_>) -> Vec<OwnedKeyExpr> {
+        self.get_matching_entries(key_expr)
+            .await
+            .into_iter()
+            .map(|(k, _ts)| k)
+            .collect()
+    }
+
+    async fn get_matching_entries(&self, key_expr: &KeyExpr<'_>) -> Vec<(OwnedKeyExpr, Timestamp)> {
         let mut result = Vec::new();
         // @TODO: if cache exists, use that to get the list
         let storage = self.storage.lock().await;
         match storage.get_all_entries().await {
             Ok(entries) => {
-                for (k, _ts) in entries {
-                    // @TODO: optimize adding back the prefix (possible inspiration from https://github.com/eclipse-zenoh/zenoh/blob/0.5.0-beta.9/backends/traits/src/utils.rs#L79)
-                    let full_key = match k {
-                        Some(key) => StorageService::get_prefixed(&self.strip_prefix, &key.into()),
-                        None => self.strip_prefix.clone().unwrap(),
-                    };
+                for (k, ts) in entries {
+                    let full_key = self.get_full_key(k);
                     if key_expr.intersects(&full_key.clone()) {
-                        result.push(full_key);
+                        result.push((full_key, ts));
                     }
                 }
             }
@@ -608,6 +1473,14 @@ impl StorageService {
         result
     }
 
+    fn get_full_key(&self, key: Option<OwnedKeyExpr>) -> OwnedKeyExpr {
+        // @TODO: optimize adding back the prefix (possible inspiration from https://github.com/eclipse-zenoh/zenoh/blob/0.5.0-beta.9/backends/traits/src/utils.rs#L79)
+        match key {
+            Some(key) => StorageService::get_prefixed(&self.strip_prefix, &key.into()),
+            None => self.strip_prefix.clone().unwrap(),
+        }
+    }
+
     fn strip_prefix(&self, key_expr: &KeyExpr<'_>) -> ZResult<Option<OwnedKeyExpr>> {
         let key = match &self.strip_prefix {
             Some(prefix) => {
@@ -688,10 +1561,10 @@ fn serialize_update(update: &Update) -> String {
 }
 
 fn construct_update(data: String) -> Update {
-    let result: (String, String, String, Vec<&[u8]>) = serde_json::from_str(&data).unwrap();
+    let result: (String, String, String, Vec<Vec<u8>>) = serde_json::from_str(&data).unwrap();
     let mut payload = ZBuf::default();
     for slice in result.3 {
-        payload.push_zslice(slice.to_vec().into());
+        payload.push_zslice(slice.into());
     }
     let value = Value::new(payload).encoding(Encoding::from(result.2));
     let data = StoredData {
@@ -750,3 +1623,127 @@ impl Timed for GarbageCollectionEvent {
         log::trace!("End garbage collection of obsolete data-infos");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends_mgt::create_and_start_storage;
+    use crate::memory_backend::MemoryBackend;
+    use zenoh::prelude::Config;
+    use zenoh_backend_traits::config::VolumeConfig;
+    use zenoh_backend_traits::{Storage, Volume, VolumeInstance};
+    use zenoh_plugin_trait::Plugin;
+
+    // A memory volume merging concurrent writes by concatenating their payloads
+    struct MergeVolume(VolumeInstance);
+
+    #[async_trait]
+    impl Volume for MergeVolume {
+        fn get_admin_status(&self) -> serde_json::Value {
+            self.0.get_admin_status()
+        }
+
+        fn get_capability(&self) -> Capability {
+            self.0.get_capability()
+        }
+
+        async fn create_storage(&self, config: StorageConfig) -> ZResult<Box<dyn Storage>> {
+            self.0.create_storage(config).await
+        }
+
+        fn incoming_data_interceptor(&self) -> Option<Arc<dyn Fn(Sample) -> Sample + Send + Sync>> {
+            None
+        }
+
+        fn outgoing_data_interceptor(&self) -> Option<Arc<dyn Fn(Sample) -> Sample + Send + Sync>> {
+            None
+        }
+
+        fn merge_function(&self) -> Option<MergeFunction> {
+            Some(Arc::new(|_, stored, incoming| {
+                let stored = String::try_from(&stored.value).unwrap();
+                let incoming = String::try_from(&incoming.value).unwrap();
+                Value::from(format!("{stored}+{incoming}"))
+            }))
+        }
+    }
+
+    #[test]
+    fn test_merge() {
+        async_std::task::block_on(async {
+            zenoh_core::zasync_executor_init!();
+
+            let mut config = Config::default();
+            config
+                .insert_json5("scouting/multicast/enabled", "false")
+                .unwrap();
+            // the merged data is timestamped by the HLC of the session
+            config.insert_json5("timestamping/enabled", "true").unwrap();
+            let session = Arc::new(zenoh::open(config).res().await.unwrap());
+
+            let volume_config = VolumeConfig {
+                name: "merge".into(),
+                backend: None,
+                paths: None,
+                required: false,
+                rest: Default::default(),
+            };
+            let volume: VolumeInstance = Box::new(MergeVolume(
+                MemoryBackend::start("merge", &volume_config).unwrap(),
+            ));
+            let storage_config = StorageConfig {
+                name: "merge_test".into(),
+                key_expr: OwnedKeyExpr::try_from("merge/test/**").unwrap(),
+                complete: false,
+                strip_prefix: None,
+                volume_id: "merge".into(),
+                volume_cfg: serde_json::Value::Null,
+                garbage_collection_config: Default::default(),
+                replica_config: None,
+                batching_config: None,
+                ttl: None,
+                conflict_resolution: ConflictResolution::Merge,
+            };
+            let _storage = create_and_start_storage(
+                "@/router/test/status/plugins/storage_manager/storages/merge_test".into(),
+                storage_config,
+                &volume,
+                None,
+                None,
+                session.clone(),
+            )
+            .await
+            .unwrap();
+            async_std::task::sleep(Duration::from_secs(1)).await;
+
+            let subscriber = session
+                .declare_subscriber("merge/test/**")
+                .res()
+                .await
+                .unwrap();
+            session.put("merge/test/a", "1").res().await.unwrap();
+            session.put("merge/test/a", "2").res().await.unwrap();
+            let mut timestamps = Vec::new();
+            for _ in 0..2 {
+                let sample = subscriber.recv_async().await.unwrap();
+                timestamps.push(sample.timestamp.unwrap());
+            }
+            async_std::task::sleep(Duration::from_secs(1)).await;
+
+            let replies = session
+                .get("merge/test/a")
+                .res()
+                .await
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>();
+            assert_eq!(replies.len(), 1);
+            let sample = replies[0].sample.as_ref().unwrap();
+            assert_eq!(String::try_from(&sample.value).unwrap(), "1+2");
+            // the merged data overrides both writes
+            assert!(timestamps
+                .iter()
+                .all(|timestamp| sample.timestamp.unwrap() > *timestamp));
+        });
+    }
+}
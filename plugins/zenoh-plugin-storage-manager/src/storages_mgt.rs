@@ -43,9 +43,26 @@ pub(crate) async fn start_storage(
         // If a configuration for replica is present, we initialize a replica, else only a storage service
         // A replica contains a storage service and all metadata required for anti-entropy
         if config.replica_config.is_some() {
-            Replica::start(zenoh.clone(), store_intercept, config, &name, rx).await;
+            Replica::start(
+                zenoh.clone(),
+                store_intercept,
+                config,
+                &name,
+                &admin_key,
+                rx,
+            )
+            .await;
         } else {
-            StorageService::start(zenoh.clone(), config, &name, store_intercept, rx, None).await;
+            StorageService::start(
+                zenoh.clone(),
+                config,
+                &name,
+                &admin_key,
+                store_intercept,
+                rx,
+                None,
+            )
+            .await;
         }
     });
 
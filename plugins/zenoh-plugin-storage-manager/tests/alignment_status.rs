@@ -0,0 +1,161 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+// Test the alignment status of replicas in the admin space -
+// 1. the status reports the digest of the replica
+// 2. a full alignment with another replica is requested through the admin space, if writable
+// 3. the status reports the entries pulled from the other replica
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use async_std::task;
+use zenoh::plugins::RunningPlugin;
+use zenoh::prelude::r#async::*;
+use zenoh::prelude::Config;
+use zenoh_core::zasync_executor_init;
+use zenoh_plugin_trait::Plugin;
+
+const ENDPOINT: &str = "tcp/127.0.0.1:47461";
+const STORAGE_NAME: &str = "alignment_test";
+
+async fn put_data(session: &zenoh::Session, key_expr: &str, value: &str) {
+    println!("Putting Data ('{key_expr}': '{value}')...");
+    session.put(key_expr, value).res().await.unwrap();
+}
+
+fn plugin_status_key(session: &zenoh::Session) -> String {
+    format!("@/router/{}/status/plugins/storage-manager", session.zid())
+}
+
+fn storage_status_key(session: &zenoh::Session) -> String {
+    format!("{}/storages/{STORAGE_NAME}", plugin_status_key(session))
+}
+
+// Gets the status of the replica in the admin space of the storage manager
+fn get_replication_status(session: &zenoh::Session, storage: &RunningPlugin) -> serde_json::Value {
+    let key = storage_status_key(session);
+    let selector = Selector::try_from(key.as_str()).unwrap();
+    let responses = storage
+        .adminspace_getter(&selector, &plugin_status_key(session))
+        .unwrap();
+    assert_eq!(responses.len(), 1);
+    let status = &responses[0].value;
+    println!("Getting status on '{key}': '{status}'...");
+    status["replication"].clone()
+}
+
+async fn start_replica(
+    listen: &[&str],
+    connect: &[&str],
+) -> (zenoh::Session, zenoh::plugins::RunningPlugin) {
+    let mut config = Config::default();
+    config
+        .insert_json5("listen/endpoints", &format!("{listen:?}"))
+        .unwrap();
+    config
+        .insert_json5("connect/endpoints", &format!("{connect:?}"))
+        .unwrap();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config
+        .insert_json5(
+            "plugins/storage-manager",
+            &format!(
+                r#"{{
+                    storages: {{
+                        {STORAGE_NAME}: {{
+                            key_expr: "alignment/test/**",
+                            volume: {{
+                                id: "memory"
+                            }},
+                            replica_config: {{
+                                publication_interval: 1,
+                                propagation_delay: 100,
+                                delta: 100
+                            }}
+                        }}
+                    }}
+                }}"#
+            ),
+        )
+        .unwrap();
+
+    let runtime = zenoh::runtime::Runtime::new(config).await.unwrap();
+    let storage =
+        zenoh_plugin_storage_manager::StoragesPlugin::start("storage-manager", &runtime).unwrap();
+
+    let session = zenoh::init(runtime).res().await.unwrap();
+
+    (session, storage)
+}
+
+async fn test_alignment_status() {
+    task::block_on(async {
+        zasync_executor_init!();
+    });
+    let (session1, storage1) = start_replica(&[ENDPOINT], &[]).await;
+    let (session2, storage2) = start_replica(&[], &[ENDPOINT]).await;
+
+    sleep(Duration::from_secs(1));
+
+    put_data(&session1, "alignment/test/a", "1").await;
+    put_data(&session1, "alignment/test/b", "2").await;
+    put_data(&session1, "alignment/test/c", "3").await;
+
+    // the replicas exchange their digests
+    sleep(Duration::from_secs(3));
+
+    // expects the digest of the replica, with the intervals of the three entries
+    let status = get_replication_status(&session1, &storage1);
+    assert_ne!(status["digest"]["checksum"], 0);
+    let intervals = status["digest"]["intervals"].as_object().unwrap();
+    assert!(intervals.values().filter_map(|n| n.as_u64()).sum::<u64>() >= 1);
+    assert_eq!(status["pulled_entries"], serde_json::json!({}));
+
+    // both replicas received the samples, there is nothing to pull
+    let status = get_replication_status(&session2, &storage2);
+    assert_eq!(status["pulled_entries"], serde_json::json!({}));
+
+    // request a full alignment of the second replica with the first one
+    let replica1 = format!("{}/{STORAGE_NAME}", session1.zid());
+    let align_key = format!("{}/align/{replica1}", storage_status_key(&session2));
+    put_data(&session2, &align_key, "").await;
+
+    sleep(Duration::from_secs(1));
+
+    // expects the request to be ignored, as the admin space is not writable
+    let status = get_replication_status(&session2, &storage2);
+    assert_eq!(status["pulled_entries"], serde_json::json!({}));
+
+    session2
+        .config()
+        .insert_json5("adminspace/permissions/write", "true")
+        .unwrap();
+    put_data(&session2, &align_key, "").await;
+
+    sleep(Duration::from_secs(1));
+
+    // expects all the entries of the first replica to be pulled
+    let status = get_replication_status(&session2, &storage2);
+    assert_eq!(status["pulled_entries"][&replica1], 3);
+    assert!(status["last_alignment"].is_string());
+
+    drop(storage1);
+    drop(storage2);
+}
+
+#[test]
+fn alignment_status_test() {
+    task::block_on(async { test_alignment_status().await });
+}
@@ -0,0 +1,158 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+// Test storages writing samples by batches -
+// 1. the batched writes are visible to queries, in order
+// 2. batches are written when full, or after their maximum delay
+// 3. wildcard updates apply to the batched writes, whatever the conflict resolution
+
+use std::thread::sleep;
+
+use async_std::task;
+use zenoh::prelude::r#async::*;
+use zenoh::prelude::Config;
+use zenoh::query::Reply;
+use zenoh_core::zasync_executor_init;
+use zenoh_plugin_trait::Plugin;
+
+async fn put_data(session: &zenoh::Session, key_expr: &str, value: &str) {
+    println!("Putting Data ('{key_expr}': '{value}')...");
+    session.put(key_expr, value).res().await.unwrap();
+}
+
+async fn delete_data(session: &zenoh::Session, key_expr: &str) {
+    println!("Deleting Data '{key_expr}'...");
+    session.delete(key_expr).res().await.unwrap();
+}
+
+async fn get_data(session: &zenoh::Session, key_expr: &str) -> Vec<Sample> {
+    let replies: Vec<Reply> = session
+        .get(key_expr)
+        .res()
+        .await
+        .unwrap()
+        .into_iter()
+        .collect();
+    println!("Getting replies on '{key_expr}': '{replies:?}'...");
+    let mut samples = Vec::new();
+    for reply in replies {
+        if let Ok(sample) = reply.sample {
+            samples.push(sample);
+        }
+    }
+    samples.sort_by(|a, b| a.key_expr.as_str().cmp(b.key_expr.as_str()));
+    println!("Getting Data on '{key_expr}': '{samples:?}'...");
+    samples
+}
+
+async fn test_batched_updates() {
+    task::block_on(async {
+        zasync_executor_init!();
+    });
+    let mut config = Config::default();
+    config
+        .insert_json5(
+            "plugins/storage-manager",
+            r#"{
+                    storages: {
+                        batching_test: {
+                            key_expr: "batching/test/**",
+                            volume: {
+                                id: "memory"
+                            },
+                            batching: {
+                                max_size: 3,
+                                max_delay: 100
+                            }
+                        },
+                        batching_fww_test: {
+                            key_expr: "batching/fww/**",
+                            volume: {
+                                id: "memory"
+                            },
+                            batching: {
+                                max_size: 100,
+                                max_delay: 10000
+                            },
+                            conflict_resolution: "first_writer_wins"
+                        }
+                    }
+                }"#,
+        )
+        .unwrap();
+
+    let runtime = zenoh::runtime::Runtime::new(config).await.unwrap();
+    let storage =
+        zenoh_plugin_storage_manager::StoragesPlugin::start("storage-manager", &runtime).unwrap();
+
+    let session = zenoh::init(runtime).res().await.unwrap();
+
+    sleep(std::time::Duration::from_secs(1));
+
+    put_data(&session, "batching/test/a", "1").await;
+    put_data(&session, "batching/test/a", "2").await;
+    put_data(&session, "batching/test/b", "3").await;
+
+    sleep(std::time::Duration::from_millis(10));
+
+    // expects only the latest sample of each key
+    let data = get_data(&session, "batching/test/**").await;
+    assert_eq!(data.len(), 2);
+    assert_eq!(data[0].key_expr.as_str(), "batching/test/a");
+    assert_eq!(format!("{}", data[0].value), "2");
+    assert_eq!(data[1].key_expr.as_str(), "batching/test/b");
+    assert_eq!(format!("{}", data[1].value), "3");
+
+    put_data(&session, "batching/test/c", "4").await;
+    delete_data(&session, "batching/test/a").await;
+
+    // the batch is written after its maximum delay
+    sleep(std::time::Duration::from_millis(500));
+
+    // expects the deleted key to be removed
+    let data = get_data(&session, "batching/test/a").await;
+    assert_eq!(data.len(), 0);
+
+    // expects exactly one sample
+    let data = get_data(&session, "batching/test/c").await;
+    assert_eq!(data.len(), 1);
+    assert_eq!(format!("{}", data[0].value), "4");
+
+    for i in 0..10 {
+        put_data(&session, &format!("batching/test/d/{i}"), &i.to_string()).await;
+    }
+
+    sleep(std::time::Duration::from_millis(10));
+
+    // expects all the samples of the full batches and of the pending one
+    let data = get_data(&session, "batching/test/d/*").await;
+    assert_eq!(data.len(), 10);
+
+    put_data(&session, "batching/fww/a", "5").await;
+    put_data(&session, "batching/fww/b", "6").await;
+    delete_data(&session, "batching/fww/*").await;
+
+    sleep(std::time::Duration::from_millis(10));
+
+    // expects the batched writes to be deleted by the wildcard delete
+    let data = get_data(&session, "batching/fww/**").await;
+    assert_eq!(data.len(), 0);
+
+    drop(storage);
+}
+
+#[test]
+fn batching_test() {
+    task::block_on(async { test_batched_updates().await });
+}
@@ -0,0 +1,152 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+// Test the first-writer-wins conflict resolution -
+// 1. a later put doesn't replace the stored value
+// 2. an earlier put (received late) replaces the stored value
+// 3. a put following a deletion is stored, and is the first writer from then on
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use async_std::task;
+use zenoh::prelude::r#async::*;
+use zenoh::prelude::Config;
+use zenoh::query::Reply;
+use zenoh_core::zasync_executor_init;
+use zenoh_plugin_trait::Plugin;
+
+async fn put_data(session: &zenoh::Session, key_expr: &str, value: &str) {
+    println!("Putting Data ('{key_expr}': '{value}')...");
+    session.put(key_expr, value).res().await.unwrap();
+}
+
+async fn delete_data(session: &zenoh::Session, key_expr: &str) {
+    println!("Deleting Data '{key_expr}'...");
+    session.delete(key_expr).res().await.unwrap();
+}
+
+async fn get_data(session: &zenoh::Session, key_expr: &str) -> Vec<Sample> {
+    let replies: Vec<Reply> = session
+        .get(key_expr)
+        .res()
+        .await
+        .unwrap()
+        .into_iter()
+        .collect();
+    println!("Getting replies on '{key_expr}': '{replies:?}'...");
+    let mut samples = Vec::new();
+    for reply in replies {
+        if let Ok(sample) = reply.sample {
+            samples.push(sample);
+        }
+    }
+    println!("Getting Data on '{key_expr}': '{samples:?}'...");
+    samples
+}
+
+async fn test_first_writer_wins() {
+    task::block_on(async {
+        zasync_executor_init!();
+    });
+    let mut config = Config::default();
+    // the import of values requires a writable admin space
+    config
+        .insert_json5("adminspace/permissions/write", "true")
+        .unwrap();
+    config
+        .insert_json5(
+            "plugins/storage-manager",
+            r#"{
+                    storages: {
+                        conflict_test: {
+                            key_expr: "conflict/test/**",
+                            volume: {
+                                id: "memory"
+                            },
+                            conflict_resolution: "first_writer_wins"
+                        }
+                    }
+                }"#,
+        )
+        .unwrap();
+
+    let runtime = zenoh::runtime::Runtime::new(config).await.unwrap();
+    let storage =
+        zenoh_plugin_storage_manager::StoragesPlugin::start("storage-manager", &runtime).unwrap();
+
+    let session = zenoh::init(runtime).res().await.unwrap();
+
+    sleep(Duration::from_secs(1));
+
+    put_data(&session, "conflict/test/a", "1").await;
+    put_data(&session, "conflict/test/a", "2").await;
+
+    sleep(Duration::from_millis(10));
+
+    // expects the first value
+    let data = get_data(&session, "conflict/test/a").await;
+    assert_eq!(data.len(), 1);
+    assert_eq!(format!("{}", data[0].value), "1");
+
+    // import a value written before the stored one
+    let dump = serde_json::json!({
+        "version": 1,
+        "key_expr": "conflict/test/**",
+        "entries": [{
+            "key": "conflict/test/a",
+            "timestamp": "2022-01-17T10:42:10.418555997Z/BC779A06D7E049BD88C3FF3DB0C17FCC",
+            "encoding": "text/plain",
+            "value": "MA=="
+        }],
+        "tombstones": []
+    });
+    let replies: Vec<Reply> = session
+        .get(format!(
+            "@/router/{}/status/plugins/storage-manager/storages/conflict_test/import",
+            session.zid()
+        ))
+        .with_value(dump.to_string())
+        .res()
+        .await
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert!(replies[0].sample.is_ok());
+
+    sleep(Duration::from_millis(10));
+
+    // expects the earlier value
+    let data = get_data(&session, "conflict/test/a").await;
+    assert_eq!(data.len(), 1);
+    assert_eq!(format!("{}", data[0].value), "0");
+
+    delete_data(&session, "conflict/test/a").await;
+    put_data(&session, "conflict/test/a", "3").await;
+    put_data(&session, "conflict/test/a", "4").await;
+
+    sleep(Duration::from_millis(10));
+
+    // expects the first value since the deletion
+    let data = get_data(&session, "conflict/test/a").await;
+    assert_eq!(data.len(), 1);
+    assert_eq!(format!("{}", data[0].value), "3");
+
+    drop(storage);
+}
+
+#[test]
+fn first_writer_wins_test() {
+    task::block_on(async { test_first_writer_wins().await });
+}
@@ -0,0 +1,238 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+// Test the file backend -
+// 1. normal case, some puts and deletes
+// 2. the content is restored by a storage opening the same directory, despite a partially written record
+// 3. the log is compacted
+// 4. wildcard puts and deletes are applied and persisted by the backend
+// 5. the keys are paged in order by the backend
+// 6. the directory is locked by a single storage at a time
+
+use std::io::Write;
+use std::path::Path;
+use std::thread::sleep;
+
+use async_std::task;
+use zenoh::prelude::r#async::*;
+use zenoh::prelude::Config;
+use zenoh::query::Reply;
+use zenoh_core::zasync_executor_init;
+use zenoh_plugin_trait::Plugin;
+use zenoh_util::ZENOH_HOME_ENV_VAR;
+
+async fn put_data(session: &zenoh::Session, key_expr: &str, value: &str) {
+    println!("Putting Data ('{key_expr}': '{value}')...");
+    session.put(key_expr, value).res().await.unwrap();
+}
+
+async fn delete_data(session: &zenoh::Session, key_expr: &str) {
+    println!("Deleting Data '{key_expr}'...");
+    session.delete(key_expr).res().await.unwrap();
+}
+
+async fn get_data(session: &zenoh::Session, key_expr: &str) -> Vec<Sample> {
+    let replies: Vec<Reply> = session
+        .get(key_expr)
+        .res()
+        .await
+        .unwrap()
+        .into_iter()
+        .collect();
+    println!("Getting replies on '{key_expr}': '{replies:?}'...");
+    let mut samples = Vec::new();
+    for reply in replies {
+        if let Ok(sample) = reply.sample {
+            samples.push(sample);
+        }
+    }
+    println!("Getting Data on '{key_expr}': '{samples:?}'...");
+    samples
+}
+
+// Starts a storage on `<prefix>/**` in the `storage` directory of the file volume
+async fn start_storage(
+    base_dir: &Path,
+    prefix: &str,
+) -> (zenoh::Session, zenoh::plugins::RunningPlugin) {
+    let mut config = Config::default();
+    config
+        .insert_json5(
+            "plugins/storage-manager",
+            &format!(
+                r#"{{
+                    volumes: {{
+                        file: {{
+                            base_dir: "{}",
+                            compaction_threshold: 10
+                        }}
+                    }},
+                    storages: {{
+                        file_test: {{
+                            key_expr: "{prefix}/**",
+                            strip_prefix: "{prefix}",
+                            volume: {{
+                                id: "file",
+                                dir: "storage"
+                            }}
+                        }}
+                    }}
+                }}"#,
+                base_dir.display()
+            ),
+        )
+        .unwrap();
+
+    let runtime = zenoh::runtime::Runtime::new(config).await.unwrap();
+    let storage =
+        zenoh_plugin_storage_manager::StoragesPlugin::start("storage-manager", &runtime).unwrap();
+
+    let session = zenoh::init(runtime).res().await.unwrap();
+
+    sleep(std::time::Duration::from_secs(1));
+
+    (session, storage)
+}
+
+async fn test_file_backend() {
+    task::block_on(async {
+        zasync_executor_init!();
+    });
+    let base_dir = std::env::temp_dir().join(format!("zenoh-file-backend-{}", std::process::id()));
+    // the metadata of durable storages are saved in the zenoh home
+    std::env::set_var(ZENOH_HOME_ENV_VAR, &base_dir);
+    let log_path = base_dir.join("storage").join("log");
+
+    let (session, storage) = start_storage(&base_dir, "file/test1").await;
+
+    put_data(&session, "file/test1/a", "1").await;
+    put_data(&session, "file/test1/b", "2").await;
+    put_data(&session, "file/test1/a", "3").await;
+    delete_data(&session, "file/test1/b").await;
+
+    sleep(std::time::Duration::from_millis(10));
+
+    // expects exactly one sample
+    let data = get_data(&session, "file/test1/a").await;
+    assert_eq!(data.len(), 1);
+    assert_eq!(format!("{}", data[0].value), "3");
+
+    // expects zero sample
+    let data = get_data(&session, "file/test1/b").await;
+    assert_eq!(data.len(), 0);
+
+    drop(storage);
+    drop(session);
+    // let the storage release the lock of its directory
+    sleep(std::time::Duration::from_millis(500));
+
+    // add a partially written record at the end of the log
+    let mut log = std::fs::OpenOptions::new()
+        .append(true)
+        .open(&log_path)
+        .unwrap();
+    log.write_all(&[42, 1, 2]).unwrap();
+    drop(log);
+
+    let (session, storage) = start_storage(&base_dir, "file/test2").await;
+
+    // expects the sample stored by the previous storage
+    let data = get_data(&session, "file/test2/a").await;
+    assert_eq!(data.len(), 1);
+    assert_eq!(format!("{}", data[0].value), "3");
+    assert_eq!(data[0].key_expr.as_str(), "file/test2/a");
+
+    // expects zero sample
+    let data = get_data(&session, "file/test2/b").await;
+    assert_eq!(data.len(), 0);
+
+    let log_len = std::fs::metadata(&log_path).unwrap().len();
+    for i in 0..100 {
+        put_data(&session, "file/test2/c", &i.to_string()).await;
+    }
+
+    sleep(std::time::Duration::from_millis(100));
+
+    // expects the log to be compacted
+    assert!(std::fs::metadata(&log_path).unwrap().len() < log_len * 10);
+
+    // expects exactly one sample
+    let data = get_data(&session, "file/test2/c").await;
+    assert_eq!(data.len(), 1);
+    assert_eq!(format!("{}", data[0].value), "99");
+
+    put_data(&session, "file/test2/d", "4").await;
+    put_data(&session, "file/test2/*", "5").await;
+
+    sleep(std::time::Duration::from_millis(10));
+
+    // expects the wildcard put to update all the stored keys
+    let data = get_data(&session, "file/test2/*").await;
+    assert_eq!(data.len(), 3);
+    assert!(data.iter().all(|sample| format!("{}", sample.value) == "5"));
+
+    delete_data(&session, "file/test2/c").await;
+    delete_data(&session, "file/test2/**").await;
+    put_data(&session, "file/test2/a", "6").await;
+
+    sleep(std::time::Duration::from_millis(10));
+
+    // expects the wildcard updates applied by the backend, and still kept by the storage manager for the late writes
+    let data = get_data(&session, "file/test2/*").await;
+    assert_eq!(data.len(), 1);
+    assert_eq!(format!("{}", data[0].value), "6");
+    assert!(base_dir.join("wildcard_updates").exists());
+
+    drop(storage);
+    drop(session);
+    // let the storage release the lock of its directory
+    sleep(std::time::Duration::from_millis(500));
+
+    let (session, storage) = start_storage(&base_dir, "file/test3").await;
+
+    // expects the wildcard updates to be persisted
+    let data = get_data(&session, "file/test3/*").await;
+    assert_eq!(data.len(), 1);
+    assert_eq!(data[0].key_expr.as_str(), "file/test3/a");
+    assert_eq!(format!("{}", data[0].value), "6");
+
+    for key in ["d", "c", "b"] {
+        put_data(&session, &format!("file/test3/{key}"), key).await;
+    }
+
+    sleep(std::time::Duration::from_millis(10));
+
+    // expects the first keys after the cursor
+    let data = get_data(&session, "file/test3/*?_limit=2&_cursor=file/test3/a").await;
+    let mut keys: Vec<_> = data.iter().map(|sample| sample.key_expr.as_str()).collect();
+    keys.sort();
+    assert_eq!(keys, ["file/test3/b", "file/test3/c"]);
+
+    // expects a second storage on the same directory to fail starting
+    let (other_session, other_storage) = start_storage(&base_dir, "file/test4").await;
+    let data = get_data(&other_session, "file/test4/a").await;
+    assert_eq!(data.len(), 0);
+    drop(other_storage);
+    drop(other_session);
+
+    drop(storage);
+    drop(session);
+
+    std::fs::remove_dir_all(&base_dir).unwrap();
+}
+
+#[test]
+fn file_backend_test() {
+    task::block_on(async { test_file_backend().await });
+}
@@ -0,0 +1,142 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+// Test storages keeping the history of the values -
+// 1. all the versions are returned on `_time` queries, only the latest one otherwise
+// 2. the number of versions kept per key is bounded
+// 3. a deletion doesn't remove the previous versions
+
+use std::thread::sleep;
+
+use async_std::task;
+use zenoh::prelude::r#async::*;
+use zenoh::prelude::Config;
+use zenoh::query::Reply;
+use zenoh_core::zasync_executor_init;
+use zenoh_plugin_trait::Plugin;
+use zenoh_util::time_range::{TimeBound, TimeExpr, TimeRange};
+
+async fn put_data(session: &zenoh::Session, key_expr: &str, value: &str) {
+    println!("Putting Data ('{key_expr}': '{value}')...");
+    session.put(key_expr, value).res().await.unwrap();
+}
+
+async fn delete_data(session: &zenoh::Session, key_expr: &str) {
+    println!("Deleting Data '{key_expr}'...");
+    session.delete(key_expr).res().await.unwrap();
+}
+
+async fn get_data(session: &zenoh::Session, selector: &str) -> Vec<Sample> {
+    let replies: Vec<Reply> = session
+        .get(selector)
+        .res()
+        .await
+        .unwrap()
+        .into_iter()
+        .collect();
+    println!("Getting replies on '{selector}': '{replies:?}'...");
+    let mut samples = Vec::new();
+    for reply in replies {
+        if let Ok(sample) = reply.sample {
+            samples.push(sample);
+        }
+    }
+    samples.sort_by_key(|sample| sample.timestamp);
+    println!("Getting Data on '{selector}': '{samples:?}'...");
+    samples
+}
+
+fn values(samples: &[Sample]) -> Vec<String> {
+    samples.iter().map(|s| s.value.to_string()).collect()
+}
+
+async fn test_history() {
+    task::block_on(async {
+        zasync_executor_init!();
+    });
+    let mut config = Config::default();
+    config
+        .insert_json5(
+            "plugins/storage-manager",
+            r#"{
+                    storages: {
+                        history_test: {
+                            key_expr: "history/test/**",
+                            volume: {
+                                id: "memory",
+                                history: "all",
+                                max_versions: 3
+                            }
+                        }
+                    }
+                }"#,
+        )
+        .unwrap();
+
+    let runtime = zenoh::runtime::Runtime::new(config).await.unwrap();
+    let storage =
+        zenoh_plugin_storage_manager::StoragesPlugin::start("storage-manager", &runtime).unwrap();
+
+    let session = zenoh::init(runtime).res().await.unwrap();
+
+    sleep(std::time::Duration::from_secs(1));
+
+    for value in ["1", "2", "3", "4"] {
+        put_data(&session, "history/test/a", value).await;
+        sleep(std::time::Duration::from_millis(10));
+    }
+    put_data(&session, "history/test/b", "5").await;
+
+    sleep(std::time::Duration::from_millis(10));
+
+    // expects only the latest sample without `_time`
+    let data = get_data(&session, "history/test/a").await;
+    assert_eq!(values(&data), ["4"]);
+
+    // expects the last 3 versions with `_time`
+    let data = get_data(&session, "history/test/a?_time=[..]").await;
+    assert_eq!(values(&data), ["2", "3", "4"]);
+
+    // expects only the versions in the time range
+    let start = data[1].timestamp.unwrap().get_time().to_system_time();
+    let time_range = TimeRange(
+        TimeBound::Inclusive(TimeExpr::Fixed(start)),
+        TimeBound::Unbounded,
+    );
+    let data = get_data(&session, &format!("history/test/a?_time={time_range}")).await;
+    assert_eq!(values(&data), ["3", "4"]);
+
+    // expects the versions of all the matching keys
+    let data = get_data(&session, "history/test/*?_time=[..]").await;
+    assert_eq!(values(&data), ["2", "3", "4", "5"]);
+
+    delete_data(&session, "history/test/a").await;
+
+    sleep(std::time::Duration::from_millis(10));
+
+    // expects zero sample without `_time`
+    let data = get_data(&session, "history/test/a").await;
+    assert_eq!(data.len(), 0);
+
+    // expects the versions before the deletion, within the bound
+    let data = get_data(&session, "history/test/a?_time=[..]").await;
+    assert_eq!(values(&data), ["3", "4"]);
+
+    drop(storage);
+}
+
+#[test]
+fn history_test() {
+    task::block_on(async { test_history().await });
+}
@@ -0,0 +1,215 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+// Test the export and import of the contents of a storage through its admin space -
+// 1. the export of a storage contains its values and its tombstones
+// 2. the import of the export in another storage restores the values with their timestamps
+// 3. the export of the other storage is the same as the original one
+// 4. the import is rejected unless the admin space is writable
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use async_std::task;
+use zenoh::prelude::r#async::*;
+use zenoh::prelude::Config;
+use zenoh::query::Reply;
+use zenoh_core::zasync_executor_init;
+use zenoh_plugin_trait::Plugin;
+
+const STORAGE_NAME: &str = "import_export_test";
+
+async fn put_data(session: &zenoh::Session, key_expr: &str, value: &str) {
+    println!("Putting Data ('{key_expr}': '{value}')...");
+    session.put(key_expr, value).res().await.unwrap();
+}
+
+async fn delete_data(session: &zenoh::Session, key_expr: &str) {
+    println!("Deleting Data '{key_expr}'...");
+    session.delete(key_expr).res().await.unwrap();
+}
+
+async fn get_data(session: &zenoh::Session, key_expr: &str) -> Vec<Sample> {
+    let replies: Vec<Reply> = session
+        .get(key_expr)
+        .res()
+        .await
+        .unwrap()
+        .into_iter()
+        .collect();
+    println!("Getting replies on '{key_expr}': '{replies:?}'...");
+    let mut samples = Vec::new();
+    for reply in replies {
+        if let Ok(sample) = reply.sample {
+            samples.push(sample);
+        }
+    }
+    samples.sort_by(|a, b| a.key_expr.as_str().cmp(b.key_expr.as_str()));
+    println!("Getting Data on '{key_expr}': '{samples:?}'...");
+    samples
+}
+
+fn storage_admin_key(session: &zenoh::Session) -> String {
+    format!(
+        "@/router/{}/status/plugins/storage-manager/storages/{STORAGE_NAME}",
+        session.zid()
+    )
+}
+
+// Exports the contents of the storage, sorted by key to compare them
+async fn export(session: &zenoh::Session) -> serde_json::Value {
+    let data = get_data(session, &format!("{}/export", storage_admin_key(session))).await;
+    assert_eq!(data.len(), 1);
+    let mut dump: serde_json::Value =
+        serde_json::from_slice(&data[0].value.payload.contiguous()).unwrap();
+    for list in ["entries", "tombstones"] {
+        dump[list]
+            .as_array_mut()
+            .unwrap()
+            .sort_by(|a, b| a["key"].as_str().cmp(&b["key"].as_str()));
+    }
+    dump
+}
+
+// Imports the contents of a dump in the storage, returning the replies
+async fn import(session: &zenoh::Session, dump: &str) -> Vec<Reply> {
+    session
+        .get(format!("{}/import", storage_admin_key(session)))
+        .with_value(dump.to_string())
+        .res()
+        .await
+        .unwrap()
+        .into_iter()
+        .collect()
+}
+
+async fn start_storage() -> (zenoh::Session, zenoh::plugins::RunningPlugin) {
+    let mut config = Config::default();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config
+        .insert_json5(
+            "plugins/storage-manager",
+            &format!(
+                r#"{{
+                    storages: {{
+                        {STORAGE_NAME}: {{
+                            key_expr: "import_export/test/**",
+                            volume: {{
+                                id: "memory"
+                            }}
+                        }}
+                    }}
+                }}"#
+            ),
+        )
+        .unwrap();
+
+    let runtime = zenoh::runtime::Runtime::new(config).await.unwrap();
+    let storage =
+        zenoh_plugin_storage_manager::StoragesPlugin::start("storage-manager", &runtime).unwrap();
+
+    let session = zenoh::init(runtime).res().await.unwrap();
+
+    (session, storage)
+}
+
+async fn test_import_export() {
+    task::block_on(async {
+        zasync_executor_init!();
+    });
+    let (session1, storage1) = start_storage().await;
+    let (session2, storage2) = start_storage().await;
+
+    sleep(Duration::from_secs(1));
+
+    put_data(&session1, "import_export/test/a", "1").await;
+    put_data(&session1, "import_export/test/b", "2").await;
+    put_data(&session1, "import_export/test/c", "3").await;
+    delete_data(&session1, "import_export/test/b").await;
+
+    sleep(Duration::from_millis(100));
+
+    // expects the values and the tombstone in the export
+    let dump = export(&session1).await;
+    assert_eq!(dump["version"], 1);
+    assert_eq!(dump["key_expr"], "import_export/test/**");
+    let entries = dump["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["key"], "import_export/test/a");
+    assert_eq!(entries[1]["key"], "import_export/test/c");
+    let tombstones = dump["tombstones"].as_array().unwrap();
+    assert_eq!(tombstones.len(), 1);
+    assert_eq!(tombstones[0]["key"], "import_export/test/b");
+
+    // expects no export on a wildcard crawl of the admin space
+    let admin_crawl = format!("{}/*", storage_admin_key(&session1));
+    assert!(get_data(&session1, &admin_crawl).await.is_empty());
+
+    // import the export in the other storage, not connected to the first one
+    assert!(get_data(&session2, "import_export/test/*").await.is_empty());
+
+    // expects an error reply, as the admin space is not writable
+    let replies = import(&session2, &dump.to_string()).await;
+    assert_eq!(replies.len(), 1);
+    assert!(replies[0].sample.is_err());
+    assert!(get_data(&session2, "import_export/test/*").await.is_empty());
+
+    session2
+        .config()
+        .insert_json5("adminspace/permissions/write", "true")
+        .unwrap();
+
+    // expects no import by a wildcard query
+    let admin_crawl = format!("{}/*", storage_admin_key(&session2));
+    let replies: Vec<Reply> = session2
+        .get(&admin_crawl)
+        .with_value(dump.to_string())
+        .res()
+        .await
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert!(replies.is_empty());
+    assert!(get_data(&session2, "import_export/test/*").await.is_empty());
+
+    let replies = import(&session2, &dump.to_string()).await;
+    assert_eq!(replies.len(), 1);
+    assert!(replies[0].sample.is_ok());
+
+    // expects the same values, with the same timestamps, in both storages
+    let data1 = get_data(&session1, "import_export/test/*").await;
+    let data2 = get_data(&session2, "import_export/test/*").await;
+    assert_eq!(data2.len(), 2);
+    for (sample1, sample2) in data1.iter().zip(data2.iter()) {
+        assert_eq!(sample1.key_expr, sample2.key_expr);
+        assert_eq!(format!("{}", sample1.value), format!("{}", sample2.value));
+        assert_eq!(sample1.timestamp, sample2.timestamp);
+    }
+
+    // expects the same contents exported by both storages
+    assert_eq!(export(&session2).await, dump);
+
+    // an invalid import is replied with an error
+    let replies = import(&session2, "not a dump").await;
+    assert_eq!(replies.len(), 1);
+    assert!(replies[0].sample.is_err());
+
+    drop(storage1);
+    drop(storage2);
+}
+
+#[test]
+fn import_export_test() {
+    task::block_on(async { test_import_export().await });
+}
@@ -0,0 +1,153 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+// Test paging through the keys of a storage -
+// 1. `_limit`, `_offset` and `_cursor` when ordering by key
+// 2. `_order=time`
+// 3. invalid paging parameters
+
+use std::thread::sleep;
+
+use async_std::task;
+use zenoh::prelude::r#async::*;
+use zenoh::prelude::Config;
+use zenoh::query::Reply;
+use zenoh_core::zasync_executor_init;
+use zenoh_plugin_trait::Plugin;
+
+async fn put_data(session: &zenoh::Session, key_expr: &str, value: &str) {
+    session.put(key_expr, value).res().await.unwrap();
+}
+
+async fn get_replies(session: &zenoh::Session, selector: &str) -> Vec<Reply> {
+    let replies: Vec<Reply> = session
+        .get(selector)
+        .res()
+        .await
+        .unwrap()
+        .into_iter()
+        .collect();
+    println!("Getting replies on '{selector}': '{replies:?}'...");
+    replies
+}
+
+// Returns the sorted keys of the samples replied
+async fn get_keys(session: &zenoh::Session, selector: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+    for reply in get_replies(session, selector).await {
+        if let Ok(sample) = reply.sample {
+            keys.push(sample.key_expr.to_string());
+        }
+    }
+    keys.sort();
+    keys
+}
+
+fn keys(names: &[&str]) -> Vec<String> {
+    names.iter().map(|n| format!("paging/test/{n}")).collect()
+}
+
+async fn test_paging() {
+    task::block_on(async {
+        zasync_executor_init!();
+    });
+    let mut config = Config::default();
+    config
+        .insert_json5(
+            "plugins/storage-manager",
+            r#"{
+                    storages: {
+                        paging_test: {
+                            key_expr: "paging/test/**",
+                            volume: {
+                                id: "memory"
+                            }
+                        }
+                    }
+                }"#,
+        )
+        .unwrap();
+
+    let runtime = zenoh::runtime::Runtime::new(config).await.unwrap();
+    let storage =
+        zenoh_plugin_storage_manager::StoragesPlugin::start("storage-manager", &runtime).unwrap();
+
+    let session = zenoh::init(runtime).res().await.unwrap();
+
+    sleep(std::time::Duration::from_secs(1));
+
+    // put the keys in the reverse order of their names
+    for i in (0..10).rev() {
+        put_data(&session, &format!("paging/test/k{i}"), &i.to_string()).await;
+        sleep(std::time::Duration::from_millis(1));
+    }
+
+    sleep(std::time::Duration::from_millis(10));
+
+    let data = get_keys(&session, "paging/test/*").await;
+    assert_eq!(data.len(), 10);
+
+    let data = get_keys(&session, "paging/test/*?_limit=3").await;
+    assert_eq!(data, keys(&["k0", "k1", "k2"]));
+
+    let data = get_keys(&session, "paging/test/*?_limit=3&_cursor=paging/test/k2").await;
+    assert_eq!(data, keys(&["k3", "k4", "k5"]));
+
+    let data = get_keys(&session, "paging/test/*?_offset=8").await;
+    assert_eq!(data, keys(&["k8", "k9"]));
+
+    let data = get_keys(&session, "paging/test/*?_order=time&_limit=2").await;
+    assert_eq!(data, keys(&["k8", "k9"]));
+
+    let data = get_keys(&session, "paging/test/*?_order=time&_offset=1&_limit=2").await;
+    assert_eq!(data, keys(&["k7", "k8"]));
+
+    // expects an error reply for invalid parameters
+    for selector in [
+        "paging/test/*?_limit=abc",
+        "paging/test/*?_order=size",
+        "paging/test/*?_order=time&_cursor=paging/test/k2",
+    ] {
+        let replies = get_replies(&session, selector).await;
+        assert_eq!(replies.len(), 1);
+        assert!(replies[0].sample.is_err());
+    }
+
+    // the keys span several pages of the storage
+    for i in 0..1500 {
+        put_data(&session, &format!("paging/test/many/{i:04}"), "").await;
+    }
+
+    sleep(std::time::Duration::from_millis(100));
+
+    let data = get_keys(&session, "paging/test/many/*?_offset=1200").await;
+    assert_eq!(data.len(), 300);
+    assert_eq!(data[0], "paging/test/many/1200");
+
+    let data = get_keys(
+        &session,
+        "paging/test/many/*?_limit=10&_cursor=paging/test/many/0995",
+    )
+    .await;
+    assert_eq!(data.len(), 10);
+    assert_eq!(data[0], "paging/test/many/0996");
+    assert_eq!(data[9], "paging/test/many/1005");
+
+    drop(storage);
+}
+
+#[test]
+fn paging_test() {
+    task::block_on(async { test_paging().await });
+}
@@ -0,0 +1,172 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+// Test replicas storing samples published without timestamp -
+// 1. both replicas store the samples with the same timestamps
+// 2. deletions without timestamp are applied by both replicas
+// 3. a replica stamps the samples itself once the other one is gone
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use async_std::task;
+use zenoh::prelude::r#async::*;
+use zenoh::prelude::Config;
+use zenoh::query::Reply;
+use zenoh_core::zasync_executor_init;
+use zenoh_plugin_trait::Plugin;
+
+const ENDPOINT_1: &str = "tcp/127.0.0.1:47451";
+const ENDPOINT_2: &str = "tcp/127.0.0.1:47452";
+
+async fn put_data(session: &zenoh::Session, key_expr: &str, value: &str) {
+    println!("Putting Data ('{key_expr}': '{value}')...");
+    session.put(key_expr, value).res().await.unwrap();
+}
+
+async fn delete_data(session: &zenoh::Session, key_expr: &str) {
+    println!("Deleting Data '{key_expr}'...");
+    session.delete(key_expr).res().await.unwrap();
+}
+
+// Gets the data replied by all the replicas, without consolidation
+async fn get_replicas_data(session: &zenoh::Session, key_expr: &str) -> Vec<Sample> {
+    let replies: Vec<Reply> = session
+        .get(key_expr)
+        .consolidation(ConsolidationMode::None)
+        .res()
+        .await
+        .unwrap()
+        .into_iter()
+        .collect();
+    println!("Getting replies on '{key_expr}': '{replies:?}'...");
+    let mut samples = Vec::new();
+    for reply in replies {
+        if let Ok(sample) = reply.sample {
+            samples.push(sample);
+        }
+    }
+    samples.sort_by(|a, b| a.key_expr.as_str().cmp(b.key_expr.as_str()));
+    println!("Getting Data on '{key_expr}': '{samples:?}'...");
+    samples
+}
+
+async fn start_replica(
+    listen: &[&str],
+    connect: &[&str],
+) -> (zenoh::Session, zenoh::plugins::RunningPlugin) {
+    let mut config = Config::default();
+    config
+        .insert_json5("listen/endpoints", &format!("{listen:?}"))
+        .unwrap();
+    config
+        .insert_json5("connect/endpoints", &format!("{connect:?}"))
+        .unwrap();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config
+        .insert_json5(
+            "plugins/storage-manager",
+            r#"{
+                    storages: {
+                        replication_test: {
+                            key_expr: "replication/test/**",
+                            volume: {
+                                id: "memory"
+                            },
+                            replica_config: {
+                                publication_interval: 1,
+                                propagation_delay: 100,
+                                delta: 100
+                            }
+                        }
+                    }
+                }"#,
+        )
+        .unwrap();
+
+    let runtime = zenoh::runtime::Runtime::new(config).await.unwrap();
+    let storage =
+        zenoh_plugin_storage_manager::StoragesPlugin::start("storage-manager", &runtime).unwrap();
+
+    let session = zenoh::init(runtime).res().await.unwrap();
+
+    (session, storage)
+}
+
+async fn test_replication_without_timestamps() {
+    task::block_on(async {
+        zasync_executor_init!();
+    });
+    let (session1, storage1) = start_replica(&[ENDPOINT_1], &[]).await;
+    let (_session2, storage2) = start_replica(&[ENDPOINT_2], &[ENDPOINT_1]).await;
+
+    // a peer without timestamping, connected to both replicas
+    let mut config = Config::default();
+    config
+        .insert_json5(
+            "connect/endpoints",
+            &format!("{:?}", [ENDPOINT_1, ENDPOINT_2]),
+        )
+        .unwrap();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let publisher = zenoh::open(config).res().await.unwrap();
+
+    // the replicas discover each other by their digests
+    sleep(Duration::from_secs(3));
+
+    put_data(&publisher, "replication/test/a", "1").await;
+    put_data(&publisher, "replication/test/b", "2").await;
+    put_data(&publisher, "replication/test/a", "3").await;
+    delete_data(&publisher, "replication/test/b").await;
+    put_data(&publisher, "replication/test/c", "4").await;
+
+    sleep(Duration::from_millis(500));
+
+    // expects the same samples, with the same timestamps, in both replicas
+    let data = get_replicas_data(&publisher, "replication/test/*").await;
+    assert_eq!(data.len(), 4);
+    for replies in data.chunks(2) {
+        assert_eq!(replies[0].key_expr, replies[1].key_expr);
+        assert_eq!(
+            format!("{}", replies[0].value),
+            format!("{}", replies[1].value)
+        );
+        assert!(replies[0].timestamp.is_some());
+        assert_eq!(replies[0].timestamp, replies[1].timestamp);
+    }
+    assert_eq!(data[0].key_expr.as_str(), "replication/test/a");
+    assert_eq!(format!("{}", data[0].value), "3");
+    assert_eq!(data[2].key_expr.as_str(), "replication/test/c");
+
+    // once a replica is gone, the other one stamps the samples itself
+    drop(storage1);
+    session1.close().res().await.unwrap();
+    sleep(Duration::from_secs(4));
+
+    put_data(&publisher, "replication/test/d", "5").await;
+
+    sleep(Duration::from_millis(500));
+
+    let data = get_replicas_data(&publisher, "replication/test/d").await;
+    assert_eq!(data.len(), 1);
+    assert_eq!(format!("{}", data[0].value), "5");
+    assert!(data[0].timestamp.is_some());
+
+    drop(storage2);
+}
+
+#[test]
+fn replication_test() {
+    task::block_on(async { test_replication_without_timestamps().await });
+}
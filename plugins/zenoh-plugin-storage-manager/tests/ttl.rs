@@ -0,0 +1,132 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+// Test the expiry of values -
+// 1. values expire after the ttl of the storage
+// 2. the ttl of a sample overrides the one of the storage
+// 3. an update postpones the expiry
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use async_std::task;
+use zenoh::prelude::r#async::*;
+use zenoh::prelude::Config;
+use zenoh::query::Reply;
+use zenoh::sample::AttachmentBuilder;
+use zenoh_core::zasync_executor_init;
+use zenoh_plugin_trait::Plugin;
+
+async fn put_data(session: &zenoh::Session, key_expr: &str, value: &str, ttl: Option<&str>) {
+    println!("Putting Data ('{key_expr}': '{value}', ttl: {ttl:?})...");
+    let mut put = session.put(key_expr, value);
+    if let Some(ttl) = ttl {
+        let mut attachment = AttachmentBuilder::new();
+        attachment.insert("ttl", ttl);
+        put = put.with_attachment(attachment.build());
+    }
+    put.res().await.unwrap();
+}
+
+async fn get_data(session: &zenoh::Session, key_expr: &str) -> Vec<Sample> {
+    let replies: Vec<Reply> = session
+        .get(key_expr)
+        .res()
+        .await
+        .unwrap()
+        .into_iter()
+        .collect();
+    println!("Getting replies on '{key_expr}': '{replies:?}'...");
+    let mut samples = Vec::new();
+    for reply in replies {
+        if let Ok(sample) = reply.sample {
+            samples.push(sample);
+        }
+    }
+    println!("Getting Data on '{key_expr}': '{samples:?}'...");
+    samples
+}
+
+async fn test_ttl() {
+    task::block_on(async {
+        zasync_executor_init!();
+    });
+    let mut config = Config::default();
+    config
+        .insert_json5(
+            "plugins/storage-manager",
+            r#"{
+                    storages: {
+                        ttl_test: {
+                            key_expr: "ttl/test/**",
+                            volume: {
+                                id: "memory"
+                            },
+                            ttl: 2
+                        }
+                    }
+                }"#,
+        )
+        .unwrap();
+
+    let runtime = zenoh::runtime::Runtime::new(config).await.unwrap();
+    let storage =
+        zenoh_plugin_storage_manager::StoragesPlugin::start("storage-manager", &runtime).unwrap();
+
+    let session = zenoh::init(runtime).res().await.unwrap();
+
+    sleep(Duration::from_secs(1));
+
+    put_data(&session, "ttl/test/a", "1", None).await;
+    put_data(&session, "ttl/test/b", "2", Some("0.5")).await;
+    put_data(&session, "ttl/test/c", "3", Some("10")).await;
+    put_data(&session, "ttl/test/d", "4", None).await;
+
+    sleep(Duration::from_millis(10));
+
+    // expects all the samples
+    let data = get_data(&session, "ttl/test/*").await;
+    assert_eq!(data.len(), 4);
+
+    sleep(Duration::from_secs(1));
+
+    // expects the sample with the shortest ttl to be deleted
+    let data = get_data(&session, "ttl/test/b").await;
+    assert_eq!(data.len(), 0);
+    let data = get_data(&session, "ttl/test/a").await;
+    assert_eq!(data.len(), 1);
+
+    put_data(&session, "ttl/test/d", "5", None).await;
+
+    sleep(Duration::from_millis(1500));
+
+    // expects the samples with the ttl of the storage to be deleted, unless updated since
+    let data = get_data(&session, "ttl/test/a").await;
+    assert_eq!(data.len(), 0);
+    let data = get_data(&session, "ttl/test/d").await;
+    assert_eq!(data.len(), 1);
+    assert_eq!(format!("{}", data[0].value), "5");
+
+    // expects the sample with the longest ttl
+    let data = get_data(&session, "ttl/test/c").await;
+    assert_eq!(data.len(), 1);
+    assert_eq!(format!("{}", data[0].value), "3");
+
+    drop(storage);
+}
+
+#[test]
+fn ttl_test() {
+    task::block_on(async { test_ttl().await });
+}
@@ -14,6 +14,7 @@
 
 //! Callback handler trait.
 use crate::API_DATA_RECEPTION_CHANNEL_SIZE;
+use zenoh_result::{bail, zerror, ZResult};
 
 /// An alias for `Arc<T>`.
 pub type Dyn<T> = std::sync::Arc<T>;
@@ -80,6 +81,175 @@ impl<T: Send + Sync + 'static> IntoCallbackReceiverPair<'static, T>
     }
 }
 
+/// A handler storing the received values in a ring buffer of the given capacity.
+///
+/// When the buffer is full, the oldest value is dropped to make room for the new one,
+/// so that a slow consumer never blocks the callback.
+///
+/// # Examples
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::handlers::RingBuffer;
+/// use zenoh::prelude::r#async::*;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let subscriber = session
+///     .declare_subscriber("key/expression")
+///     .with(RingBuffer::try_new(10).unwrap())
+///     .res()
+///     .await
+///     .unwrap();
+/// while let Ok(Some(sample)) = subscriber.try_recv() {
+///     println!("Received: {:?}", sample);
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct RingBuffer {
+    capacity: usize,
+}
+
+impl RingBuffer {
+    /// Creates a ring buffer handler keeping up to `capacity` values.
+    ///
+    /// Fails if `capacity` is 0.
+    pub fn try_new(capacity: usize) -> ZResult<Self> {
+        if capacity == 0 {
+            bail!("RingBuffer capacity must be greater than 0");
+        }
+        Ok(Self { capacity })
+    }
+}
+
+impl<T: Send + 'static> IntoCallbackReceiverPair<'static, T> for RingBuffer {
+    type Receiver = RingBufferReceiver<T>;
+
+    fn into_cb_receiver_pair(self) -> (Callback<'static, T>, Self::Receiver) {
+        let ring = Dyn::new(std::sync::Mutex::new(zenoh_collections::RingBuffer::new(
+            self.capacity,
+        )));
+        let (not_empty_tx, not_empty_rx) = flume::bounded(1);
+        (
+            Dyn::new({
+                let ring = ring.clone();
+                move |t| {
+                    zlock!(ring).push_force(t);
+                    // A pending notification is enough to wake up the receiver
+                    let _ = not_empty_tx.try_send(());
+                }
+            }),
+            RingBufferReceiver {
+                ring,
+                not_empty: not_empty_rx,
+            },
+        )
+    }
+}
+
+/// A handler keeping only the latest received value.
+///
+/// It is equivalent to a [`RingBuffer`] of capacity 1.
+///
+/// # Examples
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::handlers::LatestOnly;
+/// use zenoh::prelude::r#async::*;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let subscriber = session
+///     .declare_subscriber("key/expression")
+///     .with(LatestOnly)
+///     .res()
+///     .await
+///     .unwrap();
+/// if let Ok(Some(sample)) = subscriber.try_recv() {
+///     println!("Latest: {:?}", sample);
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct LatestOnly;
+
+impl<T: Send + 'static> IntoCallbackReceiverPair<'static, T> for LatestOnly {
+    type Receiver = RingBufferReceiver<T>;
+
+    fn into_cb_receiver_pair(self) -> (Callback<'static, T>, Self::Receiver) {
+        RingBuffer { capacity: 1 }.into_cb_receiver_pair()
+    }
+}
+
+/// The receiver of the [`RingBuffer`] and [`LatestOnly`] handlers.
+///
+/// Receiving fails once the buffer is empty and the callback has been dropped,
+/// e.g. when the subscriber is undeclared or all the replies of a query have been received.
+pub struct RingBufferReceiver<T> {
+    ring: Dyn<std::sync::Mutex<zenoh_collections::RingBuffer<T>>>,
+    not_empty: flume::Receiver<()>,
+}
+
+impl<T> RingBufferReceiver<T> {
+    /// Returns the oldest value of the buffer if any, without blocking.
+    pub fn try_recv(&self) -> ZResult<Option<T>> {
+        if let Some(t) = zlock!(self.ring).pull() {
+            return Ok(Some(t));
+        }
+        match self.not_empty.try_recv() {
+            Ok(()) | Err(flume::TryRecvError::Empty) => Ok(zlock!(self.ring).pull()),
+            Err(flume::TryRecvError::Disconnected) => self.pull_disconnected().map(Some),
+        }
+    }
+
+    /// Waits for a value and returns the oldest value of the buffer.
+    pub fn recv(&self) -> ZResult<T> {
+        loop {
+            if let Some(t) = zlock!(self.ring).pull() {
+                return Ok(t);
+            }
+            if self.not_empty.recv().is_err() {
+                return self.pull_disconnected();
+            }
+        }
+    }
+
+    /// Waits for a value for at most `timeout` and returns the oldest value of the buffer if any.
+    pub fn recv_timeout(&self, timeout: std::time::Duration) -> ZResult<Option<T>> {
+        let deadline = std::time::Instant::now() + timeout;
+        loop {
+            if let Some(t) = zlock!(self.ring).pull() {
+                return Ok(Some(t));
+            }
+            match self.not_empty.recv_deadline(deadline) {
+                Ok(()) => {}
+                Err(flume::RecvTimeoutError::Timeout) => return Ok(None),
+                Err(flume::RecvTimeoutError::Disconnected) => {
+                    return self.pull_disconnected().map(Some)
+                }
+            }
+        }
+    }
+
+    /// Asynchronously waits for a value and returns the oldest value of the buffer.
+    pub async fn recv_async(&self) -> ZResult<T> {
+        loop {
+            if let Some(t) = zlock!(self.ring).pull() {
+                return Ok(t);
+            }
+            if self.not_empty.recv_async().await.is_err() {
+                return self.pull_disconnected();
+            }
+        }
+    }
+
+    fn pull_disconnected(&self) -> ZResult<T> {
+        zlock!(self.ring)
+            .pull()
+            .ok_or_else(|| zerror!("The ring buffer handler is disconnected").into())
+    }
+}
+
 /// A function that can transform a [`FnMut`]`(T)` to
 /// a [`Fn`]`(T)` with the help of a [`Mutex`](std::sync::Mutex).
 pub fn locked<T>(fnmut: impl FnMut(T)) -> impl Fn(T) {
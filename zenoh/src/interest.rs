@@ -0,0 +1,225 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Interest primitives.
+//!
+//! see [`Interest`]
+
+use crate::{prelude::*, SessionRef, Undeclarable};
+use std::future::Ready;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use zenoh_core::{AsyncResolve, Resolvable, Result as ZResult, SyncResolve};
+use zenoh_protocol::network::declare::interest::InterestId;
+
+#[derive(Debug)]
+pub(crate) struct InterestState {
+    pub(crate) id: InterestId,
+    pub(crate) key_expr: KeyExpr<'static>,
+    pub(crate) future: bool,
+    pub(crate) complete: AtomicBool,
+}
+
+/// A builder for initializing an [`Interest`].
+///
+/// # Examples
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let interest = session
+///     .declare_interest("key/expression/**")
+///     .subscribers()
+///     .res()
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+#[zenoh_macros::unstable]
+#[derive(Debug)]
+pub struct InterestBuilder<'a, 'b> {
+    pub(crate) session: SessionRef<'a>,
+    pub(crate) key_expr: ZResult<KeyExpr<'b>>,
+    pub(crate) subscribers: bool,
+    pub(crate) queryables: bool,
+    pub(crate) future: bool,
+}
+
+#[zenoh_macros::unstable]
+impl<'a, 'b> InterestBuilder<'a, 'b> {
+    /// Restrict the interest to subscribers.
+    #[inline]
+    pub fn subscribers(mut self) -> Self {
+        self.subscribers = true;
+        self.queryables = false;
+        self
+    }
+
+    /// Restrict the interest to queryables.
+    #[inline]
+    pub fn queryables(mut self) -> Self {
+        self.subscribers = false;
+        self.queryables = true;
+        self
+    }
+
+    /// Only ask for the current declarations: the session keeps on being told about
+    /// all future declarations.
+    #[inline]
+    pub fn current_only(mut self) -> Self {
+        self.future = false;
+        self
+    }
+}
+
+#[zenoh_macros::unstable]
+impl<'a> Resolvable for InterestBuilder<'a, '_> {
+    type To = ZResult<Interest<'a>>;
+}
+
+#[zenoh_macros::unstable]
+impl SyncResolve for InterestBuilder<'_, '_> {
+    #[inline]
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        let session = self.session;
+        let key_expr = self.key_expr?.into_owned();
+        session
+            .declare_interest_inner(&key_expr, self.subscribers, self.queryables, self.future)
+            .map(|state| Interest {
+                session,
+                state,
+                alive: true,
+            })
+    }
+}
+
+#[zenoh_macros::unstable]
+impl AsyncResolve for InterestBuilder<'_, '_> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+/// An interest of the [`Session`](Session) in the declarations matching a key expression.
+///
+/// Once declared, the declarations that currently match the interest are sent to the
+/// session. While an interest in future declarations is declared, the session is only
+/// told about the subscribers and queryables matching one of its interests, so that
+/// it does not receive the declarations of the whole system. Matching listeners on
+/// key expressions outside of these interests will thus not be notified.
+///
+/// `Interests` are automatically undeclared when dropped.
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let interest = session
+///     .declare_interest("key/expression/**")
+///     .res()
+///     .await
+///     .unwrap();
+/// while !interest.is_complete() {
+///     tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+/// }
+/// # }
+/// ```
+#[zenoh_macros::unstable]
+#[derive(Debug)]
+pub struct Interest<'a> {
+    pub(crate) session: SessionRef<'a>,
+    pub(crate) state: Arc<InterestState>,
+    pub(crate) alive: bool,
+}
+
+#[zenoh_macros::unstable]
+impl<'a> Interest<'a> {
+    /// Returns the [`KeyExpr`] this Interest is declared on.
+    #[inline]
+    pub fn key_expr(&self) -> &KeyExpr<'static> {
+        &self.state.key_expr
+    }
+
+    /// Whether all the declarations that matched this Interest when it was declared
+    /// have been received.
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.state.complete.load(Ordering::Acquire)
+    }
+
+    /// Undeclare an [`Interest`].
+    ///
+    /// Interests are automatically undeclared when dropped,
+    /// but you may want to use this function to handle errors or
+    /// undeclare the Interest asynchronously.
+    #[inline]
+    pub fn undeclare(self) -> impl Resolve<ZResult<()>> + 'a {
+        Undeclarable::undeclare_inner(self, ())
+    }
+}
+
+#[zenoh_macros::unstable]
+impl<'a> Undeclarable<(), InterestUndeclaration<'a>> for Interest<'a> {
+    fn undeclare_inner(self, _: ()) -> InterestUndeclaration<'a> {
+        InterestUndeclaration { interest: self }
+    }
+}
+
+/// A [`Resolvable`] returned when undeclaring an [`Interest`].
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+#[zenoh_macros::unstable]
+pub struct InterestUndeclaration<'a> {
+    interest: Interest<'a>,
+}
+
+#[zenoh_macros::unstable]
+impl Resolvable for InterestUndeclaration<'_> {
+    type To = ZResult<()>;
+}
+
+#[zenoh_macros::unstable]
+impl SyncResolve for InterestUndeclaration<'_> {
+    fn res_sync(mut self) -> <Self as Resolvable>::To {
+        self.interest.alive = false;
+        self.interest
+            .session
+            .undeclare_interest(self.interest.state.id)
+    }
+}
+
+#[zenoh_macros::unstable]
+impl<'a> AsyncResolve for InterestUndeclaration<'a> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+#[zenoh_macros::unstable]
+impl Drop for Interest<'_> {
+    fn drop(&mut self) {
+        if self.alive {
+            let _ = self.session.undeclare_interest(self.state.id);
+        }
+    }
+}
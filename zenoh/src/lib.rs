@@ -129,13 +129,25 @@ pub use session::*;
 
 pub mod key_expr;
 pub(crate) mod net;
+/// Interceptors inspecting, modifying, redirecting or dropping the messages routed through the faces of a [`Runtime`](runtime::Runtime).
+///
+/// Interceptors are created per transport by the interceptor factories registered with
+/// [`Runtime::add_interceptor_factory`](runtime::Runtime::add_interceptor_factory).
+#[zenoh_macros::unstable]
+pub mod interceptor {
+    pub use crate::net::routing::interceptor::{InterceptorFactoryTrait, InterceptorTrait};
+    pub use crate::net::routing::{RoutingContext, RoutingFace};
+}
 pub use net::runtime;
 pub mod selector;
+pub mod serialization;
 #[deprecated = "This module is now a separate crate. Use the crate directly for shorter compile-times"]
 pub use zenoh_config as config;
 pub mod handlers;
 pub mod info;
 #[cfg(feature = "unstable")]
+pub mod interest;
+#[cfg(feature = "unstable")]
 pub mod liveliness;
 pub mod plugins;
 pub mod prelude;
@@ -24,6 +24,7 @@ use {
         handlers::locked,
         handlers::DefaultHandler,
         prelude::*,
+        publication::{CongestionControl, Priority},
         subscriber::{Subscriber, SubscriberInner},
         SessionRef, Undeclarable,
     },
@@ -767,6 +768,8 @@ where
                 QueryConsolidation::default(),
                 Locality::default(),
                 self.timeout,
+                CongestionControl::Block,
+                Priority::default(),
                 None,
                 #[cfg(feature = "unstable")]
                 None,
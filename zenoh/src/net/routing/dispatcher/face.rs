@@ -13,7 +13,7 @@
 //
 use super::super::router::*;
 use super::tables::TablesLock;
-use super::{resource::*, tables};
+use super::{interests::*, resource::*, tables};
 use crate::net::primitives::{McastMux, Mux, Primitives};
 use crate::net::routing::interceptor::{InterceptorTrait, InterceptorsChain};
 use crate::KeyExpr;
@@ -24,7 +24,9 @@ use std::sync::{Arc, Weak};
 use zenoh_protocol::zenoh::RequestBody;
 use zenoh_protocol::{
     core::{ExprId, WhatAmI, ZenohId},
-    network::{Mapping, Push, Request, RequestId, Response, ResponseFinal},
+    network::{
+        declare::interest::InterestId, Mapping, Push, Request, RequestId, Response, ResponseFinal,
+    },
 };
 use zenoh_sync::get_mut_unchecked;
 use zenoh_task::TaskController;
@@ -43,6 +45,8 @@ pub struct FaceState {
     pub(crate) remote_mappings: HashMap<ExprId, Arc<Resource>>,
     pub(crate) next_qid: RequestId,
     pub(crate) pending_queries: HashMap<RequestId, Arc<Query>>,
+    pub(crate) next_interest_id: InterestId,
+    pub(crate) pending_interests: HashMap<InterestId, Arc<PendingInterest>>,
     pub(crate) mcast_group: Option<TransportMulticast>,
     pub(crate) in_interceptors: Option<Arc<InterceptorsChain>>,
     pub(crate) hat: Box<dyn Any + Send + Sync>,
@@ -72,6 +76,8 @@ impl FaceState {
             remote_mappings: HashMap::new(),
             next_qid: 0,
             pending_queries: HashMap::new(),
+            next_interest_id: 0,
+            pending_interests: HashMap::new(),
             mcast_group,
             in_interceptors,
             hat,
@@ -233,9 +239,27 @@ impl Primitives for Face {
             }
             zenoh_protocol::network::DeclareBody::DeclareToken(_m) => todo!(),
             zenoh_protocol::network::DeclareBody::UndeclareToken(_m) => todo!(),
-            zenoh_protocol::network::DeclareBody::DeclareInterest(_m) => todo!(),
-            zenoh_protocol::network::DeclareBody::FinalInterest(_m) => todo!(),
-            zenoh_protocol::network::DeclareBody::UndeclareInterest(_m) => todo!(),
+            zenoh_protocol::network::DeclareBody::DeclareInterest(m) => {
+                declare_interest(
+                    ctrl_lock.as_ref(),
+                    &self.tables,
+                    &mut self.state.clone(),
+                    m.id,
+                    &m.wire_expr,
+                    m.interest,
+                );
+            }
+            zenoh_protocol::network::DeclareBody::FinalInterest(m) => {
+                route_final_interest(&self.tables, &mut self.state.clone(), m.id);
+            }
+            zenoh_protocol::network::DeclareBody::UndeclareInterest(m) => {
+                undeclare_interest(
+                    ctrl_lock.as_ref(),
+                    &self.tables,
+                    &mut self.state.clone(),
+                    m.id,
+                );
+            }
         }
         drop(ctrl_lock);
     }
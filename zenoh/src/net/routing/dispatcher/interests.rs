@@ -0,0 +1,183 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use super::face::FaceState;
+use super::tables::TablesLock;
+use crate::net::routing::hat::HatTrait;
+use crate::net::routing::RoutingContext;
+use std::sync::Arc;
+use zenoh_protocol::core::key_expr::OwnedKeyExpr;
+use zenoh_protocol::core::WireExpr;
+use zenoh_protocol::network::declare::common::ext::WireExprType;
+use zenoh_protocol::network::declare::{
+    ext,
+    interest::{Interest, InterestId},
+    Declare, DeclareBody, DeclareInterest, FinalInterest, UndeclareInterest,
+};
+use zenoh_sync::get_mut_unchecked;
+
+/// An interest forwarded to other faces, finalized towards its source once they all replied.
+pub(crate) struct PendingInterest {
+    src_face: Arc<FaceState>,
+    src_id: InterestId,
+}
+
+impl PendingInterest {
+    pub(crate) fn new(src_face: Arc<FaceState>, src_id: InterestId) -> Self {
+        PendingInterest { src_face, src_id }
+    }
+}
+
+pub(crate) fn declare_interest(
+    hat_code: &(dyn HatTrait + Send + Sync),
+    tables: &TablesLock,
+    face: &mut Arc<FaceState>,
+    id: InterestId,
+    expr: &WireExpr,
+    interest: Interest,
+) {
+    log::debug!("Declare interest {} {}", id, face);
+    let mut wtables = zwrite!(tables.tables);
+    let prefix = match wtables.get_mapping(face, &expr.scope, expr.mapping) {
+        Some(prefix) => prefix.expr(),
+        None => {
+            log::error!("Declare interest {} with unknown scope {}!", id, expr.scope);
+            return;
+        }
+    };
+    let full_expr = prefix + expr.suffix.as_ref();
+    // An interest on the empty key expression refers to all key expressions.
+    let key_expr = if full_expr.is_empty() {
+        None
+    } else {
+        match OwnedKeyExpr::try_from(full_expr) {
+            Ok(key_expr) => Some(key_expr),
+            Err(e) => {
+                log::error!("Declare interest {} with invalid key expression: {}", id, e);
+                return;
+            }
+        }
+    };
+    hat_code.declare_interest(&mut wtables, face, id, key_expr, interest);
+}
+
+pub(crate) fn undeclare_interest(
+    hat_code: &(dyn HatTrait + Send + Sync),
+    tables: &TablesLock,
+    face: &mut Arc<FaceState>,
+    id: InterestId,
+) {
+    log::debug!("Undeclare interest {} {}", id, face);
+    let mut wtables = zwrite!(tables.tables);
+    hat_code.undeclare_interest(&mut wtables, face, id);
+}
+
+pub(crate) fn forward_interest(
+    outface: &mut Arc<FaceState>,
+    pending: &Arc<PendingInterest>,
+    key_expr: Option<&OwnedKeyExpr>,
+    interest: Interest,
+) -> InterestId {
+    let outface_mut = get_mut_unchecked(outface);
+    outface_mut.next_interest_id += 1;
+    let id = outface_mut.next_interest_id;
+    outface_mut.pending_interests.insert(id, pending.clone());
+    log::debug!(
+        "Forward interest {}:{} to {}:{}",
+        pending.src_face,
+        pending.src_id,
+        outface,
+        id
+    );
+    let wire_expr = key_expr.map_or_else(WireExpr::empty, |key_expr| {
+        WireExpr::from(key_expr.to_string())
+    });
+    outface
+        .primitives
+        .send_declare(RoutingContext::new(Declare {
+            ext_qos: ext::QoSType::declare_default(),
+            ext_tstamp: None,
+            ext_nodeid: ext::NodeIdType::default(),
+            body: DeclareBody::DeclareInterest(DeclareInterest {
+                id,
+                wire_expr,
+                interest,
+            }),
+        }));
+    id
+}
+
+pub(crate) fn undeclare_forwarded_interest(outface: &Arc<FaceState>, id: InterestId) {
+    log::debug!("Undeclare forwarded interest {}:{}", outface, id);
+    outface
+        .primitives
+        .send_declare(RoutingContext::new(Declare {
+            ext_qos: ext::QoSType::declare_default(),
+            ext_tstamp: None,
+            ext_nodeid: ext::NodeIdType::default(),
+            body: DeclareBody::UndeclareInterest(UndeclareInterest {
+                id,
+                ext_wire_expr: WireExprType::null(),
+            }),
+        }));
+}
+
+pub(crate) fn route_final_interest(tables: &TablesLock, face: &mut Arc<FaceState>, id: InterestId) {
+    let wtables = zwrite!(tables.tables);
+    let pending = get_mut_unchecked(face).pending_interests.remove(&id);
+    drop(wtables);
+    match pending {
+        Some(pending) => {
+            log::debug!(
+                "Received final interest {}:{} from {}",
+                pending.src_face,
+                id,
+                face
+            );
+            finalize_pending_interest(pending);
+        }
+        None => log::warn!("Route final interest {}:{}: Interest not found!", face, id),
+    }
+}
+
+pub(crate) fn finalize_pending_interests(tables: &TablesLock, face: &mut Arc<FaceState>) {
+    let wtables = zwrite!(tables.tables);
+    let pending = get_mut_unchecked(face)
+        .pending_interests
+        .drain()
+        .map(|(_, pending)| pending)
+        .collect::<Vec<_>>();
+    drop(wtables);
+    for pending in pending {
+        finalize_pending_interest(pending);
+    }
+}
+
+pub(crate) fn finalize_pending_interest(pending: Arc<PendingInterest>) {
+    if let Some(pending) = Arc::into_inner(pending) {
+        log::debug!(
+            "Propagate final interest {}:{}",
+            pending.src_face,
+            pending.src_id
+        );
+        pending
+            .src_face
+            .primitives
+            .send_declare(RoutingContext::new(Declare {
+                ext_qos: ext::QoSType::declare_default(),
+                ext_tstamp: None,
+                ext_nodeid: ext::NodeIdType::default(),
+                body: DeclareBody::FinalInterest(FinalInterest { id: pending.src_id }),
+            }));
+    }
+}
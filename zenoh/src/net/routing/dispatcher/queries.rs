@@ -29,6 +29,7 @@ use zenoh_protocol::{
         declare::ext,
         request::{ext::TargetType, Request, RequestId},
         response::{self, ext::ResponderIdType, Response, ResponseFinal},
+        Mapping,
     },
     zenoh::{reply::ext::ConsolidationType, Reply, RequestBody, ResponseBody},
 };
@@ -418,6 +419,22 @@ fn get_query_route(
         })
 }
 
+#[zenoh_macros::unstable]
+#[inline]
+pub(crate) fn get_local_query_route(
+    tables: &Tables,
+    res: &Option<Arc<Resource>>,
+    expr: &mut RoutingExpr,
+) -> Arc<QueryTargetQablSet> {
+    res.as_ref()
+        .and_then(|res| res.query_route(WhatAmI::Client, 0))
+        .unwrap_or_else(|| {
+            tables
+                .hat_code
+                .compute_query_route(tables, expr, 0, WhatAmI::Client)
+        })
+}
+
 #[cfg(feature = "stats")]
 macro_rules! inc_req_stats {
     (
@@ -741,6 +758,20 @@ pub(crate) fn route_send_response(
                 inc_res_stats!(query.src_face, tx, admin, body)
             }
 
+            // The remote mappings of the replying face are only updated by the
+            // declarations it sends, which are handled in order with its responses.
+            let expr = match (key_expr.scope, key_expr.mapping) {
+                (0, _) => key_expr.suffix.to_string(),
+                (scope, Mapping::Sender) => face
+                    .get_mapping(&scope, Mapping::Sender)
+                    .map(|prefix| prefix.expr() + key_expr.suffix.as_ref())
+                    .unwrap_or_default(),
+                (scope, Mapping::Receiver) => zread!(tables_ref.tables)
+                    .get_mapping(face, &scope, Mapping::Receiver)
+                    .map(|prefix| prefix.expr() + key_expr.suffix.as_ref())
+                    .unwrap_or_default(),
+            };
+
             query
                 .src_face
                 .primitives
@@ -754,7 +785,7 @@ pub(crate) fn route_send_response(
                         ext_tstamp: None,
                         ext_respid,
                     },
-                    "".to_string(), // @TODO provide the proper key expression of the response for interceptors
+                    expr,
                 ));
         }
         None => log::warn!(
@@ -12,12 +12,15 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use super::face::FaceState;
+use super::interests::finalize_pending_interests;
 pub use super::pubsub::*;
 pub use super::queries::*;
 pub use super::resource::*;
 use crate::net::routing::hat;
 use crate::net::routing::hat::HatTrait;
+use crate::net::routing::interceptor::access_control::AclStats;
 use crate::net::routing::interceptor::interceptor_factories;
+use crate::net::routing::interceptor::rate_limit::RateLimitStats;
 use crate::net::routing::interceptor::InterceptorFactory;
 use std::any::Any;
 use std::collections::HashMap;
@@ -70,6 +73,8 @@ pub struct Tables {
     pub(crate) mcast_groups: Vec<Arc<FaceState>>,
     pub(crate) mcast_faces: Vec<Arc<FaceState>>,
     pub(crate) interceptors: Vec<InterceptorFactory>,
+    pub(crate) acl_stats: Arc<AclStats>,
+    pub(crate) rate_limit_stats: Arc<RateLimitStats>,
     pub(crate) pull_caches_lock: Mutex<()>,
     pub(crate) hat: Box<dyn Any + Send + Sync>,
     pub(crate) hat_code: Arc<dyn HatTrait + Send + Sync>, // @TODO make this a Box
@@ -89,6 +94,8 @@ impl Tables {
         let queries_default_timeout =
             Duration::from_millis(unwrap_or_default!(config.queries_default_timeout()));
         let hat_code = hat::new_hat(whatami, config);
+        let acl_stats = Arc::new(AclStats::default());
+        let rate_limit_stats = Arc::new(RateLimitStats::default());
         Ok(Tables {
             zid,
             whatami,
@@ -100,7 +107,9 @@ impl Tables {
             faces: HashMap::new(),
             mcast_groups: vec![],
             mcast_faces: vec![],
-            interceptors: interceptor_factories(config)?,
+            interceptors: interceptor_factories(config, &acl_stats, &rate_limit_stats)?,
+            acl_stats,
+            rate_limit_stats,
             pull_caches_lock: Mutex::new(()),
             hat: hat_code.new_tables(router_peers_failover_brokering),
             hat_code: hat_code.into(),
@@ -174,7 +183,10 @@ pub fn close_face(tables: &TablesLock, face: &Weak<FaceState>) {
             log::debug!("Close {}", face);
             face.task_controller.terminate_all(Duration::from_secs(10));
             finalize_pending_queries(tables, &mut face);
-            zlock!(tables.ctrl_lock).close_face(tables, &mut face);
+            finalize_pending_interests(tables, &mut face);
+            let ctrl_lock = zlock!(tables.ctrl_lock);
+            ctrl_lock.close_interests(&mut zwrite!(tables.tables), &mut face);
+            ctrl_lock.close_face(tables, &mut face);
         }
         None => log::error!("Face already closed!"),
     }
@@ -86,6 +86,7 @@ fn propagate_simple_queryable(
         let current_info = face_hat!(dst_face).local_qabls.get(res);
         if (src_face.is_none() || src_face.as_ref().unwrap().id != dst_face.id)
             && (current_info.is_none() || *current_info.unwrap() != info)
+            && face_hat!(dst_face).remote_interests.matches_queryable(res)
             && (src_face.is_none()
                 || src_face.as_ref().unwrap().whatami == WhatAmI::Client
                 || dst_face.whatami == WhatAmI::Client)
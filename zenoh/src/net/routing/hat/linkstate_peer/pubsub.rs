@@ -88,6 +88,7 @@ fn propagate_simple_subscription_to(
 ) {
     if (src_face.id != dst_face.id || res.expr().starts_with(PREFIX_LIVELINESS))
         && !face_hat!(dst_face).local_subs.contains(res)
+        && face_hat!(dst_face).remote_interests.matches_subscriber(res)
         && dst_face.whatami == WhatAmI::Client
     {
         face_hat_mut!(dst_face).local_subs.insert(res.clone());
@@ -465,6 +466,11 @@ pub(super) fn pubsub_new_face(tables: &mut Tables, face: &mut Arc<FaceState>) {
 
     if face.whatami == WhatAmI::Client {
         for sub in &hat!(tables).peer_subs {
+            if face_hat!(face).local_subs.contains(sub)
+                || !face_hat!(face).remote_interests.matches_subscriber(sub)
+            {
+                continue;
+            }
             face_hat_mut!(face).local_subs.insert(sub.clone());
             let key_expr = Resource::decl_key(sub, face);
             face.primitives.send_declare(RoutingContext::with_expr(
@@ -169,6 +169,7 @@ fn propagate_simple_queryable(
         let current_info = face_hat!(dst_face).local_qabls.get(res);
         if (src_face.is_none() || src_face.as_ref().unwrap().id != dst_face.id)
             && (current_info.is_none() || *current_info.unwrap() != info)
+            && face_hat!(dst_face).remote_interests.matches_queryable(res)
             && dst_face.whatami == WhatAmI::Client
         {
             face_hat_mut!(&mut dst_face)
@@ -518,7 +519,10 @@ fn forget_client_queryable(
 pub(super) fn queries_new_face(tables: &mut Tables, face: &mut Arc<FaceState>) {
     if face.whatami == WhatAmI::Client {
         for qabl in &hat!(tables).peer_qabls {
-            if qabl.context.is_some() {
+            if qabl.context.is_some()
+                && !face_hat!(face).local_qabls.contains_key(qabl)
+                && face_hat!(face).remote_interests.matches_queryable(qabl)
+            {
                 let info = local_qabl_info(tables, qabl, face);
                 face_hat_mut!(face).local_qabls.insert(qabl.clone(), info);
                 let key_expr = Resource::decl_key(qabl, face);
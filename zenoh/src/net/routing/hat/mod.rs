@@ -17,6 +17,9 @@
 //! This module is intended for Zenoh's internal use.
 //!
 //! [Click here for Zenoh's documentation](../zenoh/index.html)
+use super::dispatcher::interests::{
+    finalize_pending_interest, forward_interest, undeclare_forwarded_interest, PendingInterest,
+};
 use super::{
     dispatcher::{
         face::{Face, FaceState},
@@ -25,13 +28,20 @@ use super::{
     router::RoutesIndexes,
 };
 use crate::runtime::Runtime;
-use std::{any::Any, sync::Arc};
+use std::{any::Any, collections::HashMap, sync::Arc};
 use zenoh_buffers::ZBuf;
 use zenoh_config::{unwrap_or_default, Config, WhatAmI};
 use zenoh_protocol::{
-    core::WireExpr,
+    core::{
+        key_expr::{keyexpr, OwnedKeyExpr},
+        WireExpr,
+    },
     network::{
-        declare::{queryable::ext::QueryableInfo, subscriber::ext::SubscriberInfo},
+        declare::{
+            interest::{Interest, InterestId},
+            queryable::ext::QueryableInfo,
+            subscriber::ext::SubscriberInfo,
+        },
         Oam,
     },
 };
@@ -47,7 +57,10 @@ zconfigurable! {
     pub static ref TREES_COMPUTATION_DELAY_MS: u64 = 100;
 }
 
-pub(crate) trait HatTrait: HatBaseTrait + HatPubSubTrait + HatQueriesTrait {}
+pub(crate) trait HatTrait:
+    HatBaseTrait + HatPubSubTrait + HatQueriesTrait + HatInterestTrait
+{
+}
 
 pub(crate) trait HatBaseTrait {
     fn as_any(&self) -> &dyn Any;
@@ -180,6 +193,200 @@ pub(crate) trait HatQueriesTrait {
     ) -> Vec<(WireExpr<'static>, ZBuf)>;
 }
 
+pub(crate) trait HatInterestTrait {
+    /// The interests declared by `face` to this node.
+    fn remote_interests_mut<'a>(&self, face: &'a mut Arc<FaceState>) -> &'a mut RemoteInterests;
+
+    /// Declares to `face` the subscriptions and/or queryables known to this node that match
+    /// its interests and were not yet declared to it.
+    fn declare_current(
+        &self,
+        tables: &mut Tables,
+        face: &mut Arc<FaceState>,
+        subscribers: bool,
+        queryables: bool,
+    );
+
+    /// The faces an interest declared by `face` should be forwarded to, so that the
+    /// declarations they hold are known before the interest is finalized.
+    fn interest_upstreams(&self, tables: &Tables, face: &FaceState) -> Vec<Arc<FaceState>>;
+
+    /// Whether the interests in future declarations are forwarded to the
+    /// [`interest_upstreams`](Self::interest_upstreams), which then only declare to this node
+    /// the subscriptions and queryables matching them.
+    fn forwards_future_interests(&self) -> bool {
+        true
+    }
+
+    /// Registers an interest declared by `face` and replies to it.
+    ///
+    /// If the interest refers to current declarations, the matching subscriptions and
+    /// queryables known to this node that were not yet declared to `face` are sent to it.
+    /// The interest is forwarded to the [`interest_upstreams`](Self::interest_upstreams),
+    /// and a `FinalInterest` is sent back once all of them replied.
+    ///
+    /// An interest in future declarations is forwarded as is if this node
+    /// [`forwards_future_interests`](Self::forwards_future_interests), and is then undeclared
+    /// from the upstreams once `face` undeclares it or closes.
+    fn declare_interest(
+        &self,
+        tables: &mut Tables,
+        face: &mut Arc<FaceState>,
+        id: InterestId,
+        key_expr: Option<OwnedKeyExpr>,
+        interest: Interest,
+    ) {
+        let future = interest.future();
+        let current = interest.current();
+        let (subscribers, queryables) = (interest.subscribers(), interest.queryables());
+        self.remote_interests_mut(face)
+            .declare(id, key_expr.clone(), interest.clone());
+        let pending = Arc::new(PendingInterest::new(face.clone(), id));
+        if current {
+            self.declare_current(tables, face, subscribers, queryables);
+        }
+        let forward_future = future && self.forwards_future_interests();
+        if current || forward_future {
+            let interest = if forward_future {
+                interest
+            } else {
+                Interest::from(interest.as_u8() & !Interest::FUTURE.as_u8())
+            };
+            let forwarded = self
+                .interest_upstreams(tables, face)
+                .into_iter()
+                .map(|mut upstream| {
+                    let upstream_id = forward_interest(
+                        &mut upstream,
+                        &pending,
+                        key_expr.as_ref(),
+                        interest.clone(),
+                    );
+                    (upstream.id, upstream_id)
+                })
+                .collect::<Vec<_>>();
+            if forward_future {
+                self.remote_interests_mut(face).forwarded(id, forwarded);
+            }
+        }
+        if !future {
+            self.remote_interests_mut(face).undeclare(id);
+        }
+        finalize_pending_interest(pending);
+    }
+
+    fn undeclare_interest(&self, tables: &mut Tables, face: &mut Arc<FaceState>, id: InterestId) {
+        let remote_interests = self.remote_interests_mut(face);
+        let filtering_subscribers = remote_interests.filtering(Interest::subscribers);
+        let filtering_queryables = remote_interests.filtering(Interest::queryables);
+        let forwarded = remote_interests.take_forwarded(id);
+        if !remote_interests.undeclare(id) {
+            log::debug!("Undeclare unknown interest {} for {}", id, face);
+        } else {
+            // Declare what was held back from the face for the kinds it no longer filters.
+            let remote_interests = self.remote_interests_mut(face);
+            let subscribers =
+                filtering_subscribers && !remote_interests.filtering(Interest::subscribers);
+            let queryables =
+                filtering_queryables && !remote_interests.filtering(Interest::queryables);
+            if subscribers || queryables {
+                self.declare_current(tables, face, subscribers, queryables);
+            }
+        }
+        undeclare_forwarded_interests(tables, forwarded);
+    }
+
+    /// Undeclares from the upstreams the interests of a closing `face` that were forwarded to them.
+    fn close_interests(&self, tables: &mut Tables, face: &mut Arc<FaceState>) {
+        let forwarded = self.remote_interests_mut(face).drain_forwarded();
+        undeclare_forwarded_interests(tables, forwarded);
+    }
+}
+
+fn undeclare_forwarded_interests(tables: &Tables, forwarded: Vec<(usize, InterestId)>) {
+    for (face_id, upstream_id) in forwarded {
+        // The upstream faces that were closed since dropped the interest.
+        if let Some(upstream) = tables.faces.get(&face_id) {
+            undeclare_forwarded_interest(upstream, upstream_id);
+        }
+    }
+}
+
+/// The interests declared by a remote face.
+///
+/// A face with no active interest in future subscriptions (resp. queryables) receives every
+/// subscription (resp. queryable) propagated to it. While it has some, it only receives
+/// the subscriptions (resp. queryables) matching one of them.
+#[derive(Default)]
+pub(crate) struct RemoteInterests {
+    interests: HashMap<InterestId, (Option<OwnedKeyExpr>, Interest)>,
+    /// The upstream faces and ids the interests in future declarations were forwarded with.
+    forwarded: HashMap<InterestId, Vec<(usize, InterestId)>>,
+}
+
+impl RemoteInterests {
+    pub(crate) fn declare(
+        &mut self,
+        id: InterestId,
+        key_expr: Option<OwnedKeyExpr>,
+        interest: Interest,
+    ) {
+        self.interests.insert(id, (key_expr, interest));
+    }
+
+    pub(crate) fn undeclare(&mut self, id: InterestId) -> bool {
+        self.interests.remove(&id).is_some()
+    }
+
+    pub(crate) fn forwarded(&mut self, id: InterestId, forwarded: Vec<(usize, InterestId)>) {
+        self.forwarded.insert(id, forwarded);
+    }
+
+    pub(crate) fn take_forwarded(&mut self, id: InterestId) -> Vec<(usize, InterestId)> {
+        self.forwarded.remove(&id).unwrap_or_default()
+    }
+
+    pub(crate) fn drain_forwarded(&mut self) -> Vec<(usize, InterestId)> {
+        self.forwarded
+            .drain()
+            .flat_map(|(_, forwarded)| forwarded)
+            .collect()
+    }
+
+    #[inline]
+    fn filtering(&self, kind: fn(&Interest) -> bool) -> bool {
+        self.interests
+            .values()
+            .any(|(_, interest)| interest.future() && kind(interest))
+    }
+
+    #[inline]
+    fn matches(&self, res: &Resource, kind: fn(&Interest) -> bool) -> bool {
+        if !self.filtering(kind) {
+            return true;
+        }
+        let expr = res.expr();
+        self.interests.values().any(|(key_expr, interest)| {
+            kind(interest)
+                && key_expr.as_ref().map_or(true, |ke| {
+                    keyexpr::new(expr.as_str())
+                        .map(|expr| ke.intersects(expr))
+                        .unwrap_or(false)
+                })
+        })
+    }
+
+    #[inline]
+    pub(crate) fn matches_subscriber(&self, res: &Resource) -> bool {
+        self.matches(res, Interest::subscribers)
+    }
+
+    #[inline]
+    pub(crate) fn matches_queryable(&self, res: &Resource) -> bool {
+        self.matches(res, Interest::queryables)
+    }
+}
+
 pub(crate) fn new_hat(whatami: WhatAmI, config: &Config) -> Box<dyn HatTrait + Send + Sync> {
     match whatami {
         WhatAmI::Client => Box::new(client::HatCode {}),
@@ -41,7 +41,7 @@ use super::{
         face::FaceState,
         tables::{NodeId, Resource, RoutingExpr, Tables, TablesLock},
     },
-    HatBaseTrait, HatTrait,
+    HatBaseTrait, HatInterestTrait, HatTrait, RemoteInterests,
 };
 use std::{
     any::Any,
@@ -367,6 +367,7 @@ struct HatFace {
     remote_subs: HashSet<Arc<Resource>>,
     local_qabls: HashMap<Arc<Resource>, QueryableInfo>,
     remote_qabls: HashSet<Arc<Resource>>,
+    remote_interests: RemoteInterests,
 }
 
 impl HatFace {
@@ -376,10 +377,51 @@ impl HatFace {
             remote_subs: HashSet::new(),
             local_qabls: HashMap::new(),
             remote_qabls: HashSet::new(),
+            remote_interests: RemoteInterests::default(),
         }
     }
 }
 
+impl HatInterestTrait for HatCode {
+    fn remote_interests_mut<'a>(&self, face: &'a mut Arc<FaceState>) -> &'a mut RemoteInterests {
+        &mut face_hat_mut!(face).remote_interests
+    }
+
+    fn declare_current(
+        &self,
+        tables: &mut Tables,
+        face: &mut Arc<FaceState>,
+        subscribers: bool,
+        queryables: bool,
+    ) {
+        if subscribers {
+            pubsub_new_face(tables, face);
+        }
+        if queryables {
+            queries_new_face(tables, face);
+        }
+    }
+
+    fn interest_upstreams(&self, tables: &Tables, face: &FaceState) -> Vec<Arc<FaceState>> {
+        // A peer only knows the declarations of the nodes it is connected to.
+        // Interests are not forwarded among peers and routers to avoid loops.
+        if face.whatami != WhatAmI::Client {
+            return vec![];
+        }
+        tables
+            .faces
+            .values()
+            .filter(|f| f.id != face.id && f.whatami != WhatAmI::Client)
+            .cloned()
+            .collect()
+    }
+
+    fn forwards_future_interests(&self) -> bool {
+        // A peer routes the data of all its clients, so it needs every declaration of the nodes it is connected to.
+        false
+    }
+}
+
 impl HatTrait for HatCode {}
 
 #[inline]
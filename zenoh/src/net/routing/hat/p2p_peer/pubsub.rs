@@ -44,6 +44,7 @@ fn propagate_simple_subscription_to(
     if (src_face.id != dst_face.id
         || (dst_face.whatami == WhatAmI::Client && res.expr().starts_with(PREFIX_LIVELINESS)))
         && !face_hat!(dst_face).local_subs.contains(res)
+        && face_hat!(dst_face).remote_interests.matches_subscriber(res)
         && (src_face.whatami == WhatAmI::Client || dst_face.whatami == WhatAmI::Client)
     {
         face_hat_mut!(dst_face).local_subs.insert(res.clone());
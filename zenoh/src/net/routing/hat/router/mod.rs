@@ -31,7 +31,7 @@ use super::{
         face::FaceState,
         tables::{NodeId, Resource, RoutingExpr, Tables, TablesLock},
     },
-    HatBaseTrait, HatTrait,
+    HatBaseTrait, HatInterestTrait, HatTrait, RemoteInterests,
 };
 use crate::{
     net::{
@@ -795,6 +795,7 @@ struct HatFace {
     remote_subs: HashSet<Arc<Resource>>,
     local_qabls: HashMap<Arc<Resource>, QueryableInfo>,
     remote_qabls: HashSet<Arc<Resource>>,
+    remote_interests: RemoteInterests,
 }
 
 impl HatFace {
@@ -805,6 +806,7 @@ impl HatFace {
             remote_subs: HashSet::new(),
             local_qabls: HashMap::new(),
             remote_qabls: HashSet::new(),
+            remote_interests: RemoteInterests::default(),
         }
     }
 }
@@ -863,6 +865,32 @@ fn get_peer(tables: &Tables, face: &Arc<FaceState>, nodeid: NodeId) -> Option<Ze
     }
 }
 
+impl HatInterestTrait for HatCode {
+    fn remote_interests_mut<'a>(&self, face: &'a mut Arc<FaceState>) -> &'a mut RemoteInterests {
+        &mut face_hat_mut!(face).remote_interests
+    }
+
+    fn declare_current(
+        &self,
+        tables: &mut Tables,
+        face: &mut Arc<FaceState>,
+        subscribers: bool,
+        queryables: bool,
+    ) {
+        if subscribers {
+            pubsub_new_face(tables, face);
+        }
+        if queryables {
+            queries_new_face(tables, face);
+        }
+    }
+
+    fn interest_upstreams(&self, _tables: &Tables, _face: &FaceState) -> Vec<Arc<FaceState>> {
+        // The link state protocol gives this node the full view of its network.
+        vec![]
+    }
+}
+
 impl HatTrait for HatCode {}
 
 #[inline]
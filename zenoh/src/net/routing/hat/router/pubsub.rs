@@ -90,6 +90,7 @@ fn propagate_simple_subscription_to(
     if (src_face.id != dst_face.id
         || (dst_face.whatami == WhatAmI::Client && res.expr().starts_with(PREFIX_LIVELINESS)))
         && !face_hat!(dst_face).local_subs.contains(res)
+        && face_hat!(dst_face).remote_interests.matches_subscriber(res)
         && if full_peer_net {
             dst_face.whatami == WhatAmI::Client
         } else {
@@ -622,6 +623,11 @@ pub(super) fn pubsub_new_face(tables: &mut Tables, face: &mut Arc<FaceState>) {
 
     if face.whatami == WhatAmI::Client {
         for sub in &hat!(tables).router_subs {
+            if face_hat!(face).local_subs.contains(sub)
+                || !face_hat!(face).remote_interests.matches_subscriber(sub)
+            {
+                continue;
+            }
             face_hat_mut!(face).local_subs.insert(sub.clone());
             let key_expr = Resource::decl_key(sub, face);
             face.primitives.send_declare(RoutingContext::with_expr(
@@ -641,6 +647,8 @@ pub(super) fn pubsub_new_face(tables: &mut Tables, face: &mut Arc<FaceState>) {
     } else if face.whatami == WhatAmI::Peer && !hat!(tables).full_net(WhatAmI::Peer) {
         for sub in &hat!(tables).router_subs {
             if sub.context.is_some()
+                && !face_hat!(face).local_subs.contains(sub)
+                && face_hat!(face).remote_interests.matches_subscriber(sub)
                 && (res_hat!(sub).router_subs.iter().any(|r| *r != tables.zid)
                     || sub.session_ctxs.values().any(|s| {
                         s.subs.is_some()
@@ -808,7 +816,9 @@ pub(super) fn pubsub_linkstate_change(tables: &mut Tables, zid: &ZenohId, links:
 
                                     face_hat_mut!(dst_face).local_subs.remove(res);
                                 }
-                            } else if HatTables::failover_brokering_to(links, ctx.face.zid) {
+                            } else if HatTables::failover_brokering_to(links, ctx.face.zid)
+                                && face_hat!(ctx.face).remote_interests.matches_subscriber(res)
+                            {
                                 let dst_face = &mut get_mut_unchecked(ctx).face;
                                 face_hat_mut!(dst_face).local_subs.insert(res.clone());
                                 let key_expr = Resource::decl_key(res, dst_face);
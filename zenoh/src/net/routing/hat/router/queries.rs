@@ -241,6 +241,7 @@ fn propagate_simple_queryable(
         let current_info = face_hat!(dst_face).local_qabls.get(res);
         if (src_face.is_none() || src_face.as_ref().unwrap().id != dst_face.id)
             && (current_info.is_none() || *current_info.unwrap() != info)
+            && face_hat!(dst_face).remote_interests.matches_queryable(res)
             && if full_peers_net {
                 dst_face.whatami == WhatAmI::Client
             } else {
@@ -752,7 +753,10 @@ fn forget_client_queryable(
 pub(super) fn queries_new_face(tables: &mut Tables, face: &mut Arc<FaceState>) {
     if face.whatami == WhatAmI::Client {
         for qabl in hat!(tables).router_qabls.iter() {
-            if qabl.context.is_some() {
+            if qabl.context.is_some()
+                && !face_hat!(face).local_qabls.contains_key(qabl)
+                && face_hat!(face).remote_interests.matches_queryable(qabl)
+            {
                 let info = local_qabl_info(tables, qabl, face);
                 face_hat_mut!(face).local_qabls.insert(qabl.clone(), info);
                 let key_expr = Resource::decl_key(qabl, face);
@@ -774,6 +778,8 @@ pub(super) fn queries_new_face(tables: &mut Tables, face: &mut Arc<FaceState>) {
     } else if face.whatami == WhatAmI::Peer && !hat!(tables).full_net(WhatAmI::Peer) {
         for qabl in hat!(tables).router_qabls.iter() {
             if qabl.context.is_some()
+                && !face_hat!(face).local_qabls.contains_key(qabl)
+                && face_hat!(face).remote_interests.matches_queryable(qabl)
                 && (res_hat!(qabl).router_qabls.keys().any(|r| *r != tables.zid)
                     || qabl.session_ctxs.values().any(|s| {
                         s.qabl.is_some()
@@ -901,7 +907,9 @@ pub(super) fn queries_linkstate_change(tables: &mut Tables, zid: &ZenohId, links
 
                                     face_hat_mut!(dst_face).local_qabls.remove(res);
                                 }
-                            } else if HatTables::failover_brokering_to(links, ctx.face.zid) {
+                            } else if HatTables::failover_brokering_to(links, ctx.face.zid)
+                                && face_hat!(ctx.face).remote_interests.matches_queryable(res)
+                            {
                                 let dst_face = &mut get_mut_unchecked(ctx).face;
                                 let info = local_qabl_info(tables, res, dst_face);
                                 face_hat_mut!(dst_face)
@@ -0,0 +1,317 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! ⚠️ WARNING ⚠️
+//!
+//! This module is intended for Zenoh's internal use.
+//!
+//! [Click here for Zenoh's documentation](../zenoh/index.html)
+
+use crate::net::routing::interceptor::*;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use zenoh_config::{AclConfig, AclConfigRule, AclMessage, InterceptorFlow, Permission};
+use zenoh_keyexpr::keyexpr;
+use zenoh_keyexpr::keyexpr_tree::impls::KeyedSetProvider;
+use zenoh_keyexpr::keyexpr_tree::{support::UnknownWildness, KeBoxTree};
+use zenoh_keyexpr::keyexpr_tree::{IKeyExprTree, IKeyExprTreeMut};
+use zenoh_protocol::network::{DeclareBody, NetworkBody};
+use zenoh_protocol::zenoh::{PushBody, RequestBody, ResponseBody};
+use zenoh_result::ZResult;
+
+const ACL_MESSAGES: [AclMessage; 6] = [
+    AclMessage::Put,
+    AclMessage::Delete,
+    AclMessage::Query,
+    AclMessage::Reply,
+    AclMessage::DeclareSubscriber,
+    AclMessage::DeclareQueryable,
+];
+
+pub(crate) fn acl_interceptor_factories(
+    config: &AclConfig,
+    stats: &Arc<AclStats>,
+) -> ZResult<Vec<InterceptorFactory>> {
+    let mut res: Vec<InterceptorFactory> = vec![];
+
+    if *config.enabled() {
+        stats.enabled.store(true, Ordering::Relaxed);
+        res.push(Box::new(AclEnforcerFactory::new(config, stats.clone())));
+    }
+
+    Ok(res)
+}
+
+/// The access control counters, exposed in the admin space.
+#[derive(Default)]
+pub(crate) struct AclStats {
+    enabled: AtomicBool,
+    denied: [AtomicUsize; ACL_MESSAGES.len()],
+}
+
+impl AclStats {
+    fn deny(&self, message: AclMessage) -> usize {
+        let index = ACL_MESSAGES.iter().position(|m| *m == message).unwrap();
+        self.denied[index].fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    pub(crate) fn report(&self) -> serde_json::Value {
+        let denied: serde_json::Map<String, serde_json::Value> = ACL_MESSAGES
+            .iter()
+            .zip(self.denied.iter())
+            .map(|(message, denied)| {
+                (
+                    serde_json::to_value(message)
+                        .ok()
+                        .and_then(|m| m.as_str().map(String::from))
+                        .unwrap_or_default(),
+                    json!(denied.load(Ordering::Relaxed)),
+                )
+            })
+            .collect();
+        json!({
+            "enabled": self.enabled.load(Ordering::Relaxed),
+            "denied": denied,
+        })
+    }
+}
+
+pub struct AclEnforcerFactory {
+    default_permission: Permission,
+    rules: Vec<AclConfigRule>,
+    stats: Arc<AclStats>,
+}
+
+impl AclEnforcerFactory {
+    pub(crate) fn new(conf: &AclConfig, stats: Arc<AclStats>) -> Self {
+        Self {
+            default_permission: *conf.default_permission(),
+            rules: conf.rules().clone(),
+            stats,
+        }
+    }
+
+    /// The rules applying to the given interfaces and authenticated user.
+    fn rules(&self, interfaces: &[String], user: Option<&String>) -> Vec<&AclConfigRule> {
+        self.rules
+            .iter()
+            .filter(|rule| {
+                rule.interfaces
+                    .as_ref()
+                    .map(|i| interfaces.iter().any(|x| i.contains(x)))
+                    .unwrap_or(true)
+            })
+            .filter(|rule| {
+                rule.usernames
+                    .as_ref()
+                    .map(|u| user.map(|x| u.contains(x)).unwrap_or(false))
+                    .unwrap_or(true)
+            })
+            .collect()
+    }
+
+    fn enforcer(
+        &self,
+        rules: &[&AclConfigRule],
+        flow: InterceptorFlow,
+    ) -> Option<Box<dyn InterceptorTrait + Send + Sync>> {
+        let rules: Vec<&AclConfigRule> = rules
+            .iter()
+            .filter(|rule| {
+                rule.flows
+                    .as_ref()
+                    .map(|flows| flows.contains(&flow))
+                    .unwrap_or(true)
+            })
+            .copied()
+            .collect();
+        if rules.is_empty() && self.default_permission == Permission::Allow {
+            return None;
+        }
+        Some(Box::new(ComputeOnMiss::new(AclEnforcer::new(
+            self.default_permission,
+            &rules,
+            self.stats.clone(),
+        ))))
+    }
+}
+
+impl InterceptorFactoryTrait for AclEnforcerFactory {
+    fn new_transport_unicast(
+        &self,
+        transport: &TransportUnicast,
+    ) -> (Option<IngressInterceptor>, Option<EgressInterceptor>) {
+        log::debug!("New access control transport unicast {:?}", transport);
+        let interfaces: Vec<String> = transport
+            .get_links()
+            .map(|links| links.into_iter().flat_map(|l| l.interfaces).collect())
+            .unwrap_or_default();
+        let user = transport.get_auth_user().ok().flatten();
+        log::debug!(
+            "New access control transport unicast interfaces: {:?} user: {:?}",
+            interfaces,
+            user
+        );
+
+        let rules = self.rules(&interfaces, user.as_ref());
+        (
+            self.enforcer(&rules, InterceptorFlow::Ingress),
+            self.enforcer(&rules, InterceptorFlow::Egress),
+        )
+    }
+
+    // Multicast transports are not authenticated: the rules restricted to some users never
+    // apply to them.
+    fn new_transport_multicast(&self, transport: &TransportMulticast) -> Option<EgressInterceptor> {
+        log::debug!("New access control transport multicast {:?}", transport);
+        let interfaces = transport
+            .get_link()
+            .map(|link| link.interfaces)
+            .unwrap_or_default();
+        self.enforcer(&self.rules(&interfaces, None), InterceptorFlow::Egress)
+    }
+
+    fn new_peer_multicast(&self, transport: &TransportMulticast) -> Option<IngressInterceptor> {
+        log::debug!("New access control peer multicast {:?}", transport);
+        let interfaces = transport
+            .get_link()
+            .map(|link| link.interfaces)
+            .unwrap_or_default();
+        self.enforcer(&self.rules(&interfaces, None), InterceptorFlow::Ingress)
+    }
+}
+
+#[derive(Default)]
+struct PermissionTrees {
+    allow: KeBoxTree<(), UnknownWildness, KeyedSetProvider>,
+    deny: KeBoxTree<(), UnknownWildness, KeyedSetProvider>,
+}
+
+pub(crate) struct AclEnforcer {
+    default_permission: Permission,
+    trees: HashMap<AclMessage, PermissionTrees>,
+    stats: Arc<AclStats>,
+}
+
+impl AclEnforcer {
+    pub fn new(
+        default_permission: Permission,
+        rules: &[&AclConfigRule],
+        stats: Arc<AclStats>,
+    ) -> Self {
+        let mut trees: HashMap<AclMessage, PermissionTrees> = HashMap::default();
+        for rule in rules {
+            for message in &rule.messages {
+                let trees = trees.entry(*message).or_default();
+                let tree = match rule.permission {
+                    Permission::Allow => &mut trees.allow,
+                    Permission::Deny => &mut trees.deny,
+                };
+                for key_expr in &rule.key_exprs {
+                    tree.insert(key_expr, ());
+                }
+            }
+        }
+        Self {
+            default_permission,
+            trees,
+            stats,
+        }
+    }
+
+    /// A message is denied if any deny rule intersects its key expression,
+    /// allowed if any allow rule includes it, and otherwise subject to the default permission.
+    fn permission(&self, message: AclMessage, key_expr: &keyexpr) -> Permission {
+        if let Some(trees) = self.trees.get(&message) {
+            if trees.deny.intersecting_nodes(key_expr).next().is_some() {
+                return Permission::Deny;
+            }
+            if trees.allow.nodes_including(key_expr).next().is_some() {
+                return Permission::Allow;
+            }
+        }
+        self.default_permission
+    }
+}
+
+fn acl_message(msg: &NetworkMessage) -> Option<AclMessage> {
+    match &msg.body {
+        NetworkBody::Push(m) => match m.payload {
+            PushBody::Put(_) => Some(AclMessage::Put),
+            PushBody::Del(_) => Some(AclMessage::Delete),
+        },
+        NetworkBody::Request(m) => match m.payload {
+            RequestBody::Query(_) => Some(AclMessage::Query),
+            RequestBody::Put(_) => Some(AclMessage::Put),
+            RequestBody::Del(_) => Some(AclMessage::Delete),
+            RequestBody::Pull(_) => None,
+        },
+        NetworkBody::Response(m) => match m.payload {
+            ResponseBody::Reply(_) | ResponseBody::Err(_) | ResponseBody::Put(_) => {
+                Some(AclMessage::Reply)
+            }
+            ResponseBody::Ack(_) => None,
+        },
+        NetworkBody::Declare(m) => match m.body {
+            DeclareBody::DeclareSubscriber(_) => Some(AclMessage::DeclareSubscriber),
+            DeclareBody::DeclareQueryable(_) => Some(AclMessage::DeclareQueryable),
+            _ => None,
+        },
+        NetworkBody::ResponseFinal(_) | NetworkBody::OAM(_) => None,
+    }
+}
+
+impl InterceptorTrait for AclEnforcer {
+    fn compute_keyexpr_cache(&self, key_expr: &KeyExpr<'_>) -> Option<Box<dyn Any + Send + Sync>> {
+        let denied: Vec<AclMessage> = ACL_MESSAGES
+            .into_iter()
+            .filter(|m| self.permission(*m, key_expr) == Permission::Deny)
+            .collect();
+        Some(Box::new(denied))
+    }
+
+    fn intercept(
+        &self,
+        ctx: RoutingContext<NetworkMessage>,
+        cache: Option<&Box<dyn Any + Send + Sync>>,
+    ) -> Option<RoutingContext<NetworkMessage>> {
+        let message = match acl_message(&ctx.msg) {
+            Some(message) => message,
+            None => return Some(ctx),
+        };
+        let denied = match cache.and_then(|c| c.downcast_ref::<Vec<AclMessage>>()) {
+            Some(denied) => denied.contains(&message),
+            None => self.default_permission == Permission::Deny,
+        };
+        if denied {
+            let count = self.stats.deny(message);
+            log::debug!(
+                "Access control denied {:?} on {:?} (in: {}, out: {}, {} such messages denied so far)",
+                message,
+                ctx.full_expr(),
+                ctx.inface()
+                    .map(|f| f.to_string())
+                    .unwrap_or("None".to_string()),
+                ctx.outface()
+                    .map(|f| f.to_string())
+                    .unwrap_or("None".to_string()),
+                count
+            );
+            return None;
+        }
+        Some(ctx)
+    }
+}
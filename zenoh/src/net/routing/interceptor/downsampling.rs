@@ -18,16 +18,22 @@
 //!
 //! [Click here for Zenoh's documentation](../zenoh/index.html)
 
+use crate::net::primitives::{DeMux, Primitives};
+use crate::net::routing::dispatcher::face::Face;
 use crate::net::routing::interceptor::*;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::{Arc, Mutex};
 use zenoh_config::{DownsamplingFlow, DownsamplingItemConf, DownsamplingRuleConf};
 use zenoh_core::zlock;
 use zenoh_keyexpr::keyexpr_tree::impls::KeyedSetProvider;
 use zenoh_keyexpr::keyexpr_tree::{support::UnknownWildness, KeBoxTree};
-use zenoh_keyexpr::keyexpr_tree::{IKeyExprTree, IKeyExprTreeMut};
-use zenoh_protocol::network::NetworkBody;
+use zenoh_keyexpr::keyexpr_tree::{
+    IKeyExprTree, IKeyExprTreeMut, IKeyExprTreeNode, IKeyExprTreeNodeMut,
+};
+use zenoh_link::Link;
+use zenoh_protocol::network::{NetworkBody, Push};
 use zenoh_result::ZResult;
+use zenoh_transport::TransportPeerEventHandler;
 
 pub(crate) fn downsampling_interceptor_factories(
     config: &Vec<DownsamplingItemConf>,
@@ -55,6 +61,32 @@ impl DownsamplingInterceptorFactory {
             flow: conf.flow,
         }
     }
+
+    fn matches_interfaces(&self, links: &[Link]) -> bool {
+        if let Some(interfaces) = &self.interfaces {
+            log::debug!(
+                "New downsampler transport config interfaces: {:?}",
+                interfaces
+            );
+            for link in links {
+                log::debug!(
+                    "New downsampler transport link interfaces: {:?}",
+                    link.interfaces
+                );
+                if !link.interfaces.iter().any(|x| interfaces.contains(x)) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    fn interceptor(&self) -> Interceptor {
+        Box::new(ComputeOnMiss::new(DownsamplingInterceptor::new(
+            self.rules.clone(),
+            self.flow,
+        )))
+    }
 }
 
 impl InterceptorFactoryTrait for DownsamplingInterceptorFactory {
@@ -63,70 +95,142 @@ impl InterceptorFactoryTrait for DownsamplingInterceptorFactory {
         transport: &TransportUnicast,
     ) -> (Option<IngressInterceptor>, Option<EgressInterceptor>) {
         log::debug!("New downsampler transport unicast {:?}", transport);
-        if let Some(interfaces) = &self.interfaces {
-            log::debug!(
-                "New downsampler transport unicast config interfaces: {:?}",
-                interfaces
-            );
-            if let Ok(links) = transport.get_links() {
-                for link in links {
-                    log::debug!(
-                        "New downsampler transport unicast link interfaces: {:?}",
-                        link.interfaces
-                    );
-                    if !link.interfaces.iter().any(|x| interfaces.contains(x)) {
-                        return (None, None);
-                    }
-                }
+        if let Ok(links) = transport.get_links() {
+            if !self.matches_interfaces(&links) {
+                return (None, None);
             }
-        };
+        }
 
         match self.flow {
-            DownsamplingFlow::Ingress => (
-                Some(Box::new(ComputeOnMiss::new(DownsamplingInterceptor::new(
-                    self.rules.clone(),
-                )))),
-                None,
-            ),
-            DownsamplingFlow::Egress => (
-                None,
-                Some(Box::new(ComputeOnMiss::new(DownsamplingInterceptor::new(
-                    self.rules.clone(),
-                )))),
-            ),
+            DownsamplingFlow::Ingress => (Some(self.interceptor()), None),
+            DownsamplingFlow::Egress => (None, Some(self.interceptor())),
         }
     }
 
-    fn new_transport_multicast(
-        &self,
-        _transport: &TransportMulticast,
-    ) -> Option<EgressInterceptor> {
-        None
+    fn new_transport_multicast(&self, transport: &TransportMulticast) -> Option<EgressInterceptor> {
+        log::debug!("New downsampler transport multicast {:?}", transport);
+        if let Ok(link) = transport.get_link() {
+            if !self.matches_interfaces(&[link]) {
+                return None;
+            }
+        }
+
+        match self.flow {
+            DownsamplingFlow::Ingress => None,
+            DownsamplingFlow::Egress => Some(self.interceptor()),
+        }
     }
 
-    fn new_peer_multicast(&self, _transport: &TransportMulticast) -> Option<IngressInterceptor> {
-        None
+    fn new_peer_multicast(&self, transport: &TransportMulticast) -> Option<IngressInterceptor> {
+        log::debug!("New downsampler peer multicast {:?}", transport);
+        if let Ok(link) = transport.get_link() {
+            if !self.matches_interfaces(&[link]) {
+                return None;
+            }
+        }
+
+        match self.flow {
+            DownsamplingFlow::Ingress => Some(self.interceptor()),
+            DownsamplingFlow::Egress => None,
+        }
     }
 }
 
 struct Timestate {
-    pub threshold: tokio::time::Duration,
     pub latest_message_timestamp: tokio::time::Instant,
+    /// The latest message dropped in the current period, to be sent at the period end.
+    pub pending: Option<Push>,
+    /// The position of the key expression in the sending order of the rule.
+    pub sent: u64,
+}
+
+struct RuleState {
+    threshold: tokio::time::Duration,
+    per_key: bool,
+    max_keys: usize,
+    keep_latest: bool,
+    /// The time state of each key expression, or of the whole rule under the empty key.
+    states: HashMap<String, Timestate>,
+    /// The key expressions of `states` from the least to the most recently sent.
+    sent: BTreeMap<u64, String>,
+    next_sent: u64,
+}
+
+impl RuleState {
+    fn new(rule: &DownsamplingRuleConf) -> Self {
+        let threshold = if rule.freq != 0.0 {
+            tokio::time::Duration::from_nanos((1. / rule.freq * NANOS_PER_SEC) as u64)
+        } else {
+            tokio::time::Duration::MAX
+        };
+        Self {
+            threshold,
+            per_key: rule.per_key,
+            max_keys: rule.max_keys.unwrap_or(DEFAULT_MAX_KEYS).max(1),
+            keep_latest: rule.keep_latest,
+            states: HashMap::default(),
+            sent: BTreeMap::default(),
+            next_sent: 0,
+        }
+    }
+
+    /// Returns the time state of the given key, tracking it if needed, along with
+    /// the message pending for the key expression evicted to make room for it.
+    fn state(&mut self, key: &str) -> (&mut Timestate, Option<Push>) {
+        let mut evicted = None;
+        if !self.states.contains_key(key) {
+            if self.states.len() >= self.max_keys {
+                // Evict the least recently sent key expression
+                if let Some((_, lru)) = self.sent.pop_first() {
+                    evicted = self.states.remove(&lru).and_then(|state| state.pending);
+                }
+            }
+            let mut latest_message_timestamp = tokio::time::Instant::now();
+            if self.threshold != tokio::time::Duration::MAX {
+                latest_message_timestamp -= self.threshold;
+            }
+            let sent = self.next_sent;
+            self.next_sent += 1;
+            self.sent.insert(sent, key.to_string());
+            self.states.insert(
+                key.to_string(),
+                Timestate {
+                    latest_message_timestamp,
+                    pending: None,
+                    sent,
+                },
+            );
+        }
+        (self.states.get_mut(key).unwrap(), evicted)
+    }
+
+    /// Marks the given key expression as the most recently sent one.
+    fn sent(&mut self, key: &str) {
+        if let Some(state) = self.states.get_mut(key) {
+            self.sent.remove(&state.sent);
+            state.sent = self.next_sent;
+            self.next_sent += 1;
+            self.sent.insert(state.sent, key.to_string());
+        }
+    }
 }
 
 pub(crate) struct DownsamplingInterceptor {
-    ke_id: Arc<Mutex<KeBoxTree<usize, UnknownWildness, KeyedSetProvider>>>,
-    ke_state: Arc<Mutex<HashMap<usize, Timestate>>>,
+    ke_id: KeBoxTree<Vec<usize>, UnknownWildness, KeyedSetProvider>,
+    rules: Arc<Vec<Mutex<RuleState>>>,
+    flow: DownsamplingFlow,
 }
 
 impl InterceptorTrait for DownsamplingInterceptor {
     fn compute_keyexpr_cache(&self, key_expr: &KeyExpr<'_>) -> Option<Box<dyn Any + Send + Sync>> {
-        let ke_id = zlock!(self.ke_id);
-        if let Some(id) = ke_id.weight_at(&key_expr.clone()) {
-            Some(Box::new(Some(*id)))
-        } else {
-            Some(Box::new(None::<usize>))
-        }
+        let id = self
+            .ke_id
+            .nodes_including(key_expr)
+            .filter_map(|node| node.weight())
+            .flatten()
+            .copied()
+            .min();
+        Some(Box::new(id))
     }
 
     fn intercept(
@@ -134,22 +238,43 @@ impl InterceptorTrait for DownsamplingInterceptor {
         ctx: RoutingContext<NetworkMessage>,
         cache: Option<&Box<dyn Any + Send + Sync>>,
     ) -> Option<RoutingContext<NetworkMessage>> {
-        if matches!(ctx.msg.body, NetworkBody::Push(_)) {
+        if let NetworkBody::Push(push) = &ctx.msg.body {
             if let Some(cache) = cache {
                 if let Some(id) = cache.downcast_ref::<Option<usize>>() {
                     if let Some(id) = id {
-                        let mut ke_state = zlock!(self.ke_state);
-                        if let Some(state) = ke_state.get_mut(id) {
-                            let timestamp = tokio::time::Instant::now();
-
-                            if timestamp - state.latest_message_timestamp >= state.threshold {
-                                state.latest_message_timestamp = timestamp;
-                                return Some(ctx);
-                            } else {
-                                return None;
+                        let mut rule = zlock!(self.rules[*id]);
+                        let key = if rule.per_key {
+                            match ctx.full_expr() {
+                                Some(expr) => expr.to_string(),
+                                None => return Some(ctx),
                             }
                         } else {
-                            log::debug!("unxpected cache ID {}", id);
+                            String::new()
+                        };
+                        let threshold = rule.threshold;
+                        let keep_latest = rule.keep_latest;
+                        let (state, evicted) = rule.state(&key);
+                        if let Some(evicted) = evicted {
+                            // Do not lose the latest message of the evicted key expression
+                            self.flush_now(&ctx, evicted);
+                        }
+                        let timestamp = tokio::time::Instant::now();
+
+                        if timestamp - state.latest_message_timestamp >= threshold {
+                            state.latest_message_timestamp = timestamp;
+                            state.pending = None;
+                            rule.sent(&key);
+                            return Some(ctx);
+                        } else {
+                            if keep_latest {
+                                let flush = state.pending.is_none();
+                                state.pending = Some(push.clone());
+                                if flush {
+                                    let deadline = state.latest_message_timestamp + threshold;
+                                    self.flush_at(&ctx, *id, key, deadline);
+                                }
+                            }
+                            return None;
                         }
                     }
                 } else {
@@ -163,31 +288,86 @@ impl InterceptorTrait for DownsamplingInterceptor {
 }
 
 const NANOS_PER_SEC: f64 = 1_000_000_000.0;
+const DEFAULT_MAX_KEYS: usize = 1024;
 
 impl DownsamplingInterceptor {
-    pub fn new(rules: Vec<DownsamplingRuleConf>) -> Self {
-        let mut ke_id = KeBoxTree::default();
-        let mut ke_state = HashMap::default();
-        for (id, rule) in rules.into_iter().enumerate() {
-            let mut threshold = tokio::time::Duration::MAX;
-            let mut latest_message_timestamp = tokio::time::Instant::now();
-            if rule.freq != 0.0 {
-                threshold =
-                    tokio::time::Duration::from_nanos((1. / rule.freq * NANOS_PER_SEC) as u64);
-                latest_message_timestamp -= threshold;
+    pub fn new(rules: Vec<DownsamplingRuleConf>, flow: DownsamplingFlow) -> Self {
+        let mut ke_id: KeBoxTree<Vec<usize>, UnknownWildness, KeyedSetProvider> =
+            KeBoxTree::default();
+        for (id, rule) in rules.iter().enumerate() {
+            // Several rules may share a key expression: keep them all, in config order
+            let node = ke_id.node_mut_or_create(&rule.key_expr);
+            match node.weight_mut() {
+                Some(ids) => ids.push(id),
+                None => {
+                    node.insert_weight(vec![id]);
+                }
             }
-            ke_id.insert(&rule.key_expr, id);
-            ke_state.insert(
-                id,
-                Timestate {
-                    threshold,
-                    latest_message_timestamp,
-                },
-            );
         }
         Self {
-            ke_id: Arc::new(Mutex::new(ke_id)),
-            ke_state: Arc::new(Mutex::new(ke_state)),
+            ke_id,
+            rules: Arc::new(
+                rules
+                    .iter()
+                    .map(|r| Mutex::new(RuleState::new(r)))
+                    .collect(),
+            ),
+            flow,
         }
     }
+
+    fn face(&self, ctx: &RoutingContext<NetworkMessage>) -> Option<Face> {
+        match self.flow {
+            DownsamplingFlow::Ingress => ctx.inface.get().cloned(),
+            DownsamplingFlow::Egress => ctx.outface.get().cloned(),
+        }
+    }
+
+    /// Sends the latest message dropped in the period of the given key at the period end.
+    fn flush_at(
+        &self,
+        ctx: &RoutingContext<NetworkMessage>,
+        id: usize,
+        key: String,
+        deadline: tokio::time::Instant,
+    ) {
+        let face = match self.face(ctx) {
+            Some(face) => face,
+            None => return,
+        };
+        let rules = self.rules.clone();
+        let flow = self.flow;
+        zenoh_runtime::ZRuntime::Net.spawn(async move {
+            tokio::time::sleep_until(deadline).await;
+            let push = zlock!(rules[id])
+                .states
+                .get_mut(&key)
+                .and_then(|state| state.pending.take());
+            if let Some(push) = push {
+                resend(&face, flow, push);
+            }
+        });
+    }
+
+    /// Sends the latest message dropped in the period of an evicted key right away.
+    fn flush_now(&self, ctx: &RoutingContext<NetworkMessage>, push: Push) {
+        if let Some(face) = self.face(ctx) {
+            let flow = self.flow;
+            zenoh_runtime::ZRuntime::Net.spawn(async move { resend(&face, flow, push) });
+        }
+    }
+}
+
+/// Sends a message dropped by a downsampler again through all the interceptors of the face,
+/// so that those following the downsampler in the chain also apply to it.
+fn resend(face: &Face, flow: DownsamplingFlow, push: Push) {
+    match flow {
+        DownsamplingFlow::Ingress => match face.state.in_interceptors.clone() {
+            Some(interceptor) => {
+                let _ = DeMux::new(face.clone(), None, interceptor).handle_message(push.into());
+            }
+            None => face.send_push(push),
+        },
+        DownsamplingFlow::Egress => face.state.primitives.send_push(push),
+    }
 }
@@ -17,20 +17,36 @@
 //! This module is intended for Zenoh's internal use.
 //!
 //! [Click here for Zenoh's documentation](../zenoh/index.html)
-use super::RoutingContext;
+pub use super::RoutingContext;
 use crate::KeyExpr;
 use std::any::Any;
+use std::sync::Arc;
 use zenoh_config::Config;
 use zenoh_protocol::network::NetworkMessage;
 use zenoh_result::ZResult;
 use zenoh_transport::{multicast::TransportMulticast, unicast::TransportUnicast};
 
+pub mod access_control;
+use crate::net::routing::interceptor::access_control::{acl_interceptor_factories, AclStats};
+
 pub mod downsampling;
 use crate::net::routing::interceptor::downsampling::downsampling_interceptor_factories;
 
-pub(crate) trait InterceptorTrait {
+pub mod payload_transformation;
+use crate::net::routing::interceptor::payload_transformation::payload_transformation_interceptor_factories;
+
+pub mod rate_limit;
+use crate::net::routing::interceptor::rate_limit::{
+    rate_limit_interceptor_factories, RateLimitStats,
+};
+
+/// An interceptor inspects, modifies or drops the messages routed through a face.
+pub trait InterceptorTrait {
+    /// Computes a value cached alongside the given key expression and handed back to
+    /// [`intercept`](Self::intercept) for the messages on this key expression.
     fn compute_keyexpr_cache(&self, key_expr: &KeyExpr<'_>) -> Option<Box<dyn Any + Send + Sync>>;
 
+    /// Intercepts a message, returning the (possibly modified) message to route, or `None` to drop it.
     fn intercept(
         &self,
         ctx: RoutingContext<NetworkMessage>,
@@ -38,28 +54,47 @@ pub(crate) trait InterceptorTrait {
     ) -> Option<RoutingContext<NetworkMessage>>;
 }
 
-pub(crate) type Interceptor = Box<dyn InterceptorTrait + Send + Sync>;
-pub(crate) type IngressInterceptor = Interceptor;
-pub(crate) type EgressInterceptor = Interceptor;
+pub type Interceptor = Box<dyn InterceptorTrait + Send + Sync>;
+pub type IngressInterceptor = Interceptor;
+pub type EgressInterceptor = Interceptor;
 
-pub(crate) trait InterceptorFactoryTrait {
+/// An interceptor factory creates the interceptors of each new transport.
+pub trait InterceptorFactoryTrait {
+    /// Creates the ingress and egress interceptors of a new unicast transport.
     fn new_transport_unicast(
         &self,
         transport: &TransportUnicast,
     ) -> (Option<IngressInterceptor>, Option<EgressInterceptor>);
+    /// Creates the egress interceptor of a new multicast transport.
     fn new_transport_multicast(&self, transport: &TransportMulticast) -> Option<EgressInterceptor>;
+    /// Creates the ingress interceptor of a new peer on a multicast transport.
     fn new_peer_multicast(&self, transport: &TransportMulticast) -> Option<IngressInterceptor>;
 }
 
-pub(crate) type InterceptorFactory = Box<dyn InterceptorFactoryTrait + Send + Sync>;
+pub type InterceptorFactory = Box<dyn InterceptorFactoryTrait + Send + Sync>;
 
-pub(crate) fn interceptor_factories(config: &Config) -> ZResult<Vec<InterceptorFactory>> {
+pub(crate) fn interceptor_factories(
+    config: &Config,
+    acl_stats: &Arc<AclStats>,
+    rate_limit_stats: &Arc<RateLimitStats>,
+) -> ZResult<Vec<InterceptorFactory>> {
     let mut res: Vec<InterceptorFactory> = vec![];
 
     // Uncomment to log the interceptors initialisation
     // res.push(Box::new(LoggerInterceptor {}));
 
+    res.extend(acl_interceptor_factories(
+        config.access_control(),
+        acl_stats,
+    )?);
     res.extend(downsampling_interceptor_factories(config.downsampling())?);
+    res.extend(rate_limit_interceptor_factories(
+        config.rate_limit(),
+        rate_limit_stats,
+    )?);
+    res.extend(payload_transformation_interceptor_factories(
+        config.payload_transformation(),
+    )?);
 
     Ok(res)
 }
@@ -116,12 +151,13 @@ impl InterceptorTrait for InterceptorsChain {
     }
 }
 
+/// Wraps an interceptor to compute its key expression cache on the fly
+/// for the messages that were not given one.
 pub(crate) struct ComputeOnMiss<T: InterceptorTrait> {
     interceptor: T,
 }
 
 impl<T: InterceptorTrait> ComputeOnMiss<T> {
-    #[allow(dead_code)]
     pub(crate) fn new(interceptor: T) -> Self {
         Self { interceptor }
     }
@@ -0,0 +1,373 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! ⚠️ WARNING ⚠️
+//!
+//! This module is intended for Zenoh's internal use.
+//!
+//! [Click here for Zenoh's documentation](../zenoh/index.html)
+
+use crate::net::routing::interceptor::*;
+use rand::{RngCore, SeedableRng};
+use secrecy::ExposeSecret;
+use std::sync::{Arc, Mutex};
+use zenoh_buffers::{buffer::SplitBuffer, ZBuf};
+use zenoh_config::{
+    InterceptorFlow, PayloadTransformationConf, PayloadTransformationRuleConf, WhatAmIMatcher,
+};
+use zenoh_core::{zcondfeat, zlock};
+use zenoh_crypto::{AeadCipher, PseudoRng};
+use zenoh_keyexpr::keyexpr_tree::impls::KeyedSetProvider;
+use zenoh_keyexpr::keyexpr_tree::{support::UnknownWildness, KeBoxTree};
+use zenoh_keyexpr::keyexpr_tree::{
+    IKeyExprTree, IKeyExprTreeMut, IKeyExprTreeNode, IKeyExprTreeNodeMut,
+};
+use zenoh_protocol::common::{iext, ZExtBody, ZExtUnknown};
+use zenoh_protocol::network::NetworkBody;
+use zenoh_protocol::zenoh::{PushBody, RequestBody, ResponseBody};
+use zenoh_result::{bail, zerror, ZResult};
+
+/// The id of the extension marking the transformed payloads.
+/// Its value holds the transformations applied to the payload, as a byte of flags,
+/// followed by the nonce the payload is encrypted with, if any.
+const TRANSFORMATION_EXT_ID: u8 = 0x0f;
+const COMPRESSED: u8 = 1;
+const ENCRYPTED: u8 = 1 << 1;
+
+pub(crate) fn payload_transformation_interceptor_factories(
+    config: &PayloadTransformationConf,
+) -> ZResult<Vec<InterceptorFactory>> {
+    let mut res: Vec<InterceptorFactory> = vec![];
+
+    if !config.rules().is_empty() {
+        res.push(Box::new(PayloadTransformationInterceptorFactory::new(
+            config,
+        )?));
+    }
+
+    Ok(res)
+}
+
+pub struct PayloadTransformationInterceptorFactory {
+    interfaces: Option<Vec<String>>,
+    edge: WhatAmIMatcher,
+    transformer: Arc<Transformer>,
+}
+
+impl PayloadTransformationInterceptorFactory {
+    pub fn new(conf: &PayloadTransformationConf) -> ZResult<Self> {
+        Ok(Self {
+            interfaces: conf.interfaces().clone(),
+            edge: conf
+                .edge()
+                .unwrap_or_else(|| WhatAmIMatcher::empty().client()),
+            transformer: Arc::new(Transformer::new(conf.rules())?),
+        })
+    }
+}
+
+impl InterceptorFactoryTrait for PayloadTransformationInterceptorFactory {
+    fn new_transport_unicast(
+        &self,
+        transport: &TransportUnicast,
+    ) -> (Option<IngressInterceptor>, Option<EgressInterceptor>) {
+        log::debug!("New payload transformer transport unicast {:?}", transport);
+        match transport.get_whatami() {
+            Ok(whatami) if self.edge.matches(whatami) => {}
+            _ => return (None, None),
+        }
+        if let Some(interfaces) = &self.interfaces {
+            if let Ok(links) = transport.get_links() {
+                for link in links {
+                    if !link.interfaces.iter().any(|x| interfaces.contains(x)) {
+                        return (None, None);
+                    }
+                }
+            }
+        };
+
+        (
+            Some(Box::new(ComputeOnMiss::new(
+                PayloadTransformationInterceptor {
+                    transformer: self.transformer.clone(),
+                    flow: InterceptorFlow::Ingress,
+                },
+            ))),
+            Some(Box::new(ComputeOnMiss::new(
+                PayloadTransformationInterceptor {
+                    transformer: self.transformer.clone(),
+                    flow: InterceptorFlow::Egress,
+                },
+            ))),
+        )
+    }
+
+    fn new_transport_multicast(
+        &self,
+        _transport: &TransportMulticast,
+    ) -> Option<EgressInterceptor> {
+        None
+    }
+
+    fn new_peer_multicast(&self, _transport: &TransportMulticast) -> Option<IngressInterceptor> {
+        None
+    }
+}
+
+struct Transformation {
+    compression: bool,
+    cipher: Option<AeadCipher>,
+}
+
+impl Transformation {
+    fn new(conf: &PayloadTransformationRuleConf) -> ZResult<Self> {
+        let cipher = match &conf.encryption_key {
+            Some(key) => {
+                let mut bytes = [0u8; AeadCipher::KEY_SIZE];
+                hex::decode_to_slice(key.expose_secret().as_str(), &mut bytes).map_err(|e| {
+                    zerror!(
+                        "Invalid encryption key: expected {} hexadecimal digits: {}",
+                        2 * AeadCipher::KEY_SIZE,
+                        e
+                    )
+                })?;
+                Some(AeadCipher::new(bytes))
+            }
+            None => None,
+        };
+        Ok(Self {
+            compression: conf.compression,
+            cipher,
+        })
+    }
+
+    fn flags(&self) -> u8 {
+        let mut flags = 0;
+        if self.compression {
+            flags |= COMPRESSED;
+        }
+        if self.cipher.is_some() {
+            flags |= ENCRYPTED;
+        }
+        flags
+    }
+
+    /// Returns the transformed payload along with the marker of the transformation.
+    fn apply(&self, payload: &ZBuf, prng: &Mutex<PseudoRng>) -> (ZBuf, ZBuf) {
+        let flags = self.flags();
+        let mut marker = vec![flags];
+        let mut bytes = payload.contiguous().into_owned();
+        if self.compression {
+            bytes = lz4_flex::block::compress_prepend_size(&bytes);
+        }
+        if let Some(cipher) = &self.cipher {
+            // a new random nonce for each payload, authenticated along with the flags
+            let mut nonce = [0u8; AeadCipher::NONCE_SIZE];
+            zlock!(prng).fill_bytes(&mut nonce);
+            bytes = cipher.encrypt(nonce, &[flags], bytes);
+            marker.extend_from_slice(&nonce);
+        }
+        (bytes.into(), marker.into())
+    }
+
+    fn revert(&self, marker: &ZBuf, payload: &ZBuf) -> ZResult<ZBuf> {
+        let marker = marker.contiguous();
+        let flags = match marker.first() {
+            Some(flags) => *flags,
+            None => bail!("Empty payload transformation marker"),
+        };
+        let mut bytes = payload.contiguous().into_owned();
+        if flags & ENCRYPTED != 0 {
+            let cipher = match &self.cipher {
+                Some(cipher) => cipher,
+                None => bail!("No encryption key configured to decrypt the payload"),
+            };
+            let nonce = match marker[1..].try_into() {
+                Ok(nonce) => nonce,
+                Err(_) => bail!(
+                    "Invalid payload encryption nonce length: {}",
+                    marker.len() - 1
+                ),
+            };
+            bytes = cipher.decrypt(nonce, &[flags], bytes)?;
+        }
+        if flags & COMPRESSED != 0 {
+            bytes = lz4_flex::block::decompress_size_prepended(&bytes)
+                .map_err(|e| zerror!("Invalid compressed payload: {}", e))?;
+        }
+        Ok(bytes.into())
+    }
+}
+
+struct Transformer {
+    ke_id: KeBoxTree<Vec<usize>, UnknownWildness, KeyedSetProvider>,
+    transformations: Vec<Transformation>,
+    prng: Mutex<PseudoRng>,
+}
+
+impl Transformer {
+    fn new(rules: &[PayloadTransformationRuleConf]) -> ZResult<Self> {
+        let mut ke_id: KeBoxTree<Vec<usize>, UnknownWildness, KeyedSetProvider> =
+            KeBoxTree::default();
+        let mut transformations = Vec::with_capacity(rules.len());
+        for (id, rule) in rules.iter().enumerate() {
+            for key_expr in &rule.key_exprs {
+                // Several rules may share a key expression: keep them all, in config order
+                let node = ke_id.node_mut_or_create(key_expr);
+                match node.weight_mut() {
+                    Some(ids) => ids.push(id),
+                    None => {
+                        node.insert_weight(vec![id]);
+                    }
+                }
+            }
+            transformations.push(Transformation::new(rule)?);
+        }
+        Ok(Self {
+            ke_id,
+            transformations,
+            prng: Mutex::new(PseudoRng::from_entropy()),
+        })
+    }
+}
+
+/// Returns the payload of the message along with the extensions carrying the transformation marker.
+/// Shared memory payloads are not transformed.
+fn payload_mut(msg: &mut NetworkMessage) -> Option<(&mut ZBuf, &mut Vec<ZExtUnknown>)> {
+    match &mut msg.body {
+        NetworkBody::Push(m) => match &mut m.payload {
+            PushBody::Put(p) if !zcondfeat!("shared-memory", p.ext_shm.is_some(), false) => {
+                Some((&mut p.payload, &mut p.ext_unknown))
+            }
+            _ => None,
+        },
+        NetworkBody::Request(m) => match &mut m.payload {
+            RequestBody::Query(q) => match &mut q.ext_body {
+                Some(b) if !zcondfeat!("shared-memory", b.ext_shm.is_some(), false) => {
+                    Some((&mut b.payload, &mut q.ext_unknown))
+                }
+                _ => None,
+            },
+            RequestBody::Put(p) if !zcondfeat!("shared-memory", p.ext_shm.is_some(), false) => {
+                Some((&mut p.payload, &mut p.ext_unknown))
+            }
+            _ => None,
+        },
+        NetworkBody::Response(m) => match &mut m.payload {
+            ResponseBody::Reply(r) if !zcondfeat!("shared-memory", r.ext_shm.is_some(), false) => {
+                Some((&mut r.payload, &mut r.ext_unknown))
+            }
+            ResponseBody::Err(e) => match &mut e.ext_body {
+                Some(b) if !zcondfeat!("shared-memory", b.ext_shm.is_some(), false) => {
+                    Some((&mut b.payload, &mut e.ext_unknown))
+                }
+                _ => None,
+            },
+            ResponseBody::Put(p) if !zcondfeat!("shared-memory", p.ext_shm.is_some(), false) => {
+                Some((&mut p.payload, &mut p.ext_unknown))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Transforms the payloads on ingress from an edge face and reverts the transformation
+/// on egress to an edge face, so that they remain opaque in between.
+pub(crate) struct PayloadTransformationInterceptor {
+    transformer: Arc<Transformer>,
+    flow: InterceptorFlow,
+}
+
+impl InterceptorTrait for PayloadTransformationInterceptor {
+    fn compute_keyexpr_cache(&self, key_expr: &KeyExpr<'_>) -> Option<Box<dyn Any + Send + Sync>> {
+        let id = self
+            .transformer
+            .ke_id
+            .nodes_including(key_expr)
+            .filter_map(|node| node.weight())
+            .flatten()
+            .copied()
+            .min();
+        Some(Box::new(id))
+    }
+
+    fn intercept(
+        &self,
+        mut ctx: RoutingContext<NetworkMessage>,
+        cache: Option<&Box<dyn Any + Send + Sync>>,
+    ) -> Option<RoutingContext<NetworkMessage>> {
+        let id = match cache.and_then(|c| c.downcast_ref::<Option<usize>>()) {
+            Some(id) => *id,
+            None => {
+                log::debug!("unexpected cache type {:?}", ctx.full_expr());
+                None
+            }
+        };
+        let full_expr = ctx.full_expr().map(String::from);
+        let (payload, exts) = match payload_mut(&mut ctx.msg) {
+            Some(payload) => payload,
+            None => return Some(ctx),
+        };
+        if self.flow == InterceptorFlow::Ingress {
+            // Edge faces cannot send transformed payloads: drop any marker they set
+            // so that their payloads are not reverted on egress.
+            let len = exts.len();
+            exts.retain(|e| iext::mid(e.id) != TRANSFORMATION_EXT_ID);
+            if exts.len() != len {
+                log::debug!("Dropped payload transformation marker on {:?}", full_expr);
+            }
+        }
+        let marker = exts
+            .iter()
+            .position(|e| iext::mid(e.id) == TRANSFORMATION_EXT_ID);
+
+        match (self.flow, marker, id) {
+            (InterceptorFlow::Ingress, None, Some(id)) => {
+                let transformation = &self.transformer.transformations[id];
+                let (transformed, marker) = transformation.apply(payload, &self.transformer.prng);
+                *payload = transformed;
+                exts.push(ZExtUnknown::new(
+                    TRANSFORMATION_EXT_ID,
+                    false,
+                    ZExtBody::ZBuf(marker),
+                ));
+            }
+            (InterceptorFlow::Egress, Some(marker), Some(id)) => {
+                let ext = exts.remove(marker);
+                let marker = match ext.body {
+                    ZExtBody::ZBuf(marker) => marker,
+                    _ => {
+                        log::warn!("Invalid payload transformation on {:?}", full_expr);
+                        return None;
+                    }
+                };
+                match self.transformer.transformations[id].revert(&marker, payload) {
+                    Ok(reverted) => *payload = reverted,
+                    Err(e) => {
+                        log::warn!(
+                            "Unable to revert the payload transformation on {:?}: {}",
+                            full_expr,
+                            e
+                        );
+                        return None;
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Some(ctx)
+    }
+}
@@ -0,0 +1,420 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! ⚠️ WARNING ⚠️
+//!
+//! This module is intended for Zenoh's internal use.
+//!
+//! [Click here for Zenoh's documentation](../zenoh/index.html)
+
+use crate::net::routing::interceptor::*;
+use serde_json::json;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use zenoh_buffers::buffer::Buffer;
+use zenoh_config::{InterceptorFlow, RateLimitItemConf, RateLimitMessage, RateLimitRuleConf};
+use zenoh_core::zlock;
+use zenoh_keyexpr::keyexpr_tree::impls::KeyedSetProvider;
+use zenoh_keyexpr::keyexpr_tree::{support::UnknownWildness, KeBoxTree};
+use zenoh_keyexpr::keyexpr_tree::{
+    IKeyExprTree, IKeyExprTreeMut, IKeyExprTreeNode, IKeyExprTreeNodeMut,
+};
+use zenoh_keyexpr::OwnedKeyExpr;
+use zenoh_protocol::core::ZenohId;
+use zenoh_protocol::network::{response, Request, Response, ResponseFinal};
+use zenoh_protocol::network::{NetworkBody, NetworkMessage};
+use zenoh_protocol::zenoh::{self, ext::ValueType, PushBody, RequestBody, ResponseBody};
+use zenoh_result::ZResult;
+
+pub(crate) fn rate_limit_interceptor_factories(
+    config: &Vec<RateLimitItemConf>,
+    stats: &Arc<RateLimitStats>,
+) -> ZResult<Vec<InterceptorFactory>> {
+    let mut res: Vec<InterceptorFactory> = vec![];
+
+    for rl in config {
+        res.push(Box::new(RateLimitInterceptorFactory::new(
+            rl.clone(),
+            stats.clone(),
+        )));
+    }
+
+    Ok(res)
+}
+
+pub struct RateLimitInterceptorFactory {
+    interfaces: Option<Vec<String>>,
+    zids: Option<Vec<ZenohId>>,
+    rules: Vec<RateLimitRuleConf>,
+    flow: InterceptorFlow,
+    reply_error: bool,
+    stats: Arc<RateLimitStats>,
+}
+
+impl RateLimitInterceptorFactory {
+    pub(crate) fn new(conf: RateLimitItemConf, stats: Arc<RateLimitStats>) -> Self {
+        Self {
+            interfaces: conf.interfaces,
+            zids: conf.zids,
+            rules: conf.rules,
+            flow: conf.flow,
+            reply_error: conf.reply_error,
+            stats,
+        }
+    }
+}
+
+impl InterceptorFactoryTrait for RateLimitInterceptorFactory {
+    fn new_transport_unicast(
+        &self,
+        transport: &TransportUnicast,
+    ) -> (Option<IngressInterceptor>, Option<EgressInterceptor>) {
+        log::debug!("New rate limiter transport unicast {:?}", transport);
+        let zid = match transport.get_zid() {
+            Ok(zid) => zid,
+            Err(_) => return (None, None),
+        };
+        if let Some(zids) = &self.zids {
+            if !zids.contains(&zid) {
+                return (None, None);
+            }
+        }
+        if let Some(interfaces) = &self.interfaces {
+            if let Ok(links) = transport.get_links() {
+                for link in links {
+                    if !link.interfaces.iter().any(|x| interfaces.contains(x)) {
+                        return (None, None);
+                    }
+                }
+            }
+        };
+
+        let interceptor = RateLimitInterceptor::new(&self.rules, self.reply_error);
+        self.stats.register(zid, self.flow, &interceptor.rules);
+        match self.flow {
+            InterceptorFlow::Ingress => (Some(Box::new(ComputeOnMiss::new(interceptor))), None),
+            InterceptorFlow::Egress => (None, Some(Box::new(ComputeOnMiss::new(interceptor)))),
+        }
+    }
+
+    fn new_transport_multicast(
+        &self,
+        _transport: &TransportMulticast,
+    ) -> Option<EgressInterceptor> {
+        None
+    }
+
+    fn new_peer_multicast(&self, _transport: &TransportMulticast) -> Option<IngressInterceptor> {
+        None
+    }
+}
+
+struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    last_refill: tokio::time::Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: f64, burst: Option<f64>) -> Self {
+        let capacity = burst.unwrap_or(rate);
+        Self {
+            rate,
+            capacity,
+            tokens: capacity,
+            last_refill: tokio::time::Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: tokio::time::Instant) {
+        let elapsed = (now - self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.last_refill = now;
+    }
+}
+
+struct RuleState {
+    key_expr: OwnedKeyExpr,
+    messages: Vec<RateLimitMessage>,
+    buckets: Mutex<(Option<TokenBucket>, Option<TokenBucket>)>,
+    passed: AtomicU64,
+    rejected: AtomicU64,
+}
+
+impl RuleState {
+    fn new(rule: &RateLimitRuleConf) -> Self {
+        Self {
+            key_expr: rule.key_expr.clone(),
+            messages: rule.messages.clone().unwrap_or_else(|| {
+                vec![
+                    RateLimitMessage::Put,
+                    RateLimitMessage::Delete,
+                    RateLimitMessage::Query,
+                ]
+            }),
+            buckets: Mutex::new((
+                rule.messages_per_sec
+                    .map(|rate| TokenBucket::new(rate, rule.messages_burst)),
+                rule.bytes_per_sec
+                    .map(|rate| TokenBucket::new(rate, rule.bytes_burst)),
+            )),
+            passed: AtomicU64::new(0),
+            rejected: AtomicU64::new(0),
+        }
+    }
+
+    /// Takes a token for the message and its size in bytes out of the buckets of the rule,
+    /// returning whether the message is within the limits.
+    fn take(&self, size: usize) -> bool {
+        let now = tokio::time::Instant::now();
+        let mut buckets = zlock!(self.buckets);
+        let (messages, bytes) = &mut *buckets;
+        if let Some(messages) = messages.as_mut() {
+            messages.refill(now);
+        }
+        if let Some(bytes) = bytes.as_mut() {
+            bytes.refill(now);
+        }
+        // A message larger than the bytes burst is let through once the bucket is full,
+        // which is then drained below zero until it refills
+        let passed = messages.as_ref().map(|b| b.tokens >= 1.0).unwrap_or(true)
+            && bytes
+                .as_ref()
+                .map(|b| b.tokens >= (size as f64).min(b.capacity))
+                .unwrap_or(true);
+        if passed {
+            if let Some(messages) = messages.as_mut() {
+                messages.tokens -= 1.0;
+            }
+            if let Some(bytes) = bytes.as_mut() {
+                bytes.tokens -= size as f64;
+            }
+            self.passed.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.rejected.fetch_add(1, Ordering::Relaxed);
+        }
+        passed
+    }
+}
+
+struct FaceStats {
+    zid: ZenohId,
+    flow: InterceptorFlow,
+    rules: Weak<Vec<RuleState>>,
+}
+
+/// The rate limiting counters of the transports, exposed in the admin space.
+#[derive(Default)]
+pub(crate) struct RateLimitStats {
+    faces: Mutex<Vec<FaceStats>>,
+}
+
+impl RateLimitStats {
+    fn register(&self, zid: ZenohId, flow: InterceptorFlow, rules: &Arc<Vec<RuleState>>) {
+        let mut faces = zlock!(self.faces);
+        faces.retain(|face| face.rules.strong_count() > 0);
+        faces.push(FaceStats {
+            zid,
+            flow,
+            rules: Arc::downgrade(rules),
+        });
+    }
+
+    pub(crate) fn report(&self) -> serde_json::Value {
+        let mut faces = zlock!(self.faces);
+        faces.retain(|face| face.rules.strong_count() > 0);
+        let mut report = serde_json::Map::new();
+        for face in faces.iter() {
+            if let Some(rules) = face.rules.upgrade() {
+                let flow = match face.flow {
+                    InterceptorFlow::Ingress => "ingress",
+                    InterceptorFlow::Egress => "egress",
+                };
+                let rules: Vec<serde_json::Value> = rules
+                    .iter()
+                    .map(|rule| {
+                        json!({
+                            "key_expr": rule.key_expr.as_str(),
+                            "passed": rule.passed.load(Ordering::Relaxed),
+                            "rejected": rule.rejected.load(Ordering::Relaxed),
+                        })
+                    })
+                    .collect();
+                // several rate_limit items may apply to the same transport and flow
+                let entry = report
+                    .entry(face.zid.to_string())
+                    .or_insert_with(|| json!({}));
+                entry
+                    .as_object_mut()
+                    .unwrap()
+                    .entry(flow)
+                    .or_insert_with(|| json!([]))
+                    .as_array_mut()
+                    .unwrap()
+                    .extend(rules);
+            }
+        }
+        serde_json::Value::Object(report)
+    }
+}
+
+pub(crate) struct RateLimitInterceptor {
+    ke_id: KeBoxTree<Vec<usize>, UnknownWildness, KeyedSetProvider>,
+    rules: Arc<Vec<RuleState>>,
+    reply_error: bool,
+}
+
+impl RateLimitInterceptor {
+    fn new(rules: &[RateLimitRuleConf], reply_error: bool) -> Self {
+        let mut ke_id: KeBoxTree<Vec<usize>, UnknownWildness, KeyedSetProvider> =
+            KeBoxTree::default();
+        for (id, rule) in rules.iter().enumerate() {
+            // Several rules may share a key expression: keep them all, in config order
+            let node = ke_id.node_mut_or_create(&rule.key_expr);
+            match node.weight_mut() {
+                Some(ids) => ids.push(id),
+                None => {
+                    node.insert_weight(vec![id]);
+                }
+            }
+        }
+        Self {
+            ke_id,
+            rules: Arc::new(rules.iter().map(RuleState::new).collect()),
+            reply_error,
+        }
+    }
+}
+
+/// Returns the kind of the message and its payload size, if it is subject to rate limiting.
+fn rate_limited(msg: &NetworkMessage) -> Option<(RateLimitMessage, usize)> {
+    match &msg.body {
+        NetworkBody::Push(m) => match &m.payload {
+            PushBody::Put(p) => Some((RateLimitMessage::Put, p.payload.len())),
+            PushBody::Del(_) => Some((RateLimitMessage::Delete, 0)),
+        },
+        NetworkBody::Request(m) => match &m.payload {
+            RequestBody::Query(q) => Some((
+                RateLimitMessage::Query,
+                q.ext_body.as_ref().map(|b| b.payload.len()).unwrap_or(0),
+            )),
+            RequestBody::Put(p) => Some((RateLimitMessage::Put, p.payload.len())),
+            RequestBody::Del(_) => Some((RateLimitMessage::Delete, 0)),
+            RequestBody::Pull(_) => None,
+        },
+        _ => None,
+    }
+}
+
+/// Answers a rejected query with an error.
+fn reply_error(ctx: &RoutingContext<NetworkMessage>, request: &Request) {
+    let face = match ctx.inface.get() {
+        Some(face) => face,
+        None => return,
+    };
+    let expr = ctx.full_expr().unwrap_or_default().to_string();
+    face.state
+        .primitives
+        .send_response(RoutingContext::with_expr(
+            Response {
+                rid: request.id,
+                // the requester may not resolve the scope of its own request wire expr
+                wire_expr: expr.clone().into(),
+                payload: ResponseBody::Err(zenoh::Err {
+                    code: 0,
+                    is_infrastructure: true,
+                    timestamp: None,
+                    ext_sinfo: None,
+                    ext_body: Some(ValueType {
+                        #[cfg(feature = "shared-memory")]
+                        ext_shm: None,
+                        encoding: zenoh_protocol::core::Encoding::EMPTY,
+                        payload: "Rate limit exceeded".as_bytes().to_vec().into(),
+                    }),
+                    ext_unknown: vec![],
+                }),
+                ext_qos: response::ext::QoSType::response_default(),
+                ext_tstamp: None,
+                ext_respid: None,
+            },
+            expr.clone(),
+        ));
+    face.state
+        .primitives
+        .send_response_final(RoutingContext::with_expr(
+            ResponseFinal {
+                rid: request.id,
+                ext_qos: response::ext::QoSType::response_final_default(),
+                ext_tstamp: None,
+            },
+            expr,
+        ));
+}
+
+impl InterceptorTrait for RateLimitInterceptor {
+    fn compute_keyexpr_cache(&self, key_expr: &KeyExpr<'_>) -> Option<Box<dyn Any + Send + Sync>> {
+        let mut ids: Vec<usize> = self
+            .ke_id
+            .nodes_including(key_expr)
+            .filter_map(|node| node.weight())
+            .flatten()
+            .copied()
+            .collect();
+        ids.sort_unstable();
+        Some(Box::new(ids))
+    }
+
+    fn intercept(
+        &self,
+        ctx: RoutingContext<NetworkMessage>,
+        cache: Option<&Box<dyn Any + Send + Sync>>,
+    ) -> Option<RoutingContext<NetworkMessage>> {
+        let (message, size) = match rate_limited(&ctx.msg) {
+            Some(limited) => limited,
+            None => return Some(ctx),
+        };
+        let ids = match cache.and_then(|c| c.downcast_ref::<Vec<usize>>()) {
+            Some(ids) => ids,
+            None => {
+                log::debug!("unexpected cache type {:?}", ctx.full_expr());
+                return Some(ctx);
+            }
+        };
+        // The first rule limiting this kind of message applies
+        let rule = match ids
+            .iter()
+            .map(|id| &self.rules[*id])
+            .find(|rule| rule.messages.contains(&message))
+        {
+            Some(rule) => rule,
+            None => return Some(ctx),
+        };
+        if rule.take(size) {
+            return Some(ctx);
+        }
+
+        log::debug!(
+            "Rate limit exceeded for {:?} on {:?}",
+            message,
+            ctx.full_expr()
+        );
+        if let NetworkBody::Request(request) = &ctx.msg.body {
+            if self.reply_error && message == RateLimitMessage::Query {
+                reply_error(&ctx, request);
+            }
+        }
+        None
+    }
+}
@@ -22,10 +22,13 @@ pub mod hat;
 pub mod interceptor;
 pub mod router;
 
-use std::{cell::OnceCell, sync::Arc};
+use std::{cell::OnceCell, fmt, sync::Arc};
 
 use zenoh_protocol::core::key_expr::OwnedKeyExpr;
-use zenoh_protocol::{core::WireExpr, network::NetworkMessage};
+use zenoh_protocol::{
+    core::{WhatAmI, WireExpr, ZenohId},
+    network::{NetworkBody, NetworkMessage},
+};
 
 use self::{dispatcher::face::Face, router::Resource};
 
@@ -33,8 +36,9 @@ use super::runtime;
 
 pub(crate) static PREFIX_LIVELINESS: &str = "@/liveliness";
 
-pub(crate) struct RoutingContext<Msg> {
-    pub(crate) msg: Msg,
+/// A message being routed, along with the faces it is received from and sent to.
+pub struct RoutingContext<Msg> {
+    pub msg: Msg,
     pub(crate) inface: OnceCell<Face>,
     pub(crate) outface: OnceCell<Face>,
     pub(crate) prefix: OnceCell<Arc<Resource>>,
@@ -42,8 +46,8 @@ pub(crate) struct RoutingContext<Msg> {
 }
 
 impl<Msg> RoutingContext<Msg> {
-    #[allow(dead_code)]
-    pub(crate) fn new(msg: Msg) -> Self {
+    /// Creates the routing context of a message received from and sent to no face.
+    pub fn new(msg: Msg) -> Self {
         Self {
             msg,
             inface: OnceCell::new(),
@@ -86,22 +90,76 @@ impl<Msg> RoutingContext<Msg> {
         }
     }
 
-    #[allow(dead_code)]
-    pub(crate) fn inface(&self) -> Option<&Face> {
-        self.inface.get()
+    /// The face the message was received from, if intercepted on ingress.
+    pub fn inface(&self) -> Option<RoutingFace<'_>> {
+        self.inface.get().map(RoutingFace)
     }
 
-    #[allow(dead_code)]
-    pub(crate) fn outface(&self) -> Option<&Face> {
-        self.outface.get()
+    /// The face the message is sent to, if intercepted on egress.
+    pub fn outface(&self) -> Option<RoutingFace<'_>> {
+        self.outface.get().map(RoutingFace)
+    }
+}
+
+/// A face of the router, i.e. a session or transport messages are received from and sent to.
+#[derive(Clone, Copy)]
+pub struct RoutingFace<'a>(&'a Face);
+
+// only used through the unstable interceptor API
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+impl RoutingFace<'_> {
+    /// The id of this face, unique within the router.
+    pub fn id(&self) -> usize {
+        self.0.state.id
+    }
+
+    /// The [`ZenohId`] of the remote node of this face.
+    pub fn zid(&self) -> ZenohId {
+        self.0.state.zid
+    }
+
+    /// The kind of the remote node of this face.
+    pub fn whatami(&self) -> WhatAmI {
+        self.0.state.whatami
+    }
+
+    /// Sends a message to this face without routing it, e.g. to reply to an intercepted request
+    /// or to redirect an intercepted message that is then dropped.
+    ///
+    /// The wire expression of the message must be resolvable by this face, which
+    /// a [`full_expr`](RoutingContext::full_expr) with no scope always is.
+    pub fn send(&self, msg: NetworkMessage) {
+        let primitives = &self.0.state.primitives;
+        match msg.body {
+            NetworkBody::Push(m) => primitives.send_push(m),
+            NetworkBody::Request(m) => {
+                primitives.send_request(RoutingContext::new_out(m, self.0.clone()))
+            }
+            NetworkBody::Response(m) => {
+                primitives.send_response(RoutingContext::new_out(m, self.0.clone()))
+            }
+            NetworkBody::ResponseFinal(m) => {
+                primitives.send_response_final(RoutingContext::new_out(m, self.0.clone()))
+            }
+            NetworkBody::Declare(m) => {
+                primitives.send_declare(RoutingContext::new_out(m, self.0.clone()))
+            }
+            NetworkBody::OAM(_) => log::debug!("{} Unable to send OAM message", self),
+        }
+    }
+}
+
+impl fmt::Display for RoutingFace<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
     }
 }
 
 impl RoutingContext<NetworkMessage> {
+    /// The wire expression of the message, if any.
     #[inline]
-    pub(crate) fn wire_expr(&self) -> Option<&WireExpr> {
+    pub fn wire_expr(&self) -> Option<&WireExpr> {
         use zenoh_protocol::network::DeclareBody;
-        use zenoh_protocol::network::NetworkBody;
         match &self.msg.body {
             NetworkBody::Push(m) => Some(&m.wire_expr),
             NetworkBody::Request(m) => Some(&m.wire_expr),
@@ -157,9 +215,11 @@ impl RoutingContext<NetworkMessage> {
         None
     }
 
+    /// The full key expression of the message, if any, resolved against the declared key expressions.
+    ///
+    /// The result is cached: it does not reflect changes made to the message's wire expression afterwards.
     #[inline]
-    #[allow(dead_code)]
-    pub(crate) fn full_expr(&self) -> Option<&str> {
+    pub fn full_expr(&self) -> Option<&str> {
         if self.full_expr.get().is_some() {
             return Some(self.full_expr.get().as_ref().unwrap());
         }
@@ -172,8 +232,9 @@ impl RoutingContext<NetworkMessage> {
         None
     }
 
+    /// Same as [`full_expr`](Self::full_expr), as a key expression.
     #[inline]
-    pub(crate) fn full_key_expr(&self) -> Option<OwnedKeyExpr> {
+    pub fn full_key_expr(&self) -> Option<OwnedKeyExpr> {
         let full_expr = self.full_expr()?;
         OwnedKeyExpr::new(full_expr).ok()
     }
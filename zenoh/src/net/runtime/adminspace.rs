@@ -147,6 +147,16 @@ impl AdminSpace {
                 .unwrap(),
             Arc::new(peers_linkstate_data),
         );
+        handlers.insert(
+            format!("@/router/{zid_str}/access_control")
+                .try_into()
+                .unwrap(),
+            Arc::new(access_control_data),
+        );
+        handlers.insert(
+            format!("@/router/{zid_str}/rate_limit").try_into().unwrap(),
+            Arc::new(rate_limit_data),
+        );
         handlers.insert(
             format!("@/router/{zid_str}/subscriber/**")
                 .try_into()
@@ -657,6 +667,52 @@ fn peers_linkstate_data(context: &AdminContext, query: Query) {
     }
 }
 
+fn access_control_data(context: &AdminContext, query: Query) {
+    let reply_key: OwnedKeyExpr = format!("@/router/{}/access_control", context.zid_str)
+        .try_into()
+        .unwrap();
+
+    let stats = zread!(context.runtime.state.router.tables.tables)
+        .acl_stats
+        .clone();
+    let json = stats.report();
+
+    log::trace!("AdminSpace access_control_data: {:?}", json);
+    if let Err(e) = query
+        .reply(Ok(Sample::new(
+            reply_key,
+            Value::from(json.to_string().as_bytes().to_vec())
+                .encoding(KnownEncoding::AppJson.into()),
+        )))
+        .res()
+    {
+        log::error!("Error sending AdminSpace reply: {:?}", e);
+    }
+}
+
+fn rate_limit_data(context: &AdminContext, query: Query) {
+    let reply_key: OwnedKeyExpr = format!("@/router/{}/rate_limit", context.zid_str)
+        .try_into()
+        .unwrap();
+
+    let stats = zread!(context.runtime.state.router.tables.tables)
+        .rate_limit_stats
+        .clone();
+    let json = stats.report();
+
+    log::trace!("AdminSpace rate_limit_data: {:?}", json);
+    if let Err(e) = query
+        .reply(Ok(Sample::new(
+            reply_key,
+            Value::from(json.to_string().as_bytes().to_vec())
+                .encoding(KnownEncoding::AppJson.into()),
+        )))
+        .res()
+    {
+        log::error!("Error sending AdminSpace reply: {:?}", e);
+    }
+}
+
 fn subscribers_data(context: &AdminContext, query: Query) {
     let tables = zread!(context.runtime.state.router.tables.tables);
     for sub in tables.hat_code.get_subscriptions(&tables) {
@@ -22,6 +22,8 @@ pub mod orchestrator;
 
 use super::primitives::DeMux;
 use super::routing;
+#[cfg(feature = "unstable")]
+use super::routing::interceptor::InterceptorFactory;
 use super::routing::router::Router;
 use crate::config::{unwrap_or_default, Config, ModeDependent, Notifier};
 use crate::GIT_VERSION;
@@ -176,6 +178,16 @@ impl Runtime {
         zwrite!(self.state.transport_handlers).push(handler);
     }
 
+    /// Registers an interceptor factory on this runtime.
+    ///
+    /// The interceptors it creates apply to the transports established after its registration.
+    #[zenoh_macros::unstable]
+    pub fn add_interceptor_factory(&self, factory: InterceptorFactory) {
+        zwrite!(self.state.router.tables.tables)
+            .interceptors
+            .push(factory);
+    }
+
     pub async fn close(&self) -> ZResult<()> {
         log::trace!("Runtime::close())");
         // TODO: Check this whether is able to terminate all spawned task by Runtime::spawn
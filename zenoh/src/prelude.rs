@@ -37,7 +37,7 @@ pub(crate) mod common {
     pub use crate::config::{self, Config, ValidatedMap};
     pub use crate::handlers::IntoCallbackReceiverPair;
     pub use crate::selector::{Parameter, Parameters, Selector};
-    pub use crate::session::{Session, SessionDeclarations};
+    pub use crate::session::{QuerierDeclarations, Session, SessionDeclarations};
 
     pub use crate::query::{QueryConsolidation, QueryTarget};
 
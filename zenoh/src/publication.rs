@@ -111,6 +111,15 @@ impl PutBuilder<'_, '_> {
         self
     }
 
+    /// Change the express policy to apply when routing the data.
+    /// When express is set to `true`, then the message will not be batched.
+    /// This usually has a positive impact on latency but negative impact on throughput.
+    #[inline]
+    pub fn express(mut self, is_express: bool) -> Self {
+        self.publisher = self.publisher.express(is_express);
+        self
+    }
+
     /// Restrict the matching subscribers that will receive the published data
     /// to the ones that have the given [`Locality`](crate::prelude::Locality).
     #[zenoh_macros::unstable]
@@ -144,6 +153,7 @@ impl SyncResolve for PutBuilder<'_, '_> {
             key_expr,
             congestion_control,
             priority,
+            is_express,
             destination,
         } = self.publisher;
 
@@ -152,6 +162,7 @@ impl SyncResolve for PutBuilder<'_, '_> {
             key_expr: key_expr?,
             congestion_control,
             priority,
+            is_express,
             destination,
         };
 
@@ -246,6 +257,7 @@ pub struct Publisher<'a> {
     pub(crate) key_expr: KeyExpr<'a>,
     pub(crate) congestion_control: CongestionControl,
     pub(crate) priority: Priority,
+    pub(crate) is_express: bool,
     pub(crate) destination: Locality,
 }
 
@@ -268,6 +280,15 @@ impl<'a> Publisher<'a> {
         self
     }
 
+    /// Change the express policy to apply when routing the data.
+    /// When express is set to `true`, then the message will not be batched.
+    /// This usually has a positive impact on latency but negative impact on throughput.
+    #[inline]
+    pub fn express(mut self, is_express: bool) -> Self {
+        self.is_express = is_express;
+        self
+    }
+
     /// Restrict the matching subscribers that will receive the published data
     /// to the ones that have the given [`Locality`](crate::prelude::Locality).
     #[zenoh_macros::unstable]
@@ -385,8 +406,11 @@ impl<'a> Publisher<'a> {
     #[zenoh_macros::unstable]
     pub fn matching_status(&self) -> impl Resolve<ZResult<MatchingStatus>> + '_ {
         zenoh_core::ResolveFuture::new(async move {
-            self.session
-                .matching_status(self.key_expr(), self.destination)
+            self.session.matching_status(
+                self.key_expr(),
+                self.destination,
+                MatchingStatusType::Subscribers,
+            )
         })
     }
 
@@ -416,7 +440,10 @@ impl<'a> Publisher<'a> {
     #[zenoh_macros::unstable]
     pub fn matching_listener(&self) -> MatchingListenerBuilder<'_, DefaultHandler> {
         MatchingListenerBuilder {
-            publisher: PublisherRef::Borrow(self),
+            session: self.session.clone(),
+            key_expr: self.key_expr.clone(),
+            destination: self.destination,
+            match_type: MatchingStatusType::Subscribers,
             handler: DefaultHandler,
         }
     }
@@ -559,7 +586,10 @@ impl PublisherDeclarations for std::sync::Arc<Publisher<'static>> {
     #[zenoh_macros::unstable]
     fn matching_listener(&self) -> MatchingListenerBuilder<'static, DefaultHandler> {
         MatchingListenerBuilder {
-            publisher: PublisherRef::Shared(self.clone()),
+            session: self.session.clone(),
+            key_expr: self.key_expr.clone(),
+            destination: self.destination,
+            match_type: MatchingStatusType::Subscribers,
             handler: DefaultHandler,
         }
     }
@@ -720,6 +750,7 @@ pub struct PublisherBuilder<'a, 'b: 'a> {
     pub(crate) key_expr: ZResult<KeyExpr<'b>>,
     pub(crate) congestion_control: CongestionControl,
     pub(crate) priority: Priority,
+    pub(crate) is_express: bool,
     pub(crate) destination: Locality,
 }
 
@@ -733,6 +764,7 @@ impl<'a, 'b> Clone for PublisherBuilder<'a, 'b> {
             },
             congestion_control: self.congestion_control,
             priority: self.priority,
+            is_express: self.is_express,
             destination: self.destination,
         }
     }
@@ -753,6 +785,15 @@ impl<'a, 'b> PublisherBuilder<'a, 'b> {
         self
     }
 
+    /// Change the express policy to apply when routing the data.
+    /// When express is set to `true`, then the message will not be batched.
+    /// This usually has a positive impact on latency but negative impact on throughput.
+    #[inline]
+    pub fn express(mut self, is_express: bool) -> Self {
+        self.is_express = is_express;
+        self
+    }
+
     /// Restrict the matching subscribers that will receive the published data
     /// to the ones that have the given [`Locality`](crate::prelude::Locality).
     #[zenoh_macros::unstable]
@@ -808,6 +849,7 @@ impl<'a, 'b> SyncResolve for PublisherBuilder<'a, 'b> {
             key_expr,
             congestion_control: self.congestion_control,
             priority: self.priority,
+            is_express: self.is_express,
             destination: self.destination,
         };
         log::trace!("publish({:?})", publisher.key_expr);
@@ -843,7 +885,7 @@ fn resolve_put(
             ext_qos: ext::QoSType::new(
                 publisher.priority.into(),
                 publisher.congestion_control,
-                false,
+                publisher.is_express,
             ),
             ext_tstamp: None,
             ext_nodeid: ext::NodeIdType::default(),
@@ -897,7 +939,7 @@ fn resolve_put(
             qos: QoS::from(ext::QoSType::new(
                 publisher.priority.into(),
                 publisher.congestion_control,
-                false,
+                publisher.is_express,
             )),
         };
 
@@ -997,7 +1039,17 @@ impl TryFrom<ProtocolPriority> for Priority {
     }
 }
 
-/// A struct that indicates if there exist Subscribers matching the Publisher's key expression.
+/// The kind of entities whose matching with a key expression is tracked by a [`MatchingStatus`].
+#[zenoh_macros::unstable]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum MatchingStatusType {
+    Subscribers,
+    /// Only complete Queryables are considered if `true`.
+    Queryables(bool),
+}
+
+/// A struct that indicates if there exist Subscribers matching the Publisher's key expression,
+/// or Queryables matching the Querier's key expression.
 ///
 /// # Examples
 /// ```
@@ -1039,13 +1091,38 @@ impl MatchingStatus {
     pub fn matching_subscribers(&self) -> bool {
         self.matching
     }
+
+    /// Return true if there exist Queryables matching the Querier's key expression.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap().into_arc();
+    /// let querier = session.declare_querier("key/expression").res().await.unwrap();
+    /// let matching_queryables: bool = querier
+    ///     .matching_status()
+    ///     .res()
+    ///     .await
+    ///     .unwrap()
+    ///     .matching_queryables();
+    /// # }
+    /// ```
+    pub fn matching_queryables(&self) -> bool {
+        self.matching
+    }
 }
 
 /// A builder for initializing a [`MatchingListener`].
 #[zenoh_macros::unstable]
 #[derive(Debug)]
 pub struct MatchingListenerBuilder<'a, Handler> {
-    pub(crate) publisher: PublisherRef<'a>,
+    pub(crate) session: SessionRef<'a>,
+    pub(crate) key_expr: KeyExpr<'a>,
+    pub(crate) destination: Locality,
+    pub(crate) match_type: MatchingStatusType,
     pub handler: Handler,
 }
 
@@ -1082,11 +1159,17 @@ impl<'a> MatchingListenerBuilder<'a, DefaultHandler> {
         Callback: Fn(MatchingStatus) + Send + Sync + 'static,
     {
         let MatchingListenerBuilder {
-            publisher,
+            session,
+            key_expr,
+            destination,
+            match_type,
             handler: _,
         } = self;
         MatchingListenerBuilder {
-            publisher,
+            session,
+            key_expr,
+            destination,
+            match_type,
             handler: callback,
         }
     }
@@ -1154,10 +1237,19 @@ impl<'a> MatchingListenerBuilder<'a, DefaultHandler> {
         Handler: crate::prelude::IntoCallbackReceiverPair<'static, MatchingStatus>,
     {
         let MatchingListenerBuilder {
-            publisher,
+            session,
+            key_expr,
+            destination,
+            match_type,
             handler: _,
         } = self;
-        MatchingListenerBuilder { publisher, handler }
+        MatchingListenerBuilder {
+            session,
+            key_expr,
+            destination,
+            match_type,
+            handler,
+        }
     }
 }
 
@@ -1179,12 +1271,16 @@ where
     #[zenoh_macros::unstable]
     fn res_sync(self) -> <Self as Resolvable>::To {
         let (callback, receiver) = self.handler.into_cb_receiver_pair();
-        self.publisher
-            .session
-            .declare_matches_listener_inner(&self.publisher, callback)
+        self.session
+            .declare_matches_listener_inner(
+                &self.key_expr,
+                self.destination,
+                self.match_type,
+                callback,
+            )
             .map(|listener_state| MatchingListener {
                 listener: MatchingListenerInner {
-                    publisher: self.publisher,
+                    session: self.session,
                     state: listener_state,
                     alive: true,
                 },
@@ -1213,6 +1309,7 @@ pub(crate) struct MatchingListenerState {
     pub(crate) current: std::sync::Mutex<bool>,
     pub(crate) key_expr: KeyExpr<'static>,
     pub(crate) destination: Locality,
+    pub(crate) match_type: MatchingStatusType,
     pub(crate) callback: Callback<'static, MatchingStatus>,
 }
 
@@ -1228,7 +1325,7 @@ impl std::fmt::Debug for MatchingListenerState {
 
 #[zenoh_macros::unstable]
 pub(crate) struct MatchingListenerInner<'a> {
-    pub(crate) publisher: PublisherRef<'a>,
+    pub(crate) session: SessionRef<'a>,
     pub(crate) state: std::sync::Arc<MatchingListenerState>,
     pub(crate) alive: bool,
 }
@@ -1249,7 +1346,7 @@ impl<'a> Undeclarable<(), MatchingListenerUndeclaration<'a>> for MatchingListene
 }
 
 /// A listener that sends notifications when the [`MatchingStatus`] of a
-/// publisher changes.
+/// publisher or a querier changes.
 ///
 /// # Examples
 /// ```no_run
@@ -1337,7 +1434,6 @@ impl SyncResolve for MatchingListenerUndeclaration<'_> {
     fn res_sync(mut self) -> <Self as Resolvable>::To {
         self.subscriber.alive = false;
         self.subscriber
-            .publisher
             .session
             .undeclare_matches_listener_inner(self.subscriber.state.id)
     }
@@ -1356,10 +1452,7 @@ impl AsyncResolve for MatchingListenerUndeclaration<'_> {
 impl Drop for MatchingListenerInner<'_> {
     fn drop(&mut self) {
         if self.alive {
-            let _ = self
-                .publisher
-                .session
-                .undeclare_matches_listener_inner(self.state.id);
+            let _ = self.session.undeclare_matches_listener_inner(self.state.id);
         }
     }
 }
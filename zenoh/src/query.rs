@@ -16,13 +16,19 @@
 
 use crate::handlers::{locked, Callback, DefaultHandler};
 use crate::prelude::*;
+use crate::publication::{CongestionControl, Priority};
+#[zenoh_macros::unstable]
+use crate::publication::{MatchingListenerBuilder, MatchingStatus, MatchingStatusType};
 #[zenoh_macros::unstable]
 use crate::sample::Attachment;
 use crate::Session;
+use crate::SessionRef;
+use crate::Undeclarable;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::future::Ready;
 use std::time::Duration;
-use zenoh_core::{AsyncResolve, Resolvable, SyncResolve};
+use zenoh_core::{AsyncResolve, Resolvable, Resolve, SyncResolve};
 use zenoh_result::ZResult;
 
 /// The [`Queryable`](crate::queryable::Queryable)s that should be target of a [`get`](Session::get).
@@ -127,6 +133,8 @@ pub struct GetBuilder<'a, 'b, Handler> {
     pub(crate) consolidation: QueryConsolidation,
     pub(crate) destination: Locality,
     pub(crate) timeout: Duration,
+    pub(crate) congestion_control: CongestionControl,
+    pub(crate) priority: Priority,
     pub(crate) handler: Handler,
     pub(crate) value: Option<Value>,
     #[cfg(feature = "unstable")]
@@ -164,6 +172,8 @@ impl<'a, 'b> GetBuilder<'a, 'b, DefaultHandler> {
             consolidation,
             destination,
             timeout,
+            congestion_control,
+            priority,
             value,
             #[cfg(feature = "unstable")]
             attachment,
@@ -177,6 +187,8 @@ impl<'a, 'b> GetBuilder<'a, 'b, DefaultHandler> {
             consolidation,
             destination,
             timeout,
+            congestion_control,
+            priority,
             value,
             #[cfg(feature = "unstable")]
             attachment,
@@ -249,6 +261,8 @@ impl<'a, 'b> GetBuilder<'a, 'b, DefaultHandler> {
             consolidation,
             destination,
             timeout,
+            congestion_control,
+            priority,
             value,
             #[cfg(feature = "unstable")]
             attachment,
@@ -262,6 +276,8 @@ impl<'a, 'b> GetBuilder<'a, 'b, DefaultHandler> {
             consolidation,
             destination,
             timeout,
+            congestion_control,
+            priority,
             value,
             #[cfg(feature = "unstable")]
             attachment,
@@ -300,6 +316,20 @@ impl<'a, 'b, Handler> GetBuilder<'a, 'b, Handler> {
         self
     }
 
+    /// Change the `congestion_control` to apply when routing the query.
+    #[inline]
+    pub fn congestion_control(mut self, congestion_control: CongestionControl) -> Self {
+        self.congestion_control = congestion_control;
+        self
+    }
+
+    /// Change the priority of the query.
+    #[inline]
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
     /// Set query value.
     #[inline]
     pub fn with_value<IntoValue>(mut self, value: IntoValue) -> Self
@@ -331,6 +361,8 @@ impl<'a, 'b, Handler> GetBuilder<'a, 'b, Handler> {
             consolidation,
             destination,
             timeout,
+            congestion_control,
+            priority,
             value,
             attachment,
             handler,
@@ -343,6 +375,8 @@ impl<'a, 'b, Handler> GetBuilder<'a, 'b, Handler> {
             consolidation,
             destination,
             timeout,
+            congestion_control,
+            priority,
             value,
             attachment,
             handler,
@@ -392,6 +426,8 @@ where
                 self.consolidation,
                 self.destination,
                 self.timeout,
+                self.congestion_control,
+                self.priority,
                 self.value,
                 #[cfg(feature = "unstable")]
                 self.attachment,
@@ -412,3 +448,349 @@ where
         std::future::ready(self.res_sync())
     }
 }
+
+/// A querier that allows to send queries to the matching queryables
+/// with pre-declared key expression and options.
+///
+/// # Examples
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+/// use zenoh::query::*;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let querier = session
+///     .declare_querier("key/expression")
+///     .target(QueryTarget::All)
+///     .res()
+///     .await
+///     .unwrap();
+/// let replies = querier.get().res().await.unwrap();
+/// while let Ok(reply) = replies.recv_async().await {
+///     println!("Received {:?}", reply.sample)
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Querier<'a> {
+    pub(crate) session: SessionRef<'a>,
+    pub(crate) key_expr: KeyExpr<'a>,
+    pub(crate) target: QueryTarget,
+    pub(crate) consolidation: QueryConsolidation,
+    pub(crate) destination: Locality,
+    pub(crate) timeout: Duration,
+    pub(crate) congestion_control: CongestionControl,
+    pub(crate) priority: Priority,
+}
+
+impl<'a> Querier<'a> {
+    pub fn key_expr(&self) -> &KeyExpr<'a> {
+        &self.key_expr
+    }
+
+    /// Query the queryables matching the key expression of the querier.
+    ///
+    /// The returned [`GetBuilder`] is initialized with the options of the querier
+    /// and may override them for this query only.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let querier = session.declare_querier("key/expression").res().await.unwrap();
+    /// let replies = querier.get().res().await.unwrap();
+    /// # }
+    /// ```
+    #[inline]
+    pub fn get(&self) -> GetBuilder<'_, '_, DefaultHandler> {
+        self.get_with_parameters("")
+    }
+
+    /// Query the queryables matching the key expression of the querier
+    /// with the given selector parameters.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let querier = session.declare_querier("key/expression").res().await.unwrap();
+    /// let replies = querier.get_with_parameters("value>1").res().await.unwrap();
+    /// # }
+    /// ```
+    pub fn get_with_parameters<'b, IntoParameters>(
+        &'b self,
+        parameters: IntoParameters,
+    ) -> GetBuilder<'b, 'b, DefaultHandler>
+    where
+        IntoParameters: Into<Cow<'b, str>>,
+    {
+        GetBuilder {
+            session: &self.session,
+            selector: Ok(Selector {
+                key_expr: self.key_expr.clone(),
+                parameters: parameters.into(),
+            }),
+            scope: Ok(None),
+            target: self.target,
+            consolidation: self.consolidation,
+            destination: self.destination,
+            timeout: self.timeout,
+            congestion_control: self.congestion_control,
+            priority: self.priority,
+            value: None,
+            #[cfg(feature = "unstable")]
+            attachment: None,
+            handler: DefaultHandler,
+        }
+    }
+
+    /// Return the [`MatchingStatus`] of the querier.
+    ///
+    /// [`MatchingStatus::matching_queryables`] will return true if there exist Queryables
+    /// matching the Querier's key expression and target and false otherwise.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let querier = session.declare_querier("key/expression").res().await.unwrap();
+    /// let matching_queryables: bool = querier
+    ///     .matching_status()
+    ///     .res()
+    ///     .await
+    ///     .unwrap()
+    ///     .matching_queryables();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn matching_status(&self) -> impl Resolve<ZResult<MatchingStatus>> + '_ {
+        zenoh_core::ResolveFuture::new(async move {
+            self.session
+                .matching_status(self.key_expr(), self.destination, self.match_type())
+        })
+    }
+
+    /// Return a [`MatchingListener`](crate::publication::MatchingListener) for this Querier.
+    ///
+    /// The [`MatchingListener`](crate::publication::MatchingListener) will send a notification
+    /// each time the [`MatchingStatus`] of the Querier changes.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let querier = session.declare_querier("key/expression").res().await.unwrap();
+    /// let matching_listener = querier.matching_listener().res().await.unwrap();
+    /// while let Ok(matching_status) = matching_listener.recv_async().await {
+    ///     if matching_status.matching_queryables() {
+    ///         println!("Querier has matching queryables.");
+    ///     } else {
+    ///         println!("Querier has NO MORE matching queryables.");
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn matching_listener(&self) -> MatchingListenerBuilder<'a, DefaultHandler> {
+        MatchingListenerBuilder {
+            session: self.session.clone(),
+            key_expr: self.key_expr.clone(),
+            destination: self.destination,
+            match_type: self.match_type(),
+            handler: DefaultHandler,
+        }
+    }
+
+    #[zenoh_macros::unstable]
+    fn match_type(&self) -> MatchingStatusType {
+        MatchingStatusType::Queryables(self.target == QueryTarget::AllComplete)
+    }
+
+    /// Undeclares the [`Querier`]. No more queries can be sent through it.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let querier = session.declare_querier("key/expression").res().await.unwrap();
+    /// querier.undeclare().res().await.unwrap();
+    /// # }
+    /// ```
+    pub fn undeclare(self) -> impl Resolve<ZResult<()>> + 'a {
+        Undeclarable::undeclare_inner(self, ())
+    }
+}
+
+impl<'a> Undeclarable<(), QuerierUndeclaration<'a>> for Querier<'a> {
+    fn undeclare_inner(self, _: ()) -> QuerierUndeclaration<'a> {
+        QuerierUndeclaration { querier: self }
+    }
+}
+
+/// A [`Resolvable`] returned when undeclaring a querier.
+///
+/// # Examples
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let querier = session.declare_querier("key/expression").res().await.unwrap();
+/// querier.undeclare().res().await.unwrap();
+/// # }
+/// ```
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct QuerierUndeclaration<'a> {
+    querier: Querier<'a>,
+}
+
+impl Resolvable for QuerierUndeclaration<'_> {
+    type To = ZResult<()>;
+}
+
+impl SyncResolve for QuerierUndeclaration<'_> {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        // The querier does not declare anything to the network: its key expression
+        // may be shared with other entities of the session and is left declared.
+        log::trace!("undeclare querier({:?})", self.querier.key_expr);
+        drop(self.querier);
+        Ok(())
+    }
+}
+
+impl AsyncResolve for QuerierUndeclaration<'_> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+/// A builder for initializing a [`Querier`].
+///
+/// # Examples
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use std::time::Duration;
+/// use zenoh::prelude::r#async::*;
+/// use zenoh::query::*;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let querier = session
+///     .declare_querier("key/expression")
+///     .consolidation(ConsolidationMode::None)
+///     .timeout(Duration::from_secs(1))
+///     .res()
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+#[derive(Debug)]
+pub struct QuerierBuilder<'a, 'b: 'a> {
+    pub(crate) session: SessionRef<'a>,
+    pub(crate) key_expr: ZResult<KeyExpr<'b>>,
+    pub(crate) target: QueryTarget,
+    pub(crate) consolidation: QueryConsolidation,
+    pub(crate) destination: Locality,
+    pub(crate) timeout: Duration,
+    pub(crate) congestion_control: CongestionControl,
+    pub(crate) priority: Priority,
+}
+
+impl<'a, 'b> QuerierBuilder<'a, 'b> {
+    /// Change the default target of the queries.
+    #[inline]
+    pub fn target(mut self, target: QueryTarget) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Change the default consolidation mode of the queries.
+    #[inline]
+    pub fn consolidation<QC: Into<QueryConsolidation>>(mut self, consolidation: QC) -> Self {
+        self.consolidation = consolidation.into();
+        self
+    }
+
+    /// Restrict the matching queryables that will receive the queries
+    /// to the ones that have the given [`Locality`](crate::prelude::Locality).
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn allowed_destination(mut self, destination: Locality) -> Self {
+        self.destination = destination;
+        self
+    }
+
+    /// Set the default timeout of the queries.
+    #[inline]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Change the `congestion_control` to apply when routing the queries.
+    #[inline]
+    pub fn congestion_control(mut self, congestion_control: CongestionControl) -> Self {
+        self.congestion_control = congestion_control;
+        self
+    }
+
+    /// Change the priority of the queries.
+    #[inline]
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+impl<'a, 'b> Resolvable for QuerierBuilder<'a, 'b> {
+    type To = ZResult<Querier<'a>>;
+}
+
+impl<'a, 'b> SyncResolve for QuerierBuilder<'a, 'b> {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        let mut key_expr = self.key_expr?;
+        if !key_expr.is_fully_optimized(&self.session) {
+            key_expr = self.session._declare_keyexpr(Ok(key_expr)).res_sync()?;
+        }
+        let querier = Querier {
+            session: self.session,
+            key_expr,
+            target: self.target,
+            consolidation: self.consolidation,
+            destination: self.destination,
+            timeout: self.timeout,
+            congestion_control: self.congestion_control,
+            priority: self.priority,
+        };
+        log::trace!("querier({:?})", querier.key_expr);
+        Ok(querier)
+    }
+}
+
+impl<'a, 'b> AsyncResolve for QuerierBuilder<'a, 'b> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
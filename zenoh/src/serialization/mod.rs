@@ -0,0 +1,559 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Typed serialization of payloads.
+//!
+//! Values are serialized in a compact binary format, so that applications agree on the
+//! representation of their payloads:
+//!
+//! | Type                      | Representation                                       |
+//! |---------------------------|------------------------------------------------------|
+//! | `bool`                    | 1 byte, `0` or `1`                                   |
+//! | integers and floats       | fixed size little-endian, `usize`/`isize` as 64 bits |
+//! | `str`, `String`           | length as a zint, followed by the UTF-8 bytes        |
+//! | `ZBuf`, `[u8]`, `Vec<u8>` | length as a zint, followed by the bytes              |
+//! | `[T]`, `Vec<T>`           | length as a zint, followed by the elements           |
+//! | `HashMap<K, V>`           | length as a zint, followed by the key-value pairs    |
+//! | `Option<T>`               | 1 byte, `0` for `None` or `1` followed by the value  |
+//! | tuples                    | the elements one after the other                     |
+//!
+//! A zint is the variable length integer encoding used by the Zenoh protocol.
+//!
+//! Several values serialized one after the other form a stream that can be deserialized
+//! lazily with [`ZDeserializer::iter`].
+//!
+//! Types implementing [`serde::Serialize`] and [`serde::Deserialize`] can be written and read in
+//! the same format with [`serialize_serde`] and [`deserialize_serde`]. The format not being
+//! self-describing, structs are represented as the tuple of their fields and enums as their
+//! variant index as a zint followed by the variant content.
+//!
+//! # Examples
+//! ```
+//! use std::collections::HashMap;
+//! use zenoh::serialization::{deserialize, serialize};
+//!
+//! let map = HashMap::from([(1u32, "one".to_string()), (2u32, "two".to_string())]);
+//! let payload = serialize(&(42i64, map.clone()));
+//! let (number, other): (i64, HashMap<u32, String>) = deserialize(&payload).unwrap();
+//! assert_eq!(number, 42);
+//! assert_eq!(other, map);
+//! ```
+mod zserde;
+
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+use zenoh_buffers::{
+    buffer::{Buffer, SplitBuffer},
+    reader::{HasReader, Reader},
+    writer::HasWriter,
+    ZBuf, ZBufReader,
+};
+use zenoh_codec::{RCodec, WCodec, Zenoh080};
+use zenoh_result::{bail, zerror, ZResult};
+
+/// A type that can be serialized with a [`ZSerializer`].
+pub trait ZSerialize {
+    fn serialize(&self, serializer: &mut ZSerializer);
+}
+
+/// A type that can be deserialized with a [`ZDeserializer`].
+pub trait ZDeserialize: Sized {
+    fn deserialize(deserializer: &mut ZDeserializer<'_>) -> ZResult<Self>;
+}
+
+/// Serializes a value into a payload.
+pub fn serialize<T: ZSerialize + ?Sized>(t: &T) -> ZBuf {
+    let mut serializer = ZSerializer::new();
+    serializer.serialize(t);
+    serializer.finish()
+}
+
+/// Deserializes a value from a payload, failing if the payload is not entirely consumed.
+pub fn deserialize<T: ZDeserialize>(zbuf: &ZBuf) -> ZResult<T> {
+    let mut deserializer = ZDeserializer::new(zbuf);
+    let t = deserializer.deserialize()?;
+    if !deserializer.done() {
+        bail!(
+            "{} trailing bytes after deserialization",
+            deserializer.remaining()
+        );
+    }
+    Ok(t)
+}
+
+/// Serializes a [`serde::Serialize`] value into a payload.
+pub fn serialize_serde<T: serde::Serialize + ?Sized>(t: &T) -> ZResult<ZBuf> {
+    let mut serializer = ZSerializer::new();
+    serializer.serialize_serde(t)?;
+    Ok(serializer.finish())
+}
+
+/// Deserializes a [`serde::Deserialize`] value from a payload, failing if the payload is not entirely consumed.
+pub fn deserialize_serde<T: serde::de::DeserializeOwned>(zbuf: &ZBuf) -> ZResult<T> {
+    let mut deserializer = ZDeserializer::new(zbuf);
+    let t = deserializer.deserialize_serde()?;
+    if !deserializer.done() {
+        bail!(
+            "{} trailing bytes after deserialization",
+            deserializer.remaining()
+        );
+    }
+    Ok(t)
+}
+
+/// A serializer appending values to a payload.
+///
+/// # Examples
+/// ```
+/// use zenoh::serialization::{ZDeserializer, ZSerializer};
+///
+/// let mut serializer = ZSerializer::new();
+/// for i in 0..3u32 {
+///     serializer.serialize(&(i, format!("value{i}")));
+/// }
+/// let payload = serializer.finish();
+///
+/// let values: Vec<(u32, String)> = ZDeserializer::new(&payload)
+///     .iter()
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+/// assert_eq!(values[2], (2, "value2".to_string()));
+/// ```
+#[derive(Debug, Default)]
+pub struct ZSerializer {
+    buf: Vec<u8>,
+}
+
+impl ZSerializer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a value to the payload.
+    pub fn serialize<T: ZSerialize + ?Sized>(&mut self, t: &T) {
+        t.serialize(self)
+    }
+
+    /// Appends a [`serde::Serialize`] value to the payload.
+    ///
+    /// On error, nothing is appended.
+    pub fn serialize_serde<T: serde::Serialize + ?Sized>(&mut self, t: &T) -> ZResult<()> {
+        zserde::serialize(self, t)
+    }
+
+    /// Returns the serialized payload.
+    pub fn finish(self) -> ZBuf {
+        self.buf.into()
+    }
+
+    fn write_len(&mut self, len: usize) {
+        // Writing into a Vec<u8> can't fail
+        let _ = Zenoh080::new().write(&mut (&mut self.buf).writer(), len as u64);
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write_len(bytes.len());
+        self.buf.extend_from_slice(bytes);
+    }
+}
+
+/// A deserializer reading values from a payload.
+pub struct ZDeserializer<'a> {
+    reader: ZBufReader<'a>,
+}
+
+impl<'a> ZDeserializer<'a> {
+    pub fn new(zbuf: &'a ZBuf) -> Self {
+        Self {
+            reader: zbuf.reader(),
+        }
+    }
+
+    /// Reads the next value of the payload.
+    pub fn deserialize<T: ZDeserialize>(&mut self) -> ZResult<T> {
+        T::deserialize(self)
+    }
+
+    /// Reads the next [`serde::Deserialize`] value of the payload.
+    pub fn deserialize_serde<T: serde::de::DeserializeOwned>(&mut self) -> ZResult<T> {
+        zserde::deserialize(self)
+    }
+
+    /// Returns true if the whole payload has been read.
+    pub fn done(&self) -> bool {
+        !self.reader.can_read()
+    }
+
+    /// Returns the number of bytes left to read.
+    pub fn remaining(&self) -> usize {
+        self.reader.remaining()
+    }
+
+    /// Returns an iterator deserializing the values of the payload one by one.
+    ///
+    /// The iterator stops at the end of the payload or after the first error.
+    pub fn iter<T: ZDeserialize>(self) -> ZDeserializeIter<'a, T> {
+        ZDeserializeIter {
+            deserializer: self,
+            failed: false,
+            _t: PhantomData,
+        }
+    }
+
+    fn read_array<const N: usize>(&mut self) -> ZResult<[u8; N]> {
+        let mut bytes = [0u8; N];
+        self.reader
+            .read_exact(&mut bytes)
+            .map_err(|_| zerror!("Unable to read {} bytes", N))?;
+        Ok(bytes)
+    }
+
+    fn read_len(&mut self) -> ZResult<usize> {
+        let len: u64 = Zenoh080::new()
+            .read(&mut self.reader)
+            .map_err(|_| zerror!("Unable to read a length"))?;
+        let len = usize::try_from(len).map_err(|_| zerror!("Invalid length: {}", len))?;
+        if len > self.remaining() {
+            bail!(
+                "Invalid length: {} > {} remaining bytes",
+                len,
+                self.remaining()
+            );
+        }
+        Ok(len)
+    }
+
+    fn read_bytes(&mut self) -> ZResult<Vec<u8>> {
+        let mut bytes = vec![0u8; self.read_len()?];
+        if bytes.is_empty() {
+            // Readers fail on empty reads at the end of the buffer
+            return Ok(bytes);
+        }
+        self.reader
+            .read_exact(&mut bytes)
+            .map_err(|_| zerror!("Unable to read {} bytes", bytes.len()))?;
+        Ok(bytes)
+    }
+}
+
+/// An iterator over the values of a payload, returned by [`ZDeserializer::iter`].
+pub struct ZDeserializeIter<'a, T> {
+    deserializer: ZDeserializer<'a>,
+    failed: bool,
+    _t: PhantomData<T>,
+}
+
+impl<T: ZDeserialize> Iterator for ZDeserializeIter<'_, T> {
+    type Item = ZResult<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.deserializer.done() {
+            return None;
+        }
+        let res = self.deserializer.deserialize();
+        self.failed = res.is_err();
+        Some(res)
+    }
+}
+
+macro_rules! impl_number {
+    ($($t:ty),*) => {
+        $(
+            impl ZSerialize for $t {
+                fn serialize(&self, serializer: &mut ZSerializer) {
+                    serializer.buf.extend_from_slice(&self.to_le_bytes());
+                }
+            }
+
+            impl ZDeserialize for $t {
+                fn deserialize(deserializer: &mut ZDeserializer<'_>) -> ZResult<Self> {
+                    deserializer.read_array().map(<$t>::from_le_bytes)
+                }
+            }
+        )*
+    };
+}
+impl_number!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl ZSerialize for usize {
+    fn serialize(&self, serializer: &mut ZSerializer) {
+        (*self as u64).serialize(serializer)
+    }
+}
+
+impl ZDeserialize for usize {
+    fn deserialize(deserializer: &mut ZDeserializer<'_>) -> ZResult<Self> {
+        let n = u64::deserialize(deserializer)?;
+        usize::try_from(n).map_err(|_| zerror!("Invalid usize: {}", n).into())
+    }
+}
+
+impl ZSerialize for isize {
+    fn serialize(&self, serializer: &mut ZSerializer) {
+        (*self as i64).serialize(serializer)
+    }
+}
+
+impl ZDeserialize for isize {
+    fn deserialize(deserializer: &mut ZDeserializer<'_>) -> ZResult<Self> {
+        let n = i64::deserialize(deserializer)?;
+        isize::try_from(n).map_err(|_| zerror!("Invalid isize: {}", n).into())
+    }
+}
+
+impl ZSerialize for bool {
+    fn serialize(&self, serializer: &mut ZSerializer) {
+        u8::from(*self).serialize(serializer)
+    }
+}
+
+impl ZDeserialize for bool {
+    fn deserialize(deserializer: &mut ZDeserializer<'_>) -> ZResult<Self> {
+        match u8::deserialize(deserializer)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            b => bail!("Invalid bool: {}", b),
+        }
+    }
+}
+
+impl ZSerialize for str {
+    fn serialize(&self, serializer: &mut ZSerializer) {
+        serializer.write_bytes(self.as_bytes())
+    }
+}
+
+impl ZSerialize for String {
+    fn serialize(&self, serializer: &mut ZSerializer) {
+        self.as_str().serialize(serializer)
+    }
+}
+
+impl ZDeserialize for String {
+    fn deserialize(deserializer: &mut ZDeserializer<'_>) -> ZResult<Self> {
+        String::from_utf8(deserializer.read_bytes()?)
+            .map_err(|e| zerror!("Invalid string: {}", e).into())
+    }
+}
+
+impl ZSerialize for ZBuf {
+    fn serialize(&self, serializer: &mut ZSerializer) {
+        serializer.write_len(self.len());
+        for slice in self.slices() {
+            serializer.buf.extend_from_slice(slice);
+        }
+    }
+}
+
+impl ZDeserialize for ZBuf {
+    fn deserialize(deserializer: &mut ZDeserializer<'_>) -> ZResult<Self> {
+        let len = deserializer.read_len()?;
+        let mut zbuf = ZBuf::empty();
+        if len == 0 {
+            return Ok(zbuf);
+        }
+        deserializer
+            .reader
+            .read_zslices(len, |slice| zbuf.push_zslice(slice))
+            .map_err(|_| zerror!("Unable to read {} bytes", len))?;
+        Ok(zbuf)
+    }
+}
+
+impl<T: ZSerialize + ?Sized> ZSerialize for &T {
+    fn serialize(&self, serializer: &mut ZSerializer) {
+        (**self).serialize(serializer)
+    }
+}
+
+impl<T: ZSerialize> ZSerialize for [T] {
+    fn serialize(&self, serializer: &mut ZSerializer) {
+        serializer.write_len(self.len());
+        for t in self {
+            t.serialize(serializer);
+        }
+    }
+}
+
+impl<T: ZSerialize> ZSerialize for Vec<T> {
+    fn serialize(&self, serializer: &mut ZSerializer) {
+        self.as_slice().serialize(serializer)
+    }
+}
+
+impl<T: ZDeserialize> ZDeserialize for Vec<T> {
+    fn deserialize(deserializer: &mut ZDeserializer<'_>) -> ZResult<Self> {
+        let len = deserializer.read_len()?;
+        (0..len).map(|_| deserializer.deserialize()).collect()
+    }
+}
+
+impl<K: ZSerialize, V: ZSerialize, S> ZSerialize for HashMap<K, V, S> {
+    fn serialize(&self, serializer: &mut ZSerializer) {
+        serializer.write_len(self.len());
+        for (k, v) in self {
+            k.serialize(serializer);
+            v.serialize(serializer);
+        }
+    }
+}
+
+impl<K, V, S> ZDeserialize for HashMap<K, V, S>
+where
+    K: ZDeserialize + Eq + Hash,
+    V: ZDeserialize,
+    S: BuildHasher + Default,
+{
+    fn deserialize(deserializer: &mut ZDeserializer<'_>) -> ZResult<Self> {
+        let len = deserializer.read_len()?;
+        (0..len)
+            .map(|_| Ok((deserializer.deserialize()?, deserializer.deserialize()?)))
+            .collect()
+    }
+}
+
+impl<T: ZSerialize> ZSerialize for Option<T> {
+    fn serialize(&self, serializer: &mut ZSerializer) {
+        match self {
+            Some(t) => {
+                true.serialize(serializer);
+                t.serialize(serializer);
+            }
+            None => false.serialize(serializer),
+        }
+    }
+}
+
+impl<T: ZDeserialize> ZDeserialize for Option<T> {
+    fn deserialize(deserializer: &mut ZDeserializer<'_>) -> ZResult<Self> {
+        if bool::deserialize(deserializer)? {
+            deserializer.deserialize().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($t:ident),+) => {
+        impl<$($t: ZSerialize),+> ZSerialize for ($($t,)+) {
+            #[allow(non_snake_case)]
+            fn serialize(&self, serializer: &mut ZSerializer) {
+                let ($($t,)+) = self;
+                $($t.serialize(serializer);)+
+            }
+        }
+
+        impl<$($t: ZDeserialize),+> ZDeserialize for ($($t,)+) {
+            fn deserialize(deserializer: &mut ZDeserializer<'_>) -> ZResult<Self> {
+                Ok(($(deserializer.deserialize::<$t>()?,)+))
+            }
+        }
+    };
+}
+impl_tuple!(A);
+impl_tuple!(A, B);
+impl_tuple!(A, B, C);
+impl_tuple!(A, B, C, D);
+impl_tuple!(A, B, C, D, E);
+impl_tuple!(A, B, C, D, E, F);
+impl_tuple!(A, B, C, D, E, F, G);
+impl_tuple!(A, B, C, D, E, F, G, H);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip<T: ZSerialize + ZDeserialize + PartialEq + std::fmt::Debug>(t: T) {
+        let zbuf = serialize(&t);
+        assert_eq!(deserialize::<T>(&zbuf).unwrap(), t);
+    }
+
+    #[test]
+    fn serialization_roundtrip() {
+        roundtrip(true);
+        roundtrip(u8::MAX);
+        roundtrip(i16::MIN);
+        roundtrip(u64::MAX);
+        roundtrip(-1i128);
+        roundtrip(usize::MAX);
+        roundtrip(1.5f32);
+        roundtrip(-2.5f64);
+        roundtrip("zenoh".to_string());
+        roundtrip(String::new());
+        roundtrip(vec![0u8; 300]);
+        roundtrip(vec![(1u32, "a".to_string()), (2, "b".to_string())]);
+        roundtrip(HashMap::from([
+            ("a".to_string(), vec![1.0f64]),
+            ("b".to_string(), vec![]),
+        ]));
+        roundtrip((Some(1u8), None::<String>, ZBuf::from(vec![1u8, 2, 3])));
+
+        let value = crate::value::Value::serialize(&(1u8, "a"));
+        assert_eq!(
+            value.deserialize::<(u8, String)>().unwrap(),
+            (1, "a".to_string())
+        );
+    }
+
+    #[test]
+    fn serialization_format() {
+        assert_eq!(serialize(&1u32).contiguous().as_ref(), &[1, 0, 0, 0]);
+        assert_eq!(serialize(&true).contiguous().as_ref(), &[1]);
+        assert_eq!(serialize("ab").contiguous().as_ref(), &[2, b'a', b'b']);
+        assert_eq!(
+            serialize(&vec![1u16, 2]).contiguous().as_ref(),
+            &[2, 1, 0, 2, 0]
+        );
+        assert_eq!(serialize(&vec![0u8; 200]).contiguous()[..2], [200, 1]);
+        // Bytes and u8 sequences share the same representation
+        assert_eq!(
+            serialize(&ZBuf::from(vec![7u8; 3])).contiguous(),
+            serialize(&vec![7u8; 3]).contiguous()
+        );
+    }
+
+    #[test]
+    fn deserialization_errors() {
+        assert!(deserialize::<u32>(&serialize(&1u16)).is_err());
+        assert!(deserialize::<u16>(&serialize(&1u32)).is_err());
+        assert!(deserialize::<bool>(&serialize(&2u8)).is_err());
+        assert!(deserialize::<String>(&serialize(&vec![0xffu8])).is_err());
+        assert!(deserialize::<Vec<u64>>(&ZBuf::from(vec![0xffu8, 0xff, 0x01])).is_err());
+    }
+
+    #[test]
+    fn deserialization_stream() {
+        let mut serializer = ZSerializer::new();
+        for i in 0..10u64 {
+            serializer.serialize(&(i, i.to_string()));
+        }
+        let zbuf = serializer.finish();
+        let values = ZDeserializer::new(&zbuf)
+            .iter::<(u64, String)>()
+            .collect::<ZResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(values.len(), 10);
+        assert_eq!(values[9], (9, "9".to_string()));
+
+        // The iteration stops after the first error
+        let zbuf = ZBuf::from(vec![0u8, 1, 2, 0]);
+        let mut iter = ZDeserializer::new(&zbuf).iter::<bool>();
+        assert!(!iter.next().unwrap().unwrap());
+        assert!(iter.next().unwrap().unwrap());
+        assert!(iter.next().unwrap().is_err());
+        assert!(iter.next().is_none());
+    }
+}
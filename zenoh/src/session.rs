@@ -17,6 +17,8 @@ use crate::config::Config;
 use crate::config::Notifier;
 use crate::handlers::{Callback, DefaultHandler};
 use crate::info::*;
+#[zenoh_macros::unstable]
+use crate::interest::{InterestBuilder, InterestState};
 use crate::key_expr::KeyExprInner;
 #[zenoh_macros::unstable]
 use crate::liveliness::{Liveliness, LivelinessTokenState};
@@ -46,6 +48,8 @@ use std::convert::TryFrom;
 use std::convert::TryInto;
 use std::fmt;
 use std::ops::Deref;
+#[zenoh_macros::unstable]
+use std::sync::atomic::AtomicBool;
 use std::sync::atomic::{AtomicU16, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::sync::RwLock;
@@ -55,6 +59,11 @@ use zenoh_buffers::ZBuf;
 use zenoh_collections::SingleOrVec;
 use zenoh_config::unwrap_or_default;
 use zenoh_core::{zconfigurable, zread, Resolve, ResolveClosure, ResolveFuture, SyncResolve};
+#[zenoh_macros::unstable]
+use zenoh_protocol::network::declare::{
+    interest::{Interest, InterestId},
+    DeclareInterest, UndeclareInterest,
+};
 use zenoh_protocol::network::AtomicRequestId;
 use zenoh_protocol::network::RequestId;
 use zenoh_protocol::{
@@ -106,6 +115,8 @@ pub(crate) struct SessionState {
     pub(crate) tokens: HashMap<Id, Arc<LivelinessTokenState>>,
     #[cfg(feature = "unstable")]
     pub(crate) matching_listeners: HashMap<Id, Arc<MatchingListenerState>>,
+    #[cfg(feature = "unstable")]
+    pub(crate) interests: HashMap<InterestId, Arc<InterestState>>,
     pub(crate) queries: HashMap<RequestId, QueryState>,
     pub(crate) aggregated_subscribers: Vec<OwnedKeyExpr>,
     //pub(crate) aggregated_publishers: Vec<OwnedKeyExpr>,
@@ -130,6 +141,8 @@ impl SessionState {
             tokens: HashMap::new(),
             #[cfg(feature = "unstable")]
             matching_listeners: HashMap::new(),
+            #[cfg(feature = "unstable")]
+            interests: HashMap::new(),
             queries: HashMap::new(),
             aggregated_subscribers,
             //aggregated_publishers,
@@ -332,6 +345,7 @@ impl<'s, 'a> SessionDeclarations<'s, 'a> for SessionRef<'a> {
             key_expr: key_expr.try_into().map_err(Into::into),
             congestion_control: CongestionControl::default(),
             priority: Priority::default(),
+            is_express: false,
             destination: Locality::default(),
         }
     }
@@ -348,6 +362,32 @@ impl<'s, 'a> SessionDeclarations<'s, 'a> for SessionRef<'a> {
     }
 }
 
+impl<'s, 'a> QuerierDeclarations<'s, 'a> for SessionRef<'a> {
+    fn declare_querier<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> QuerierBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        let timeout = {
+            let conf = self.runtime.config().lock();
+            Duration::from_millis(unwrap_or_default!(conf.queries_default_timeout()))
+        };
+        QuerierBuilder {
+            session: self.clone(),
+            key_expr: key_expr.try_into().map_err(Into::into),
+            target: QueryTarget::default(),
+            consolidation: QueryConsolidation::default(),
+            destination: Locality::default(),
+            timeout,
+            congestion_control: CongestionControl::Block,
+            priority: Priority::default(),
+        }
+    }
+}
+
 impl Deref for SessionRef<'_> {
     type Target = Session;
 
@@ -524,8 +564,11 @@ impl Session {
             self.task_controller.terminate_all(Duration::from_secs(10));
             self.runtime.close().await?;
 
+            // Closing the face undeclares the remote entities to this session, which reads
+            // its state, so the state must not be locked while doing so.
+            let primitives = zread!(self.state).primitives.as_ref().unwrap().clone();
+            primitives.send_close();
             let mut state = zwrite!(self.state);
-            state.primitives.as_ref().unwrap().send_close();
             // clean up to break cyclic references from self.state to itself
             state.primitives.take();
             state.queryables.clear();
@@ -616,6 +659,19 @@ impl<'a> SessionDeclarations<'a, 'a> for Session {
     }
 }
 
+impl<'a> QuerierDeclarations<'a, 'a> for Session {
+    fn declare_querier<'b, TryIntoKeyExpr>(
+        &'a self,
+        key_expr: TryIntoKeyExpr,
+    ) -> QuerierBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        SessionRef::Borrow(self).declare_querier(key_expr)
+    }
+}
+
 impl Session {
     /// Informs Zenoh that you intend to use `key_expr` multiple times and that it should optimize its transmission.
     ///
@@ -644,7 +700,7 @@ impl Session {
         self._declare_keyexpr(key_expr)
     }
 
-    fn _declare_keyexpr<'a, 'b: 'a>(
+    pub(crate) fn _declare_keyexpr<'a, 'b: 'a>(
         &'a self,
         key_expr: ZResult<KeyExpr<'b>>,
     ) -> impl Resolve<ZResult<KeyExpr<'b>>> + 'a {
@@ -677,6 +733,45 @@ impl Session {
         })
     }
 
+    /// Declare an [`Interest`](crate::interest::Interest) in the subscribers and queryables
+    /// matching a key expression.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_expr` - The key expression the declarations of interest should intersect
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let interest = session
+    ///     .declare_interest("key/expression/**")
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn declare_interest<'a, 'b, TryIntoKeyExpr>(
+        &'a self,
+        key_expr: TryIntoKeyExpr,
+    ) -> InterestBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        InterestBuilder {
+            session: SessionRef::Borrow(self),
+            key_expr: key_expr.try_into().map_err(Into::into),
+            subscribers: true,
+            queryables: true,
+            future: true,
+        }
+    }
+
     /// Put data.
     ///
     /// # Arguments
@@ -795,6 +890,8 @@ impl Session {
             consolidation: QueryConsolidation::default(),
             destination: Locality::default(),
             timeout,
+            congestion_control: CongestionControl::Block,
+            priority: Priority::default(),
             value: None,
             #[cfg(feature = "unstable")]
             attachment: None,
@@ -1087,7 +1184,7 @@ impl Session {
             #[cfg(feature = "unstable")]
             {
                 let state = zread!(self.state);
-                self.update_status_up(&state, &key_expr)
+                self.update_status_up(&state, &key_expr, false)
             }
         }
 
@@ -1151,7 +1248,7 @@ impl Session {
                             #[cfg(feature = "unstable")]
                             {
                                 let state = zread!(self.state);
-                                self.update_status_down(&state, &sub_state.key_expr)
+                                self.update_status_down(&state, &sub_state.key_expr, false)
                             }
                         }
                     }
@@ -1178,7 +1275,7 @@ impl Session {
                             #[cfg(feature = "unstable")]
                             {
                                 let state = zread!(self.state);
-                                self.update_status_down(&state, &sub_state.key_expr)
+                                self.update_status_down(&state, &sub_state.key_expr, false)
                             }
                         }
                     }
@@ -1229,6 +1326,14 @@ impl Session {
                         ext_info: qabl_info,
                     }),
                 });
+
+                #[cfg(feature = "unstable")]
+                {
+                    let state = zread!(self.state);
+                    if let Ok(expr) = state.local_wireexpr_to_expr(key_expr) {
+                        self.update_status_up(&state, &expr, true)
+                    }
+                }
             }
         }
         #[cfg(not(feature = "complete_n"))]
@@ -1257,6 +1362,14 @@ impl Session {
                         ext_info: qabl_info,
                     }),
                 });
+
+                #[cfg(feature = "unstable")]
+                {
+                    let state = zread!(self.state);
+                    if let Ok(expr) = state.local_wireexpr_to_expr(key_expr) {
+                        self.update_status_up(&state, &expr, true)
+                    }
+                }
             }
         }
         Ok(qable_state)
@@ -1322,6 +1435,16 @@ impl Session {
                                     ext_info: qabl_info,
                                 }),
                             });
+
+                            #[cfg(feature = "unstable")]
+                            {
+                                let state = zread!(self.state);
+                                if let Ok(expr) =
+                                    state.local_wireexpr_to_expr(&qable_state.key_expr)
+                                {
+                                    self.update_status_down(&state, &expr, true)
+                                }
+                            }
                         }
                         #[cfg(not(feature = "complete_n"))]
                         {
@@ -1341,6 +1464,16 @@ impl Session {
                                         ext_info: qabl_info,
                                     }),
                                 });
+
+                                #[cfg(feature = "unstable")]
+                                {
+                                    let state = zread!(self.state);
+                                    if let Ok(expr) =
+                                        state.local_wireexpr_to_expr(&qable_state.key_expr)
+                                    {
+                                        self.update_status_down(&state, &expr, true)
+                                    }
+                                }
                             }
                         }
                     }
@@ -1358,6 +1491,14 @@ impl Session {
                             },
                         }),
                     });
+
+                    #[cfg(feature = "unstable")]
+                    {
+                        let state = zread!(self.state);
+                        if let Ok(expr) = state.local_wireexpr_to_expr(&qable_state.key_expr) {
+                            self.update_status_down(&state, &expr, true)
+                        }
+                    }
                 }
             }
             Ok(())
@@ -1425,21 +1566,91 @@ impl Session {
         }
     }
 
+    #[zenoh_macros::unstable]
+    pub(crate) fn declare_interest_inner(
+        &self,
+        key_expr: &KeyExpr,
+        subscribers: bool,
+        queryables: bool,
+        future: bool,
+    ) -> ZResult<Arc<InterestState>> {
+        let mut state = zwrite!(self.state);
+        log::trace!("declare_interest({:?})", key_expr);
+        let id = state.decl_id_counter.fetch_add(1, Ordering::SeqCst) as InterestId;
+        let interest_state = Arc::new(InterestState {
+            id,
+            key_expr: key_expr.clone().into_owned(),
+            future,
+            complete: AtomicBool::new(false),
+        });
+        state.interests.insert(id, interest_state.clone());
+        let primitives = state.primitives.as_ref().unwrap().clone();
+        drop(state);
+        let mut interest = Interest::CURRENT;
+        if subscribers {
+            interest = interest | Interest::SUBSCRIBERS;
+        }
+        if queryables {
+            interest = interest | Interest::QUERYABLES;
+        }
+        if future {
+            interest = interest | Interest::FUTURE;
+        }
+        primitives.send_declare(Declare {
+            ext_qos: declare::ext::QoSType::declare_default(),
+            ext_tstamp: None,
+            ext_nodeid: declare::ext::NodeIdType::default(),
+            body: DeclareBody::DeclareInterest(DeclareInterest {
+                id,
+                wire_expr: key_expr.to_wire(self).to_owned(),
+                interest,
+            }),
+        });
+        Ok(interest_state)
+    }
+
+    #[zenoh_macros::unstable]
+    pub(crate) fn undeclare_interest(&self, id: InterestId) -> ZResult<()> {
+        let mut state = zwrite!(self.state);
+        if let Some(interest_state) = state.interests.remove(&id) {
+            trace!("undeclare_interest({:?})", interest_state);
+            if interest_state.future {
+                let primitives = state.primitives.as_ref().unwrap().clone();
+                drop(state);
+                primitives.send_declare(Declare {
+                    ext_qos: ext::QoSType::declare_default(),
+                    ext_tstamp: None,
+                    ext_nodeid: ext::NodeIdType::default(),
+                    body: DeclareBody::UndeclareInterest(UndeclareInterest {
+                        id,
+                        ext_wire_expr: WireExprType::null(),
+                    }),
+                });
+            }
+            Ok(())
+        } else {
+            Err(zerror!("Unable to find interest").into())
+        }
+    }
+
     #[zenoh_macros::unstable]
     pub(crate) fn declare_matches_listener_inner(
         &self,
-        publisher: &Publisher,
+        key_expr: &KeyExpr,
+        destination: Locality,
+        match_type: MatchingStatusType,
         callback: Callback<'static, MatchingStatus>,
     ) -> ZResult<Arc<MatchingListenerState>> {
         let mut state = zwrite!(self.state);
 
         let id = state.decl_id_counter.fetch_add(1, Ordering::SeqCst);
-        log::trace!("matches_listener({:?}) => {id}", publisher.key_expr);
+        log::trace!("matches_listener({:?}, {:?}) => {id}", key_expr, match_type);
         let listener_state = Arc::new(MatchingListenerState {
             id,
             current: std::sync::Mutex::new(false),
-            destination: publisher.destination,
-            key_expr: publisher.key_expr.clone().into_owned(),
+            destination,
+            match_type,
+            key_expr: key_expr.clone().into_owned(),
             callback,
         });
         state.matching_listeners.insert(id, listener_state.clone());
@@ -1447,8 +1658,8 @@ impl Session {
         match listener_state.current.lock() {
             Ok(mut current) => {
                 if self
-                    .matching_status(&publisher.key_expr, listener_state.destination)
-                    .map(|s| s.matching_subscribers())
+                    .matching_status(key_expr, destination, match_type)
+                    .map(|s| s.matching)
                     .unwrap_or(true)
                 {
                     *current = true;
@@ -1465,6 +1676,7 @@ impl Session {
         &self,
         key_expr: &KeyExpr,
         destination: Locality,
+        match_type: MatchingStatusType,
     ) -> ZResult<MatchingStatus> {
         use crate::net::routing::dispatcher::tables::RoutingExpr;
         let router = self.runtime.router();
@@ -1474,25 +1686,43 @@ impl Session {
             key_expr.as_str(),
         );
 
-        let route = crate::net::routing::dispatcher::pubsub::get_local_data_route(
-            &tables,
-            &res,
-            &mut RoutingExpr::new(&tables.root_res, key_expr.as_str()),
-        );
+        let faces: Vec<_> = match match_type {
+            MatchingStatusType::Subscribers => {
+                crate::net::routing::dispatcher::pubsub::get_local_data_route(
+                    &tables,
+                    &res,
+                    &mut RoutingExpr::new(&tables.root_res, key_expr.as_str()),
+                )
+                .values()
+                .map(|dir| dir.0.clone())
+                .collect()
+            }
+            MatchingStatusType::Queryables(complete) => {
+                crate::net::routing::dispatcher::queries::get_local_query_route(
+                    &tables,
+                    &res,
+                    &mut RoutingExpr::new(&tables.root_res, key_expr.as_str()),
+                )
+                .iter()
+                .filter(|qabl| !complete || qabl.complete > 0)
+                .map(|qabl| qabl.direction.0.clone())
+                .collect()
+            }
+        };
 
         drop(tables);
         let matching = match destination {
-            Locality::Any => !route.is_empty(),
+            Locality::Any => !faces.is_empty(),
             Locality::Remote => {
                 if let Some(face) = zread!(self.state).primitives.as_ref() {
-                    route.values().any(|dir| !Arc::ptr_eq(&dir.0, &face.state))
+                    faces.iter().any(|f| !Arc::ptr_eq(f, &face.state))
                 } else {
-                    !route.is_empty()
+                    !faces.is_empty()
                 }
             }
             Locality::SessionLocal => {
                 if let Some(face) = zread!(self.state).primitives.as_ref() {
-                    route.values().any(|dir| Arc::ptr_eq(&dir.0, &face.state))
+                    faces.iter().any(|f| Arc::ptr_eq(f, &face.state))
                 } else {
                     false
                 }
@@ -1502,9 +1732,16 @@ impl Session {
     }
 
     #[zenoh_macros::unstable]
-    pub(crate) fn update_status_up(&self, state: &SessionState, key_expr: &KeyExpr) {
+    pub(crate) fn update_status_up(
+        &self,
+        state: &SessionState,
+        key_expr: &KeyExpr,
+        queryables: bool,
+    ) {
         for msub in state.matching_listeners.values() {
-            if key_expr.intersects(&msub.key_expr) {
+            if queryables == matches!(msub.match_type, MatchingStatusType::Queryables(_))
+                && key_expr.intersects(&msub.key_expr)
+            {
                 // Cannot hold session lock when calling tables (matching_status())
                 // TODO: check which ZRuntime should be used
                 self.task_controller
@@ -1515,10 +1752,12 @@ impl Session {
                             match msub.current.lock() {
                                 Ok(mut current) => {
                                     if !*current {
-                                        if let Ok(status) = session
-                                            .matching_status(&msub.key_expr, msub.destination)
-                                        {
-                                            if status.matching_subscribers() {
+                                        if let Ok(status) = session.matching_status(
+                                            &msub.key_expr,
+                                            msub.destination,
+                                            msub.match_type,
+                                        ) {
+                                            if status.matching {
                                                 *current = true;
                                                 let callback = msub.callback.clone();
                                                 (callback)(status)
@@ -1540,9 +1779,16 @@ impl Session {
     }
 
     #[zenoh_macros::unstable]
-    pub(crate) fn update_status_down(&self, state: &SessionState, key_expr: &KeyExpr) {
+    pub(crate) fn update_status_down(
+        &self,
+        state: &SessionState,
+        key_expr: &KeyExpr,
+        queryables: bool,
+    ) {
         for msub in state.matching_listeners.values() {
-            if key_expr.intersects(&msub.key_expr) {
+            if queryables == matches!(msub.match_type, MatchingStatusType::Queryables(_))
+                && key_expr.intersects(&msub.key_expr)
+            {
                 // Cannot hold session lock when calling tables (matching_status())
                 // TODO: check which ZRuntime should be used
                 self.task_controller
@@ -1553,10 +1799,12 @@ impl Session {
                             match msub.current.lock() {
                                 Ok(mut current) => {
                                     if *current {
-                                        if let Ok(status) = session
-                                            .matching_status(&msub.key_expr, msub.destination)
-                                        {
-                                            if !status.matching_subscribers() {
+                                        if let Ok(status) = session.matching_status(
+                                            &msub.key_expr,
+                                            msub.destination,
+                                            msub.match_type,
+                                        ) {
+                                            if !status.matching {
                                                 *current = false;
                                                 let callback = msub.callback.clone();
                                                 (callback)(status)
@@ -1746,6 +1994,8 @@ impl Session {
         consolidation: QueryConsolidation,
         destination: Locality,
         timeout: Duration,
+        congestion_control: CongestionControl,
+        priority: Priority,
         value: Option<Value>,
         #[cfg(feature = "unstable")] attachment: Option<Attachment>,
         callback: Callback<'static, Reply>,
@@ -1833,7 +2083,7 @@ impl Session {
             primitives.send_request(Request {
                 id: qid,
                 wire_expr: wexpr.clone(),
-                ext_qos: request::ext::QoSType::request_default(),
+                ext_qos: request::ext::QoSType::new(priority.into(), congestion_control, false),
                 ext_tstamp: None,
                 ext_nodeid: request::ext::NodeIdType::default(),
                 ext_target: target,
@@ -2077,6 +2327,7 @@ impl<'s> SessionDeclarations<'s, 'static> for Arc<Session> {
             key_expr: key_expr.try_into().map_err(Into::into),
             congestion_control: CongestionControl::default(),
             priority: Priority::default(),
+            is_express: false,
             destination: Locality::default(),
         }
     }
@@ -2112,6 +2363,44 @@ impl<'s> SessionDeclarations<'s, 'static> for Arc<Session> {
     }
 }
 
+impl<'s> QuerierDeclarations<'s, 'static> for Arc<Session> {
+    /// Create a [`Querier`](crate::query::Querier) for the given key expression.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_expr` - The key expression matching the queryables to query
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap().into_arc();
+    /// let querier = session.declare_querier("key/expression")
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// tokio::task::spawn(async move {
+    ///     let replies = querier.get().res().await.unwrap();
+    ///     while let Ok(reply) = replies.recv_async().await {
+    ///         println!("Received {:?}", reply.sample);
+    ///     }
+    /// }).await;
+    /// # }
+    /// ```
+    fn declare_querier<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> QuerierBuilder<'static, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        SessionRef::Shared(self.clone()).declare_querier(key_expr)
+    }
+}
+
 impl Primitives for Session {
     fn send_declare(&self, msg: zenoh_protocol::network::Declare) {
         match msg.body {
@@ -2149,7 +2438,7 @@ impl Primitives for Session {
                     let state = zread!(self.state);
                     match state.wireexpr_to_keyexpr(&m.wire_expr, false) {
                         Ok(expr) => {
-                            self.update_status_up(&state, &expr);
+                            self.update_status_up(&state, &expr, false);
 
                             if expr
                                 .as_str()
@@ -2179,7 +2468,7 @@ impl Primitives for Session {
                     let state = zread!(self.state);
                     match state.wireexpr_to_keyexpr(&m.ext_wire_expr.wire_expr, false) {
                         Ok(expr) => {
-                            self.update_status_down(&state, &expr);
+                            self.update_status_down(&state, &expr, false);
 
                             if expr
                                 .as_str()
@@ -2208,15 +2497,55 @@ impl Primitives for Session {
             }
             zenoh_protocol::network::DeclareBody::DeclareQueryable(m) => {
                 trace!("recv DeclareQueryable {} {:?}", m.id, m.wire_expr);
+                #[cfg(feature = "unstable")]
+                {
+                    let state = zread!(self.state);
+                    match state.wireexpr_to_keyexpr(&m.wire_expr, false) {
+                        Ok(expr) => {
+                            // The declaration may also update the completeness of a known queryable.
+                            self.update_status_up(&state, &expr, true);
+                            self.update_status_down(&state, &expr, true);
+                        }
+                        Err(err) => {
+                            log::error!("Received DeclareQueryable for unkown wire_expr: {}", err)
+                        }
+                    }
+                }
             }
             zenoh_protocol::network::DeclareBody::UndeclareQueryable(m) => {
                 trace!("recv UndeclareQueryable {:?}", m.id);
+                #[cfg(feature = "unstable")]
+                {
+                    let state = zread!(self.state);
+                    match state.wireexpr_to_keyexpr(&m.ext_wire_expr.wire_expr, false) {
+                        Ok(expr) => self.update_status_down(&state, &expr, true),
+                        Err(err) => {
+                            log::error!("Received Forget Queryable for unkown key_expr: {}", err)
+                        }
+                    }
+                }
             }
             DeclareBody::DeclareToken(_) => todo!(),
             DeclareBody::UndeclareToken(_) => todo!(),
-            DeclareBody::DeclareInterest(_) => todo!(),
-            DeclareBody::FinalInterest(_) => todo!(),
-            DeclareBody::UndeclareInterest(_) => todo!(),
+            DeclareBody::DeclareInterest(m) => {
+                trace!("recv DeclareInterest {} {:?}", m.id, m.wire_expr);
+            }
+            DeclareBody::FinalInterest(m) => {
+                trace!("recv FinalInterest {}", m.id);
+                #[cfg(feature = "unstable")]
+                {
+                    let state = zread!(self.state);
+                    match state.interests.get(&m.id) {
+                        Some(interest_state) => {
+                            interest_state.complete.store(true, Ordering::Release)
+                        }
+                        None => log::debug!("Received FinalInterest for unknown interest {}", m.id),
+                    }
+                }
+            }
+            DeclareBody::UndeclareInterest(m) => {
+                trace!("recv UndeclareInterest {}", m.id);
+            }
         }
     }
 
@@ -2690,6 +3019,55 @@ pub trait SessionDeclarations<'s, 'a> {
     fn info(&'s self) -> SessionInfo<'a>;
 }
 
+/// Functions to create a [`Querier`](crate::query::Querier)
+///
+/// This trait is implemented by [`Session`](crate::session::Session) itself and
+/// by wrappers [`SessionRef`](crate::session::SessionRef) and [`Arc<Session>`](crate::session::Arc<Session>)
+///
+/// # Examples
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let querier = session.declare_querier("key/expression")
+///     .res()
+///     .await
+///     .unwrap();
+/// let replies = querier.get().res().await.unwrap();
+/// # }
+/// ```
+pub trait QuerierDeclarations<'s, 'a> {
+    /// Create a [`Querier`](crate::query::Querier) for the given key expression.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_expr` - The key expression matching the queryables to query
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let querier = session.declare_querier("key/expression")
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// let replies = querier.get().res().await.unwrap();
+    /// # }
+    /// ```
+    fn declare_querier<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> QuerierBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>;
+}
+
 impl crate::net::primitives::EPrimitives for Session {
     #[inline]
     fn send_declare(&self, ctx: crate::net::routing::RoutingContext<Declare>) {
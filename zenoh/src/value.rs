@@ -21,10 +21,11 @@ use std::convert::TryFrom;
 use std::sync::Arc;
 
 use zenoh_collections::Properties;
-use zenoh_result::ZError;
+use zenoh_result::{ZError, ZResult};
 
 use crate::buffers::ZBuf;
 use crate::prelude::{Encoding, KnownEncoding, Sample, SplitBuffer};
+use crate::serialization::{self, ZDeserialize, ZSerialize};
 #[cfg(feature = "shared-memory")]
 use zenoh_shm::SharedMemoryBuf;
 
@@ -61,6 +62,17 @@ impl Value {
         self.encoding = encoding;
         self
     }
+
+    /// Creates a zenoh Value from a value serialized with [`serialization`](crate::serialization),
+    /// with the [`Encoding::ZENOH_SERIALIZED`] encoding.
+    pub fn serialize<T: ZSerialize + ?Sized>(t: &T) -> Self {
+        Value::new(serialization::serialize(t)).encoding(Encoding::ZENOH_SERIALIZED)
+    }
+
+    /// Deserializes the payload of this zenoh Value with [`serialization`](crate::serialization).
+    pub fn deserialize<T: ZDeserialize>(&self) -> ZResult<T> {
+        serialization::deserialize(&self.payload)
+    }
 }
 
 impl std::fmt::Debug for Value {
@@ -0,0 +1,240 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use zenoh::prelude::sync::*;
+use zenoh::subscriber::Subscriber;
+use zenoh_core::zlock;
+
+const SLEEP: Duration = Duration::from_secs(1);
+const MSG_COUNT: usize = 10;
+
+fn open_sub(endpoint: &str, acl: &str, auth: Option<&str>) -> Session {
+    let mut config = Config::default();
+    config
+        .insert_json5("listen/endpoints", &format!(r#"["{endpoint}"]"#))
+        .unwrap();
+    config.insert_json5("access_control", acl).unwrap();
+    if let Some(auth) = auth {
+        config.insert_json5("transport/auth/usrpwd", auth).unwrap();
+    }
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    zenoh::open(config).res().unwrap()
+}
+
+fn subscribe(session: &Session) -> (Subscriber<'_, ()>, Arc<Mutex<HashMap<String, usize>>>) {
+    let received: Arc<Mutex<HashMap<String, usize>>> = Arc::new(Mutex::new(HashMap::new()));
+    let c_received = received.clone();
+    let subscriber = session
+        .declare_subscriber("test/acl/**")
+        .callback(move |sample| {
+            let value = sample.value.to_string();
+            *zlock!(c_received).entry(value).or_default() += 1;
+        })
+        .res()
+        .unwrap();
+    (subscriber, received)
+}
+
+fn open_pub(endpoint: &str, auth: Option<&str>) -> Session {
+    let mut config = Config::default();
+    config
+        .insert_json5("connect/endpoints", &format!(r#"["{endpoint}"]"#))
+        .unwrap();
+    if let Some(auth) = auth {
+        config.insert_json5("transport/auth/usrpwd", auth).unwrap();
+    }
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    zenoh::open(config).res().unwrap()
+}
+
+fn put_all(session: &Session, key_expr: &str, value: &str) {
+    for _ in 0..MSG_COUNT {
+        session.put(key_expr, value).res().unwrap();
+    }
+}
+
+#[test]
+fn acl_by_keyexpr() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let endpoint = "tcp/127.0.0.1:38450";
+
+    let acl = r#"{
+        enabled: true,
+        default_permission: "allow",
+        rules: [
+            {
+                key_exprs: ["test/acl/denied/**"],
+                messages: ["put", "delete"],
+                flows: ["ingress"],
+                permission: "deny",
+            },
+        ],
+    }"#;
+    let sub = open_sub(endpoint, acl, None);
+    let (subscriber, received) = subscribe(&sub);
+    let publ = open_pub(endpoint, None);
+    std::thread::sleep(SLEEP);
+
+    put_all(&publ, "test/acl/allowed/a", "allowed");
+    put_all(&publ, "test/acl/denied/a", "denied");
+    std::thread::sleep(SLEEP);
+
+    let received = zlock!(received).clone();
+    assert_eq!(received.get("allowed"), Some(&MSG_COUNT));
+    assert_eq!(received.get("denied"), None);
+
+    publ.close().res().unwrap();
+    subscriber.undeclare().res().unwrap();
+    sub.close().res().unwrap();
+}
+
+#[test]
+fn acl_by_username() {
+    let _ = env_logger::builder().is_test(true).try_init();
+    let endpoint = "tcp/127.0.0.1:38451";
+
+    let dictionary = std::env::temp_dir().join("zenoh-test-acl-credentials.txt");
+    std::fs::write(&dictionary, "alice:alice_pwd\nbob:bob_pwd\n").unwrap();
+
+    let acl = r#"{
+        enabled: true,
+        default_permission: "deny",
+        rules: [
+            {
+                usernames: ["alice"],
+                key_exprs: ["test/acl/**"],
+                messages: ["put"],
+                flows: ["ingress"],
+                permission: "allow",
+            },
+            {
+                key_exprs: ["test/acl/**"],
+                messages: ["declare_subscriber"],
+                flows: ["egress"],
+                permission: "allow",
+            },
+        ],
+    }"#;
+    let sub = open_sub(
+        endpoint,
+        acl,
+        Some(&format!(
+            r#"{{ dictionary_file: "{}" }}"#,
+            dictionary.to_string_lossy()
+        )),
+    );
+    let (subscriber, received) = subscribe(&sub);
+    let alice = open_pub(
+        endpoint,
+        Some(r#"{ user: "alice", password: "alice_pwd" }"#),
+    );
+    let bob = open_pub(endpoint, Some(r#"{ user: "bob", password: "bob_pwd" }"#));
+    std::thread::sleep(SLEEP);
+
+    put_all(&alice, "test/acl/a", "alice");
+    put_all(&bob, "test/acl/a", "bob");
+    std::thread::sleep(SLEEP);
+
+    let received = zlock!(received).clone();
+    assert_eq!(received.get("alice"), Some(&MSG_COUNT));
+    assert_eq!(received.get("bob"), None);
+
+    alice.close().res().unwrap();
+    bob.close().res().unwrap();
+    subscriber.undeclare().res().unwrap();
+    sub.close().res().unwrap();
+    let _ = std::fs::remove_file(dictionary);
+}
+
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn acl_stats() {
+    use zenoh::prelude::r#async::AsyncResolve;
+    use zenoh::runtime::{AdminSpace, Runtime};
+
+    let _ = env_logger::builder().is_test(true).try_init();
+    let endpoint = "tcp/127.0.0.1:38453";
+
+    let mut config = Config::default();
+    config
+        .insert_json5("listen/endpoints", &format!(r#"["{endpoint}"]"#))
+        .unwrap();
+    config
+        .insert_json5(
+            "access_control",
+            r#"{
+                enabled: true,
+                default_permission: "allow",
+                rules: [
+                    {
+                        key_exprs: ["test/acl/denied/**"],
+                        messages: ["put"],
+                        flows: ["ingress"],
+                        permission: "deny",
+                    },
+                ],
+            }"#,
+        )
+        .unwrap();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let runtime = Runtime::new(config).await.unwrap();
+    AdminSpace::start(
+        &runtime,
+        zenoh::plugins::PluginsManager::static_plugins_only(),
+        String::from("test"),
+    )
+    .await;
+    let sub = zenoh::init(runtime).res_async().await.unwrap();
+    let (subscriber, received) = subscribe(&sub);
+    let mut config = Config::default();
+    config
+        .insert_json5("connect/endpoints", &format!(r#"["{endpoint}"]"#))
+        .unwrap();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let publ = zenoh::open(config).res_async().await.unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    for _ in 0..MSG_COUNT {
+        publ.put("test/acl/denied/a", "denied")
+            .res_async()
+            .await
+            .unwrap();
+        publ.put("test/acl/allowed/a", "allowed")
+            .res_async()
+            .await
+            .unwrap();
+    }
+    tokio::time::sleep(SLEEP).await;
+
+    let reply = sub
+        .get(format!("@/router/{}/access_control", sub.zid()))
+        .res_async()
+        .await
+        .unwrap()
+        .recv_async()
+        .await
+        .unwrap();
+    let stats: serde_json::Value =
+        serde_json::from_slice(&reply.sample.unwrap().value.payload.contiguous()).unwrap();
+    assert_eq!(stats["enabled"], true);
+    assert_eq!(stats["denied"]["put"], MSG_COUNT);
+    assert_eq!(stats["denied"]["delete"], 0);
+    assert_eq!(zlock!(received).get("allowed"), Some(&MSG_COUNT));
+
+    publ.close().res_async().await.unwrap();
+    subscriber.undeclare().res_async().await.unwrap();
+    sub.close().res_async().await.unwrap();
+}
@@ -0,0 +1,115 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::time::Duration;
+use zenoh::handlers::{LatestOnly, RingBuffer};
+use zenoh::prelude::sync::*;
+use zenoh::query::ConsolidationMode;
+
+const RECV_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[test]
+fn pubsub_with_ringbuffer() {
+    let zenoh = zenoh::open(Config::default()).res().unwrap();
+    let sub = zenoh
+        .declare_subscriber("test/ringbuffer")
+        .with(RingBuffer::try_new(3).unwrap())
+        .res()
+        .unwrap();
+    for i in 0..10 {
+        zenoh
+            .put("test/ringbuffer", format!("put{i}"))
+            .res()
+            .unwrap();
+    }
+    // Only the last 3 samples are kept, in order
+    for i in 7..10 {
+        let sample = sub.recv_timeout(RECV_TIMEOUT).unwrap().unwrap();
+        assert_eq!(sample.value.to_string(), format!("put{i}"));
+    }
+    assert!(sub.try_recv().unwrap().is_none());
+    assert!(sub.recv_timeout(RECV_TIMEOUT).unwrap().is_none());
+}
+
+#[test]
+fn ringbuffer_with_zero_capacity() {
+    assert!(RingBuffer::try_new(0).is_err());
+}
+
+#[test]
+fn pubsub_with_latest_only() {
+    let zenoh = zenoh::open(Config::default()).res().unwrap();
+    let sub = zenoh
+        .declare_subscriber("test/latest_only")
+        .with(LatestOnly)
+        .res()
+        .unwrap();
+    for i in 0..10 {
+        zenoh
+            .put("test/latest_only", format!("put{i}"))
+            .res()
+            .unwrap();
+    }
+    let sample = sub.recv().unwrap();
+    assert_eq!(sample.value.to_string(), "put9");
+    assert!(sub.try_recv().unwrap().is_none());
+}
+
+#[test]
+fn queries_with_ringbuffer() {
+    let zenoh = zenoh::open(Config::default()).res().unwrap();
+    let qabl = zenoh
+        .declare_queryable("test/ringbuffer/query")
+        .with(RingBuffer::try_new(2).unwrap())
+        .res()
+        .unwrap();
+    for _ in 0..3 {
+        let _ = zenoh.get("test/ringbuffer/query").res().unwrap();
+    }
+    // The oldest query is dropped and finalized
+    for _ in 0..2 {
+        let query = qabl.recv_timeout(RECV_TIMEOUT).unwrap().unwrap();
+        for i in 0..5 {
+            query
+                .reply(Ok(Sample::new(
+                    query.key_expr().clone(),
+                    format!("reply{i}"),
+                )))
+                .res()
+                .unwrap();
+        }
+    }
+    assert!(qabl.try_recv().unwrap().is_none());
+
+    let replies = zenoh
+        .get("test/ringbuffer/query")
+        .consolidation(ConsolidationMode::None)
+        .with(LatestOnly)
+        .res()
+        .unwrap();
+    let query = qabl.recv().unwrap();
+    for i in 0..5 {
+        query
+            .reply(Ok(Sample::new(
+                query.key_expr().clone(),
+                format!("reply{i}"),
+            )))
+            .res()
+            .unwrap();
+    }
+    drop(query);
+    // Only the latest reply is kept, then the handler is disconnected
+    let reply = replies.recv().unwrap();
+    assert_eq!(reply.sample.unwrap().value.to_string(), "reply4");
+    assert!(replies.recv().is_err());
+}
@@ -161,6 +161,270 @@ fn downsampling_by_keyexpr() {
     downsampling_by_keyexpr_impl(false);
 }
 
+fn downsampling_per_key_keep_latest_impl(egress: bool) {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    use zenoh::prelude::sync::*;
+
+    let ds_cfg = format!(
+        r#"
+          [
+            {{
+              flow: "{}",
+              rules: [
+                {{ key_expr: "test/downsamples_per_key/*", freq: 2, per_key: true, keep_latest: true }},
+              ],
+            }},
+          ] "#,
+        (if egress { "egress" } else { "ingress" })
+    );
+
+    // declare subscriber
+    let mut config_sub = Config::default();
+    if !egress {
+        config_sub.insert_json5("downsampling", &ds_cfg).unwrap();
+    }
+    config_sub
+        .insert_json5("listen/endpoints", r#"["tcp/127.0.0.1:38453"]"#)
+        .unwrap();
+    config_sub
+        .scouting
+        .multicast
+        .set_enabled(Some(false))
+        .unwrap();
+    let zenoh_sub = zenoh::open(config_sub).res().unwrap();
+
+    let received = Arc::new(Mutex::new(vec![]));
+    let c_received = received.clone();
+    let sub = zenoh_sub
+        .declare_subscriber("test/downsamples_per_key/*")
+        .callback(move |sample| {
+            zlock!(c_received).push((sample.key_expr.to_string(), sample.value.to_string()))
+        })
+        .res()
+        .unwrap();
+
+    // declare publisher
+    let mut config_pub = Config::default();
+    if egress {
+        config_pub.insert_json5("downsampling", &ds_cfg).unwrap();
+    }
+    config_pub
+        .insert_json5("connect/endpoints", r#"["tcp/127.0.0.1:38453"]"#)
+        .unwrap();
+    config_pub
+        .scouting
+        .multicast
+        .set_enabled(Some(false))
+        .unwrap();
+    let zenoh_pub = zenoh::open(config_pub).res().unwrap();
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    // Each key expression lets the first message through and sends the latest one at the period end
+    for i in 0..20 {
+        for key in ["a", "b"] {
+            zenoh_pub
+                .put(format!("test/downsamples_per_key/{key}"), i.to_string())
+                .res()
+                .unwrap();
+        }
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    let mut received = zlock!(received).clone();
+    received.sort();
+    let expected: Vec<(String, String)> = ["a", "b"]
+        .iter()
+        .flat_map(|key| {
+            ["0", "19"].map(|value| (format!("test/downsamples_per_key/{key}"), value.to_string()))
+        })
+        .collect();
+    assert_eq!(received, expected);
+
+    zenoh_pub.close().res().unwrap();
+    sub.undeclare().res().unwrap();
+    zenoh_sub.close().res().unwrap();
+}
+
+#[test]
+fn downsampling_per_key_keep_latest() {
+    downsampling_per_key_keep_latest_impl(true);
+    downsampling_per_key_keep_latest_impl(false);
+}
+
+fn downsampling_session(
+    mode: &str,
+    endpoint: &str,
+    config: Option<(&str, &str)>,
+) -> zenoh::Session {
+    use zenoh::prelude::sync::*;
+
+    let mut zconfig = Config::default();
+    if let Some((key, value)) = config {
+        zconfig.insert_json5(key, value).unwrap();
+    }
+    zconfig
+        .insert_json5(mode, &format!(r#"["{endpoint}"]"#))
+        .unwrap();
+    zconfig.scouting.multicast.set_enabled(Some(false)).unwrap();
+    zenoh::open(zconfig).res().unwrap()
+}
+
+#[allow(clippy::type_complexity)]
+fn received_values<'a>(
+    session: &'a zenoh::Session,
+    key_expr: &str,
+) -> (
+    zenoh::subscriber::Subscriber<'a, ()>,
+    Arc<Mutex<Vec<(String, String)>>>,
+) {
+    use zenoh::prelude::sync::*;
+
+    let received = Arc::new(Mutex::new(vec![]));
+    let c_received = received.clone();
+    let sub = session
+        .declare_subscriber(key_expr.to_string())
+        .callback(move |sample| {
+            zlock!(c_received).push((sample.key_expr.to_string(), sample.value.to_string()))
+        })
+        .res()
+        .unwrap();
+    (sub, received)
+}
+
+#[test]
+fn downsampling_keep_latest_with_rate_limit() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    use zenoh::prelude::sync::*;
+
+    const ENDPOINT: &str = "tcp/127.0.0.1:38455";
+    let mut config_sub = Config::default();
+    config_sub
+        .insert_json5(
+            "downsampling",
+            r#"[
+                  {
+                    flow: "ingress",
+                    rules: [ { key_expr: "test/downsamples_rate_limit/*", freq: 2, keep_latest: true } ],
+                  },
+                ]"#,
+        )
+        .unwrap();
+    config_sub
+        .insert_json5(
+            "rate_limit",
+            r#"[
+                  {
+                    flow: "ingress",
+                    rules: [ { key_expr: "test/downsamples_rate_limit/*", messages_per_sec: 0.1, messages_burst: 1 } ],
+                  },
+                ]"#,
+        )
+        .unwrap();
+    config_sub
+        .insert_json5("listen/endpoints", &format!(r#"["{ENDPOINT}"]"#))
+        .unwrap();
+    config_sub
+        .scouting
+        .multicast
+        .set_enabled(Some(false))
+        .unwrap();
+    let zenoh_sub = zenoh::open(config_sub).res().unwrap();
+    let (sub, received) = received_values(&zenoh_sub, "test/downsamples_rate_limit/*");
+
+    let zenoh_pub = downsampling_session("connect/endpoints", ENDPOINT, None);
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    for i in 0..20 {
+        zenoh_pub
+            .put("test/downsamples_rate_limit/a", i.to_string())
+            .res()
+            .unwrap();
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    // The latest message sent at the period end is rate limited like any other one
+    assert_eq!(
+        *zlock!(received),
+        vec![("test/downsamples_rate_limit/a".to_string(), "0".to_string())]
+    );
+
+    zenoh_pub.close().res().unwrap();
+    sub.undeclare().res().unwrap();
+    zenoh_sub.close().res().unwrap();
+}
+
+#[test]
+fn downsampling_wildcard_rule_and_eviction() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    use zenoh::prelude::sync::*;
+
+    const ENDPOINT: &str = "tcp/127.0.0.1:38456";
+    let zenoh_sub = downsampling_session("listen/endpoints", ENDPOINT, None);
+    let (sub, received) = received_values(&zenoh_sub, "test/downsamples_wildcard/**");
+
+    let zenoh_pub = downsampling_session(
+        "connect/endpoints",
+        ENDPOINT,
+        Some((
+            "downsampling",
+            r#"[
+                  {
+                    flow: "egress",
+                    rules: [
+                      { key_expr: "test/downsamples_wildcard/all/*", freq: 0.1 },
+                      // Shadowed by the first rule
+                      { key_expr: "test/downsamples_wildcard/all/*", freq: 1000.0 },
+                      { key_expr: "test/downsamples_wildcard/per_key/*", freq: 0.1, per_key: true, max_keys: 1, keep_latest: true },
+                    ],
+                  },
+                ]"#,
+        )),
+    );
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    // The rules apply to all the key expressions they include, together unless per_key is set
+    for key in ["a", "b"] {
+        zenoh_pub
+            .put(format!("test/downsamples_wildcard/all/{key}"), "all")
+            .res()
+            .unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    // Evicting a key expression sends its latest dropped message
+    for (key, value) in [("a", "first"), ("a", "latest"), ("b", "first")] {
+        zenoh_pub
+            .put(format!("test/downsamples_wildcard/per_key/{key}"), value)
+            .res()
+            .unwrap();
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    let mut received = zlock!(received).clone();
+    received.sort();
+    let expected: Vec<(String, String)> = [
+        ("all/a", "all"),
+        ("per_key/a", "first"),
+        ("per_key/a", "latest"),
+        ("per_key/b", "first"),
+    ]
+    .iter()
+    .map(|(key, value)| {
+        (
+            format!("test/downsamples_wildcard/{key}"),
+            value.to_string(),
+        )
+    })
+    .collect();
+    assert_eq!(received, expected);
+
+    zenoh_pub.close().res().unwrap();
+    sub.undeclare().res().unwrap();
+    zenoh_sub.close().res().unwrap();
+}
+
 #[cfg(unix)]
 fn downsampling_by_interface_impl(egress: bool) {
     let _ = env_logger::builder().is_test(true).try_init();
@@ -289,3 +553,658 @@ fn downsampling_config_error_wrong_strategy() {
 
     zenoh::open(config).res().unwrap();
 }
+
+fn payload_transformation_session(
+    mode: &str,
+    endpoints: &str,
+    transformation: Option<&str>,
+) -> zenoh::Session {
+    use zenoh::prelude::sync::*;
+
+    let mut config = Config::default();
+    config
+        .insert_json5("mode", &format!(r#""{mode}""#))
+        .unwrap();
+    if let Some(transformation) = transformation {
+        config
+            .insert_json5("payload_transformation", transformation)
+            .unwrap();
+        config.insert_json5("listen/endpoints", endpoints).unwrap();
+    } else {
+        config.insert_json5("connect/endpoints", endpoints).unwrap();
+    }
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    zenoh::open(config).res().unwrap()
+}
+
+#[test]
+fn payload_transformation() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    use zenoh::prelude::sync::*;
+
+    const KEY_EXPR: &str = "test/payload_transformation/secret";
+    let value = "A secret value that should remain opaque to the routers in between ".repeat(8);
+
+    let endpoints = r#"["tcp/127.0.0.1:38449"]"#;
+    let router = payload_transformation_session(
+        "router",
+        endpoints,
+        Some(
+            r#"{
+              rules: [
+                {
+                  key_exprs: ["test/payload_transformation/**"],
+                  compression: true,
+                  encryption_key: "000102030405060708090a0b0c0d0e0f",
+                },
+                // Shadowed by the first rule
+                {
+                  key_exprs: ["test/payload_transformation/**"],
+                  compression: false,
+                },
+              ],
+            }"#,
+        ),
+    );
+
+    // The client at the edge receives the original payloads while the peer receives the transformed ones
+    let edge = payload_transformation_session("client", endpoints, None);
+    let edge_received = Arc::new(Mutex::new(vec![]));
+    let c_edge_received = edge_received.clone();
+    let edge_sub = edge
+        .declare_subscriber(KEY_EXPR)
+        .callback(move |sample| {
+            zlock!(c_edge_received).push(sample.value.payload.contiguous().to_vec())
+        })
+        .res()
+        .unwrap();
+    let edge_queryable = edge
+        .declare_queryable(KEY_EXPR)
+        .callback({
+            let value = value.clone();
+            move |query| {
+                query
+                    .reply(Ok(Sample::new(query.key_expr().clone(), value.clone())))
+                    .res()
+                    .unwrap()
+            }
+        })
+        .res()
+        .unwrap();
+
+    let middle = payload_transformation_session("peer", endpoints, None);
+    let middle_received = Arc::new(Mutex::new(vec![]));
+    let c_middle_received = middle_received.clone();
+    let middle_sub = middle
+        .declare_subscriber(KEY_EXPR)
+        .callback(move |sample| {
+            zlock!(c_middle_received).push(sample.value.payload.contiguous().to_vec())
+        })
+        .res()
+        .unwrap();
+
+    let publisher = payload_transformation_session("client", endpoints, None);
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    for _ in 0..10 {
+        publisher.put(KEY_EXPR, value.clone()).res().unwrap();
+    }
+    let replies: Vec<Vec<u8>> = publisher
+        .get(KEY_EXPR)
+        .res()
+        .unwrap()
+        .into_iter()
+        .map(|reply| reply.sample.unwrap().value.payload.contiguous().to_vec())
+        .collect();
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    let edge_received = zlock!(edge_received);
+    assert_eq!(edge_received.len(), 10);
+    assert!(edge_received.iter().all(|p| p == value.as_bytes()));
+    let middle_received = zlock!(middle_received);
+    assert_eq!(middle_received.len(), 10);
+    assert!(middle_received.iter().all(|p| p != value.as_bytes()));
+    assert!(middle_received.iter().all(|p| p.len() < value.len()));
+    // Each payload is encrypted with its own nonce
+    assert_ne!(middle_received[0], middle_received[1]);
+    assert_eq!(replies, vec![value.as_bytes().to_vec()]);
+
+    publisher.close().res().unwrap();
+    middle_sub.undeclare().res().unwrap();
+    middle.close().res().unwrap();
+    edge_queryable.undeclare().res().unwrap();
+    edge_sub.undeclare().res().unwrap();
+    edge.close().res().unwrap();
+    router.close().res().unwrap();
+}
+
+#[test]
+#[should_panic(expected = "Invalid encryption key")]
+fn payload_transformation_config_error_wrong_key() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    use zenoh::prelude::sync::*;
+
+    let mut config = Config::default();
+    config
+        .insert_json5(
+            "payload_transformation",
+            r#"
+              {
+                rules: [
+                  { key_exprs: ["test/payload_transformation/**"], encryption_key: "0001" },
+                ],
+              }
+            "#,
+        )
+        .unwrap();
+
+    zenoh::open(config).res().unwrap();
+}
+
+#[test]
+fn rate_limit() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    use zenoh::prelude::sync::*;
+
+    let mut config_sub = Config::default();
+    config_sub
+        .insert_json5(
+            "rate_limit",
+            r#"
+              [
+                {
+                  flow: "ingress",
+                  reply_error: true,
+                  rules: [
+                    { key_expr: "test/rate_limit/limited", messages: ["put"], messages_per_sec: 0.1, messages_burst: 5 },
+                    { key_expr: "test/rate_limit/queried", messages: ["query"], messages_per_sec: 0.1, messages_burst: 1 },
+                  ],
+                },
+              ]
+            "#,
+        )
+        .unwrap();
+    config_sub
+        .insert_json5("listen/endpoints", r#"["tcp/127.0.0.1:38452"]"#)
+        .unwrap();
+    config_sub
+        .scouting
+        .multicast
+        .set_enabled(Some(false))
+        .unwrap();
+    let zenoh_sub = zenoh::open(config_sub).res().unwrap();
+
+    let limited_count = Arc::new(Mutex::new(0));
+    let free_count = Arc::new(Mutex::new(0));
+    let c_limited_count = limited_count.clone();
+    let c_free_count = free_count.clone();
+    let sub = zenoh_sub
+        .declare_subscriber("test/rate_limit/*")
+        .callback(move |sample| match sample.key_expr.as_str() {
+            "test/rate_limit/limited" => *zlock!(c_limited_count) += 1,
+            "test/rate_limit/free" => *zlock!(c_free_count) += 1,
+            _ => {}
+        })
+        .res()
+        .unwrap();
+    let queryable = zenoh_sub
+        .declare_queryable("test/rate_limit/queried")
+        .callback(|query| {
+            query
+                .reply(Ok(Sample::new(query.key_expr().clone(), "reply")))
+                .res()
+                .unwrap()
+        })
+        .res()
+        .unwrap();
+
+    let mut config_pub = Config::default();
+    config_pub
+        .insert_json5("connect/endpoints", r#"["tcp/127.0.0.1:38452"]"#)
+        .unwrap();
+    config_pub
+        .scouting
+        .multicast
+        .set_enabled(Some(false))
+        .unwrap();
+    let zenoh_pub = zenoh::open(config_pub).res().unwrap();
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    for _ in 0..20 {
+        zenoh_pub
+            .put("test/rate_limit/limited", "value")
+            .res()
+            .unwrap();
+        zenoh_pub
+            .put("test/rate_limit/free", "value")
+            .res()
+            .unwrap();
+    }
+    let replies = |zenoh_pub: &Session| -> Vec<bool> {
+        zenoh_pub
+            .get("test/rate_limit/queried")
+            .res()
+            .unwrap()
+            .into_iter()
+            .map(|reply| reply.sample.is_ok())
+            .collect()
+    };
+    let first = replies(&zenoh_pub);
+    let second = replies(&zenoh_pub);
+    // The error reply also reaches a querier using a declared key expression
+    let querier = zenoh_pub
+        .declare_querier("test/rate_limit/queried")
+        .res()
+        .unwrap();
+    let third: Vec<bool> = querier
+        .get()
+        .res()
+        .unwrap()
+        .into_iter()
+        .map(|reply| reply.sample.is_ok())
+        .collect();
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    assert_eq!(*zlock!(limited_count), 5);
+    assert_eq!(*zlock!(free_count), 20);
+    assert_eq!(first, vec![true]);
+    assert_eq!(second, vec![false]);
+    assert_eq!(third, vec![false]);
+
+    zenoh_pub.close().res().unwrap();
+    queryable.undeclare().res().unwrap();
+    sub.undeclare().res().unwrap();
+    zenoh_sub.close().res().unwrap();
+}
+
+#[test]
+fn rate_limit_shared_key_expr_and_bytes() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    use zenoh::prelude::sync::*;
+
+    let mut config_sub = Config::default();
+    config_sub
+        .insert_json5(
+            "rate_limit",
+            r#"
+              [
+                {
+                  flow: "ingress",
+                  reply_error: true,
+                  rules: [
+                    { key_expr: "test/rate_limit_shared/key", messages: ["put"], messages_per_sec: 0.1, messages_burst: 3 },
+                    { key_expr: "test/rate_limit_shared/key", messages: ["query"], messages_per_sec: 0.1, messages_burst: 1 },
+                    { key_expr: "test/rate_limit_shared/bytes", messages: ["put"], bytes_per_sec: 1, bytes_burst: 100 },
+                  ],
+                },
+              ]
+            "#,
+        )
+        .unwrap();
+    config_sub
+        .insert_json5("listen/endpoints", r#"["tcp/127.0.0.1:38457"]"#)
+        .unwrap();
+    config_sub
+        .scouting
+        .multicast
+        .set_enabled(Some(false))
+        .unwrap();
+    let zenoh_sub = zenoh::open(config_sub).res().unwrap();
+
+    let key_count = Arc::new(Mutex::new(0));
+    let bytes_count = Arc::new(Mutex::new(0));
+    let c_key_count = key_count.clone();
+    let c_bytes_count = bytes_count.clone();
+    let sub = zenoh_sub
+        .declare_subscriber("test/rate_limit_shared/*")
+        .callback(move |sample| match sample.key_expr.as_str() {
+            "test/rate_limit_shared/key" => *zlock!(c_key_count) += 1,
+            "test/rate_limit_shared/bytes" => *zlock!(c_bytes_count) += 1,
+            _ => {}
+        })
+        .res()
+        .unwrap();
+    let queryable = zenoh_sub
+        .declare_queryable("test/rate_limit_shared/key")
+        .callback(|query| {
+            query
+                .reply(Ok(Sample::new(query.key_expr().clone(), "reply")))
+                .res()
+                .unwrap()
+        })
+        .res()
+        .unwrap();
+
+    let mut config_pub = Config::default();
+    config_pub
+        .insert_json5("connect/endpoints", r#"["tcp/127.0.0.1:38457"]"#)
+        .unwrap();
+    config_pub
+        .scouting
+        .multicast
+        .set_enabled(Some(false))
+        .unwrap();
+    let zenoh_pub = zenoh::open(config_pub).res().unwrap();
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    // Both rules on the same key expression apply, each to its own kind of message
+    for _ in 0..10 {
+        zenoh_pub
+            .put("test/rate_limit_shared/key", "value")
+            .res()
+            .unwrap();
+    }
+    let replies = |zenoh_pub: &Session| -> Vec<bool> {
+        zenoh_pub
+            .get("test/rate_limit_shared/key")
+            .res()
+            .unwrap()
+            .into_iter()
+            .map(|reply| reply.sample.is_ok())
+            .collect()
+    };
+    let first = replies(&zenoh_pub);
+    let second = replies(&zenoh_pub);
+
+    // A message larger than the bytes burst drains the full bucket
+    for _ in 0..10 {
+        zenoh_pub
+            .put("test/rate_limit_shared/bytes", vec![0u8; 1000])
+            .res()
+            .unwrap();
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    assert_eq!(*zlock!(key_count), 3);
+    assert_eq!(first, vec![true]);
+    assert_eq!(second, vec![false]);
+    assert_eq!(*zlock!(bytes_count), 1);
+
+    zenoh_pub.close().res().unwrap();
+    queryable.undeclare().res().unwrap();
+    sub.undeclare().res().unwrap();
+    zenoh_sub.close().res().unwrap();
+}
+
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn rate_limit_stats() {
+    use zenoh::prelude::r#async::*;
+    use zenoh::runtime::{AdminSpace, Runtime};
+
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let mut config_sub = Config::default();
+    config_sub
+        .insert_json5(
+            "rate_limit",
+            r#"
+              [
+                {
+                  flow: "ingress",
+                  rules: [
+                    { key_expr: "test/rate_limit_stats/**", messages: ["query"], messages_per_sec: 0.1, messages_burst: 1 },
+                    { key_expr: "test/rate_limit_stats/limited", messages: ["put"], messages_per_sec: 0.1, messages_burst: 5 },
+                  ],
+                },
+                {
+                  flow: "ingress",
+                  rules: [
+                    { key_expr: "test/rate_limit_stats/other", messages: ["put"], messages_per_sec: 0.1, messages_burst: 5 },
+                  ],
+                },
+              ]
+            "#,
+        )
+        .unwrap();
+    config_sub
+        .insert_json5("listen/endpoints", r#"["tcp/127.0.0.1:38454"]"#)
+        .unwrap();
+    config_sub
+        .scouting
+        .multicast
+        .set_enabled(Some(false))
+        .unwrap();
+    let runtime = Runtime::new(config_sub).await.unwrap();
+    AdminSpace::start(
+        &runtime,
+        zenoh::plugins::PluginsManager::static_plugins_only(),
+        String::from("test"),
+    )
+    .await;
+    let zenoh_sub = zenoh::init(runtime).res_async().await.unwrap();
+
+    let count = Arc::new(Mutex::new(0));
+    let c_count = count.clone();
+    let sub = zenoh_sub
+        .declare_subscriber("test/rate_limit_stats/limited")
+        .callback(move |_| *zlock!(c_count) += 1)
+        .res_async()
+        .await
+        .unwrap();
+
+    let mut config_pub = Config::default();
+    config_pub
+        .insert_json5("connect/endpoints", r#"["tcp/127.0.0.1:38454"]"#)
+        .unwrap();
+    config_pub
+        .scouting
+        .multicast
+        .set_enabled(Some(false))
+        .unwrap();
+    let zenoh_pub = zenoh::open(config_pub).res_async().await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+
+    for _ in 0..20 {
+        zenoh_pub
+            .put("test/rate_limit_stats/limited", "value")
+            .res_async()
+            .await
+            .unwrap();
+    }
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+
+    // The puts are limited by the second rule, the first one only limiting queries
+    assert_eq!(*zlock!(count), 5);
+
+    let reply = zenoh_sub
+        .get(format!("@/router/{}/rate_limit", zenoh_sub.zid()))
+        .res_async()
+        .await
+        .unwrap()
+        .recv_async()
+        .await
+        .unwrap();
+    let stats: serde_json::Value =
+        serde_json::from_slice(&reply.sample.unwrap().value.payload.contiguous()).unwrap();
+    let rules = &stats[zenoh_pub.zid().to_string()]["ingress"];
+    assert_eq!(rules[0]["passed"], 0);
+    assert_eq!(rules[0]["rejected"], 0);
+    assert_eq!(rules[1]["key_expr"], "test/rate_limit_stats/limited");
+    assert_eq!(rules[1]["passed"], 5);
+    assert_eq!(rules[1]["rejected"], 15);
+    // The rules of every rate_limit item applying to the transport are reported
+    assert_eq!(rules[2]["key_expr"], "test/rate_limit_stats/other");
+    assert_eq!(rules[2]["passed"], 0);
+
+    zenoh_pub.close().res_async().await.unwrap();
+    sub.undeclare().res_async().await.unwrap();
+    zenoh_sub.close().res_async().await.unwrap();
+}
+
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn custom_interceptor() {
+    use std::any::Any;
+    use zenoh::interceptor::{InterceptorFactoryTrait, InterceptorTrait, RoutingContext};
+    use zenoh::key_expr::KeyExpr;
+    use zenoh::prelude::r#async::*;
+    use zenoh::runtime::Runtime;
+    use zenoh_buffers::ZBuf;
+    use zenoh_protocol::network::{NetworkBody, NetworkMessage};
+    use zenoh_protocol::zenoh::PushBody;
+    use zenoh_transport::{multicast::TransportMulticast, unicast::TransportUnicast};
+
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    type Interceptor = Box<dyn InterceptorTrait + Send + Sync>;
+
+    // Redacts the payloads published on `*/redacted`, drops the ones published on `*/dropped`
+    // and sends the ones published on `*/bounced` back to their publisher
+    struct Redactor {
+        publishers: Arc<Mutex<Vec<ZenohId>>>,
+    }
+
+    impl InterceptorTrait for Redactor {
+        fn compute_keyexpr_cache(
+            &self,
+            key_expr: &KeyExpr<'_>,
+        ) -> Option<Box<dyn Any + Send + Sync>> {
+            Some(Box::new(
+                key_expr.as_str().rsplit('/').next().map(String::from),
+            ))
+        }
+
+        fn intercept(
+            &self,
+            mut ctx: RoutingContext<NetworkMessage>,
+            cache: Option<&Box<dyn Any + Send + Sync>>,
+        ) -> Option<RoutingContext<NetworkMessage>> {
+            let chunk = match cache.and_then(|c| c.downcast_ref::<Option<String>>()) {
+                Some(chunk) => chunk.clone(),
+                None => ctx
+                    .full_expr()
+                    .and_then(|e| e.rsplit('/').next().map(String::from)),
+            };
+            if let (NetworkBody::Push(_), Some(face)) = (&ctx.msg.body, ctx.inface()) {
+                zlock!(self.publishers).push(face.zid());
+                if chunk.as_deref() == Some("bounced") {
+                    // the wire expression may be scoped to a key expression declared by the publisher
+                    let mut msg = ctx.msg.clone();
+                    if let NetworkBody::Push(push) = &mut msg.body {
+                        push.wire_expr = ctx.full_expr()?.to_string().into();
+                    }
+                    face.send(msg);
+                    return None;
+                }
+            }
+            if let NetworkBody::Push(push) = &mut ctx.msg.body {
+                if let PushBody::Put(put) = &mut push.payload {
+                    match chunk.as_deref() {
+                        Some("redacted") => put.payload = ZBuf::from(b"***".to_vec()),
+                        Some("dropped") => return None,
+                        _ => {}
+                    }
+                }
+            }
+            Some(ctx)
+        }
+    }
+
+    struct RedactorFactory {
+        publishers: Arc<Mutex<Vec<ZenohId>>>,
+    }
+
+    impl InterceptorFactoryTrait for RedactorFactory {
+        fn new_transport_unicast(
+            &self,
+            _transport: &TransportUnicast,
+        ) -> (Option<Interceptor>, Option<Interceptor>) {
+            let redactor = Redactor {
+                publishers: self.publishers.clone(),
+            };
+            (Some(Box::new(redactor)), None)
+        }
+
+        fn new_transport_multicast(&self, _transport: &TransportMulticast) -> Option<Interceptor> {
+            None
+        }
+
+        fn new_peer_multicast(&self, _transport: &TransportMulticast) -> Option<Interceptor> {
+            None
+        }
+    }
+
+    // declare subscriber on a runtime with the custom interceptor
+    let mut config_sub = Config::default();
+    config_sub
+        .insert_json5("listen/endpoints", r#"["tcp/127.0.0.1:38448"]"#)
+        .unwrap();
+    config_sub
+        .scouting
+        .multicast
+        .set_enabled(Some(false))
+        .unwrap();
+    let runtime = Runtime::new(config_sub).await.unwrap();
+    let publishers = Arc::new(Mutex::new(vec![]));
+    runtime.add_interceptor_factory(Box::new(RedactorFactory {
+        publishers: publishers.clone(),
+    }));
+    let zenoh_sub = zenoh::init(runtime).res().await.unwrap();
+
+    let received = Arc::new(Mutex::new(vec![]));
+    let c_received = received.clone();
+    let _sub = zenoh_sub
+        .declare_subscriber("test/custom_interceptor/*")
+        .callback(move |sample| {
+            zlock!(c_received).push((
+                sample.key_expr.as_str().to_string(),
+                sample.value.to_string(),
+            ));
+        })
+        .res()
+        .await
+        .unwrap();
+
+    // declare publisher
+    let mut config_pub = Config::default();
+    config_pub
+        .insert_json5("connect/endpoints", r#"["tcp/127.0.0.1:38448"]"#)
+        .unwrap();
+    config_pub
+        .scouting
+        .multicast
+        .set_enabled(Some(false))
+        .unwrap();
+    let zenoh_pub = zenoh::open(config_pub).res().await.unwrap();
+    let bounced = Arc::new(Mutex::new(0));
+    let c_bounced = bounced.clone();
+    let _bounced_sub = zenoh_pub
+        .declare_subscriber("test/custom_interceptor/bounced")
+        .callback(move |_| *zlock!(c_bounced) += 1)
+        .res()
+        .await
+        .unwrap();
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+
+    for chunk in ["clear", "redacted", "dropped", "bounced"] {
+        zenoh_pub
+            .put(format!("test/custom_interceptor/{chunk}"), "secret")
+            .allowed_destination(Locality::Remote)
+            .res()
+            .await
+            .unwrap();
+    }
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+
+    assert!(zlock!(publishers).contains(&zenoh_pub.zid()));
+    assert_eq!(*zlock!(bounced), 1);
+
+    assert_eq!(
+        *zlock!(received),
+        vec![
+            (
+                "test/custom_interceptor/clear".to_string(),
+                "secret".to_string()
+            ),
+            (
+                "test/custom_interceptor/redacted".to_string(),
+                "***".to_string()
+            ),
+        ]
+    );
+}
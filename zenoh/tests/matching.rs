@@ -226,3 +226,115 @@ async fn zenoh_matching_status_local() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn zenoh_querier_matching_status_any() -> Result<()> {
+    use flume::RecvTimeoutError;
+
+    let (session1, session2) = create_session_pair("tcp/127.0.0.1:18002").await;
+
+    let querier1 = ztimeout!(session1
+        .declare_querier("zenoh_querier_matching_status_any_test")
+        .allowed_destination(Locality::Any)
+        .res_async())
+    .unwrap();
+
+    let matching_listener = ztimeout!(querier1.matching_listener().res_async()).unwrap();
+
+    let received_status = matching_listener.recv_timeout(RECV_TIMEOUT);
+    assert!(received_status.err() == Some(RecvTimeoutError::Timeout));
+
+    let matching_status = ztimeout!(querier1.matching_status().res_async()).unwrap();
+    assert!(!matching_status.matching_queryables());
+
+    let qabl = ztimeout!(session1
+        .declare_queryable("zenoh_querier_matching_status_any_test")
+        .res_async())
+    .unwrap();
+
+    let received_status = matching_listener.recv_timeout(RECV_TIMEOUT);
+    assert!(received_status.ok().map(|s| s.matching_queryables()) == Some(true));
+
+    let matching_status = ztimeout!(querier1.matching_status().res_async()).unwrap();
+    assert!(matching_status.matching_queryables());
+
+    ztimeout!(qabl.undeclare().res_async()).unwrap();
+
+    let received_status = matching_listener.recv_timeout(RECV_TIMEOUT);
+    assert!(received_status.ok().map(|s| s.matching_queryables()) == Some(false));
+
+    let matching_status = ztimeout!(querier1.matching_status().res_async()).unwrap();
+    assert!(!matching_status.matching_queryables());
+
+    let qabl = ztimeout!(session2
+        .declare_queryable("zenoh_querier_matching_status_any_test")
+        .res_async())
+    .unwrap();
+
+    let received_status = matching_listener.recv_timeout(RECV_TIMEOUT);
+    assert!(received_status.ok().map(|s| s.matching_queryables()) == Some(true));
+
+    let matching_status = ztimeout!(querier1.matching_status().res_async()).unwrap();
+    assert!(matching_status.matching_queryables());
+
+    ztimeout!(qabl.undeclare().res_async()).unwrap();
+
+    let received_status = matching_listener.recv_timeout(RECV_TIMEOUT);
+    assert!(received_status.ok().map(|s| s.matching_queryables()) == Some(false));
+
+    let matching_status = ztimeout!(querier1.matching_status().res_async()).unwrap();
+    assert!(!matching_status.matching_queryables());
+    Ok(())
+}
+
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn zenoh_querier_matching_status_complete() -> Result<()> {
+    use flume::RecvTimeoutError;
+    use zenoh::query::QueryTarget;
+
+    let (session1, session2) = create_session_pair("tcp/127.0.0.1:18003").await;
+
+    let querier1 = ztimeout!(session1
+        .declare_querier("zenoh_querier_matching_status_complete_test")
+        .target(QueryTarget::AllComplete)
+        .res_async())
+    .unwrap();
+
+    let matching_listener = ztimeout!(querier1.matching_listener().res_async()).unwrap();
+
+    let received_status = matching_listener.recv_timeout(RECV_TIMEOUT);
+    assert!(received_status.err() == Some(RecvTimeoutError::Timeout));
+
+    let qabl = ztimeout!(session2
+        .declare_queryable("zenoh_querier_matching_status_complete_test")
+        .res_async())
+    .unwrap();
+
+    let received_status = matching_listener.recv_timeout(RECV_TIMEOUT);
+    assert!(received_status.err() == Some(RecvTimeoutError::Timeout));
+
+    let matching_status = ztimeout!(querier1.matching_status().res_async()).unwrap();
+    assert!(!matching_status.matching_queryables());
+
+    let complete_qabl = ztimeout!(session2
+        .declare_queryable("zenoh_querier_matching_status_complete_test")
+        .complete(true)
+        .res_async())
+    .unwrap();
+
+    let received_status = matching_listener.recv_timeout(RECV_TIMEOUT);
+    assert!(received_status.ok().map(|s| s.matching_queryables()) == Some(true));
+
+    ztimeout!(complete_qabl.undeclare().res_async()).unwrap();
+
+    let received_status = matching_listener.recv_timeout(RECV_TIMEOUT);
+    assert!(received_status.ok().map(|s| s.matching_queryables()) == Some(false));
+
+    let matching_status = ztimeout!(querier1.matching_status().res_async()).unwrap();
+    assert!(!matching_status.matching_queryables());
+
+    ztimeout!(qabl.undeclare().res_async()).unwrap();
+    Ok(())
+}
@@ -12,8 +12,10 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use std::{
+    any::Any,
+    collections::HashMap,
     str::FromStr,
-    sync::{atomic::AtomicUsize, atomic::Ordering, Arc},
+    sync::{atomic::AtomicUsize, atomic::Ordering, Arc, Mutex},
     time::Duration,
 };
 use tokio_util::{sync::CancellationToken, task::TaskTracker};
@@ -24,8 +26,22 @@ use zenoh::{
     Result,
 };
 use zenoh_core::ztimeout;
-use zenoh_protocol::core::{WhatAmI, WhatAmIMatcher};
+use zenoh_link::Link;
+use zenoh_protocol::{
+    core::{ExprId, WhatAmI, WhatAmIMatcher},
+    network::{
+        declare::{
+            common::ext::WireExprType, ext, interest::Interest, Declare, DeclareBody,
+            DeclareInterest, UndeclareInterest,
+        },
+        NetworkBody, NetworkMessage,
+    },
+};
 use zenoh_result::bail;
+use zenoh_transport::{
+    multicast::TransportMulticast, unicast::TransportUnicast, TransportEventHandler,
+    TransportManager, TransportMulticastEventHandler, TransportPeer, TransportPeerEventHandler,
+};
 
 const TIMEOUT: Duration = Duration::from_secs(10);
 const MSG_COUNT: usize = 50;
@@ -734,3 +750,389 @@ async fn two_node_combination() -> Result<()> {
     println!("Two-node combination test passed.");
     Result::Ok(())
 }
+
+// Declarations received by a raw client transport, in order.
+#[derive(Debug, PartialEq, Eq)]
+enum Declared {
+    Subscriber(String),
+    FinalInterest(u32),
+}
+
+struct InterestClient {
+    mappings: Mutex<HashMap<ExprId, String>>,
+    sender: flume::Sender<Declared>,
+}
+
+impl TransportEventHandler for InterestClient {
+    fn new_unicast(
+        &self,
+        _peer: TransportPeer,
+        _transport: TransportUnicast,
+    ) -> Result<Arc<dyn TransportPeerEventHandler>> {
+        Ok(Arc::new(InterestClient {
+            mappings: Mutex::new(HashMap::new()),
+            sender: self.sender.clone(),
+        }))
+    }
+
+    fn new_multicast(
+        &self,
+        _transport: TransportMulticast,
+    ) -> Result<Arc<dyn TransportMulticastEventHandler>> {
+        panic!();
+    }
+}
+
+impl TransportPeerEventHandler for InterestClient {
+    fn handle_message(&self, msg: NetworkMessage) -> Result<()> {
+        if let NetworkBody::Declare(declare) = msg.body {
+            let mut mappings = self.mappings.lock().unwrap();
+            match declare.body {
+                DeclareBody::DeclareKeyExpr(m) => {
+                    mappings.insert(m.id, m.wire_expr.suffix.to_string());
+                }
+                DeclareBody::DeclareSubscriber(m) => {
+                    let prefix = mappings
+                        .get(&m.wire_expr.scope)
+                        .cloned()
+                        .unwrap_or_default();
+                    let expr = prefix + m.wire_expr.suffix.as_ref();
+                    if expr.starts_with("test/") {
+                        let _ = self.sender.send(Declared::Subscriber(expr));
+                    }
+                }
+                DeclareBody::FinalInterest(m) => {
+                    let _ = self.sender.send(Declared::FinalInterest(m.id));
+                }
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    fn new_link(&self, _link: Link) {}
+    fn del_link(&self, _link: Link) {}
+    fn closing(&self) {}
+    fn closed(&self) {}
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+fn interest_msg(body: DeclareBody) -> NetworkMessage {
+    NetworkMessage {
+        body: NetworkBody::Declare(Declare {
+            ext_qos: ext::QoSType::declare_default(),
+            ext_tstamp: None,
+            ext_nodeid: ext::NodeIdType::default(),
+            body,
+        }),
+        #[cfg(feature = "stats")]
+        size: None,
+    }
+}
+
+// A client that declared interests in future declarations only receives the subscriber
+// declarations matching them, and the ones held back once they are all undeclared.
+async fn check_interests(mut config: Config, locator: &str) -> Result<()> {
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config
+        .listen
+        .set_endpoints(vec![locator.parse().unwrap()])
+        .unwrap();
+    let node = ztimeout!(zenoh::open(config).res_async())?;
+    let mut subs = vec![];
+    subs.push(ztimeout!(node
+        .declare_subscriber("test/interests/a")
+        .res_async())?);
+    subs.push(ztimeout!(node
+        .declare_subscriber("test/others/b")
+        .res_async())?);
+
+    let (sender, receiver) = flume::unbounded();
+    let manager = TransportManager::builder()
+        .whatami(WhatAmI::Client)
+        .build(Arc::new(InterestClient {
+            mappings: Mutex::new(HashMap::new()),
+            sender,
+        }))?;
+    let transport = ztimeout!(manager.open_transport_unicast(locator.parse().unwrap()))?;
+    let next = || async { ztimeout!(receiver.recv_async()).unwrap() };
+
+    // Without interests, the client is told about every subscriber.
+    let mut initial = vec![next().await, next().await];
+    initial.sort_by_key(|d| format!("{d:?}"));
+    assert_eq!(
+        initial,
+        vec![
+            Declared::Subscriber("test/interests/a".into()),
+            Declared::Subscriber("test/others/b".into())
+        ]
+    );
+
+    transport.schedule(interest_msg(DeclareBody::DeclareInterest(
+        DeclareInterest {
+            id: 1,
+            wire_expr: "test/interests/**".into(),
+            interest: Interest::SUBSCRIBERS | Interest::CURRENT | Interest::FUTURE,
+        },
+    )))?;
+    assert_eq!(next().await, Declared::FinalInterest(1));
+
+    // Only the future subscribers matching the interest are declared.
+    subs.push(ztimeout!(node
+        .declare_subscriber("test/others/d")
+        .res_async())?);
+    subs.push(ztimeout!(node
+        .declare_subscriber("test/interests/c")
+        .res_async())?);
+    assert_eq!(
+        next().await,
+        Declared::Subscriber("test/interests/c".into())
+    );
+
+    // Current subscribers can be requested without registering the interest.
+    transport.schedule(interest_msg(DeclareBody::DeclareInterest(
+        DeclareInterest {
+            id: 2,
+            wire_expr: "test/others/**".into(),
+            interest: Interest::SUBSCRIBERS | Interest::CURRENT,
+        },
+    )))?;
+    assert_eq!(next().await, Declared::Subscriber("test/others/d".into()));
+    assert_eq!(next().await, Declared::FinalInterest(2));
+    subs.push(ztimeout!(node
+        .declare_subscriber("test/others/f")
+        .res_async())?);
+    subs.push(ztimeout!(node
+        .declare_subscriber("test/interests/e")
+        .res_async())?);
+    assert_eq!(
+        next().await,
+        Declared::Subscriber("test/interests/e".into())
+    );
+
+    // Once the last interest is undeclared, the subscribers held back are declared.
+    transport.schedule(interest_msg(DeclareBody::UndeclareInterest(
+        UndeclareInterest {
+            id: 1,
+            ext_wire_expr: WireExprType::null(),
+        },
+    )))?;
+    assert_eq!(next().await, Declared::Subscriber("test/others/f".into()));
+    subs.push(ztimeout!(node
+        .declare_subscriber("test/others/g")
+        .res_async())?);
+    assert_eq!(next().await, Declared::Subscriber("test/others/g".into()));
+
+    ztimeout!(transport.close())?;
+    ztimeout!(manager.close());
+    drop(subs);
+    ztimeout!(node.close().res_async())?;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn router_interests() -> Result<()> {
+    env_logger::try_init().unwrap_or_default();
+    let mut config = Config::default();
+    config.set_mode(Some(WhatAmI::Router)).unwrap();
+    check_interests(config, "tcp/127.0.0.1:17510").await
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn peer_interests() -> Result<()> {
+    env_logger::try_init().unwrap_or_default();
+    let mut config = Config::default();
+    config.set_mode(Some(WhatAmI::Peer)).unwrap();
+    check_interests(config, "tcp/127.0.0.1:17511").await
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn linkstate_peer_interests() -> Result<()> {
+    env_logger::try_init().unwrap_or_default();
+    let mut config = Config::default();
+    config.set_mode(Some(WhatAmI::Peer)).unwrap();
+    config
+        .routing
+        .peer
+        .set_mode(Some("linkstate".to_string()))
+        .unwrap();
+    check_interests(config, "tcp/127.0.0.1:17513").await
+}
+
+// A raw router answering the interests forwarded to it.
+#[cfg(feature = "unstable")]
+struct InterestRouter {
+    forwarded: flume::Sender<DeclareBody>,
+}
+
+#[cfg(feature = "unstable")]
+impl TransportEventHandler for InterestRouter {
+    fn new_unicast(
+        &self,
+        _peer: TransportPeer,
+        transport: TransportUnicast,
+    ) -> Result<Arc<dyn TransportPeerEventHandler>> {
+        Ok(Arc::new(InterestRouterPeer {
+            transport,
+            forwarded: self.forwarded.clone(),
+        }))
+    }
+
+    fn new_multicast(
+        &self,
+        _transport: TransportMulticast,
+    ) -> Result<Arc<dyn TransportMulticastEventHandler>> {
+        panic!();
+    }
+}
+
+#[cfg(feature = "unstable")]
+struct InterestRouterPeer {
+    transport: TransportUnicast,
+    forwarded: flume::Sender<DeclareBody>,
+}
+
+#[cfg(feature = "unstable")]
+impl TransportPeerEventHandler for InterestRouterPeer {
+    fn handle_message(&self, msg: NetworkMessage) -> Result<()> {
+        if let NetworkBody::Declare(declare) = msg.body {
+            match declare.body {
+                DeclareBody::DeclareInterest(m) => {
+                    self.transport
+                        .schedule(interest_msg(DeclareBody::FinalInterest(
+                            zenoh_protocol::network::declare::FinalInterest { id: m.id },
+                        )))?;
+                    let _ = self.forwarded.send(DeclareBody::DeclareInterest(m));
+                }
+                body @ DeclareBody::UndeclareInterest(_) => {
+                    let _ = self.forwarded.send(body);
+                }
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    fn new_link(&self, _link: Link) {}
+    fn del_link(&self, _link: Link) {}
+    fn closing(&self) {}
+    fn closed(&self) {}
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+// A client forwards the interests of its session to the node it is connected to,
+// completes them once that node replied, and forwards their undeclaration.
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn client_interests() -> Result<()> {
+    env_logger::try_init().unwrap_or_default();
+    let locator = String::from("tcp/127.0.0.1:17512");
+
+    let (forwarded, receiver) = flume::unbounded();
+    let manager = TransportManager::builder()
+        .whatami(WhatAmI::Router)
+        .build(Arc::new(InterestRouter { forwarded }))?;
+    ztimeout!(manager.add_listener_unicast(locator.parse().unwrap()))?;
+
+    let mut config = Config::default();
+    config.set_mode(Some(WhatAmI::Client)).unwrap();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config
+        .connect
+        .set_endpoints(vec![locator.parse().unwrap()])
+        .unwrap();
+    let client = ztimeout!(zenoh::open(config).res_async())?;
+
+    let interest = ztimeout!(client
+        .declare_interest("test/interests/**")
+        .subscribers()
+        .res_async())?;
+    let id = match ztimeout!(receiver.recv_async())? {
+        DeclareBody::DeclareInterest(m) => {
+            assert_eq!(m.wire_expr.suffix, "test/interests/**");
+            assert_eq!(
+                m.interest,
+                Interest::SUBSCRIBERS | Interest::CURRENT | Interest::FUTURE,
+                "the upstream node filters the future declarations"
+            );
+            m.id
+        }
+        body => panic!("Unexpected declaration {body:?}"),
+    };
+    ztimeout!(async {
+        while !interest.is_complete() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    });
+
+    ztimeout!(interest.undeclare().res_async())?;
+    match ztimeout!(receiver.recv_async())? {
+        DeclareBody::UndeclareInterest(m) => assert_eq!(m.id, id),
+        body => panic!("Unexpected declaration {body:?}"),
+    }
+    ztimeout!(client.close().res_async())?;
+    ztimeout!(manager.close());
+    Ok(())
+}
+
+// A client with an interest in future subscribers only is still told about the queryables,
+// and gets the replies to its queries.
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn subscriber_interest_queries() -> Result<()> {
+    env_logger::try_init().unwrap_or_default();
+    let locator = String::from("tcp/127.0.0.1:17514");
+
+    let mut config = Config::default();
+    config.set_mode(Some(WhatAmI::Router)).unwrap();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config
+        .listen
+        .set_endpoints(vec![locator.parse().unwrap()])
+        .unwrap();
+    let router = ztimeout!(zenoh::open(config).res_async())?;
+
+    let mut config = Config::default();
+    config.set_mode(Some(WhatAmI::Client)).unwrap();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config
+        .connect
+        .set_endpoints(vec![locator.parse().unwrap()])
+        .unwrap();
+    let client = ztimeout!(zenoh::open(config).res_async())?;
+
+    let interest = ztimeout!(client
+        .declare_interest("test/interests/**")
+        .subscribers()
+        .res_async())?;
+    ztimeout!(async {
+        while !interest.is_complete() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    });
+
+    // The queryable is declared once the client filters the future subscribers.
+    let queryable = ztimeout!(router.declare_queryable("test/queryable").res_async())?;
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let replies = ztimeout!(client.get("test/queryable").res_async())?;
+    let query = ztimeout!(queryable.recv_async())?;
+    ztimeout!(query
+        .reply(Ok(Sample::new(query.key_expr().clone(), "reply")))
+        .res_async())?;
+    drop(query);
+    let reply = ztimeout!(replies.recv_async())?;
+    assert_eq!(reply.sample.unwrap().value.to_string(), "reply");
+
+    ztimeout!(queryable.undeclare().res_async())?;
+    ztimeout!(interest.undeclare().res_async())?;
+    ztimeout!(client.close().res_async())?;
+    ztimeout!(router.close().res_async())?;
+    Ok(())
+}
@@ -181,6 +181,61 @@ async fn test_session_qryrep(peer01: &Session, peer02: &Session, reliability: Re
     }
 }
 
+async fn test_session_querier(peer01: &Session, peer02: &Arc<Session>) {
+    let key_expr = "test/session/querier";
+    let msgs = Arc::new(AtomicUsize::new(0));
+
+    for size in MSG_SIZE {
+        msgs.store(0, Ordering::Relaxed);
+
+        // Queryable to data
+        println!("[QR][01d] Queryable on peer01 session");
+        let c_msgs = msgs.clone();
+        let qbl = ztimeout!(peer01
+            .declare_queryable(key_expr)
+            .callback(move |sample| {
+                c_msgs.fetch_add(1, Ordering::Relaxed);
+                let rep = Sample::try_from(key_expr, vec![0u8; size]).unwrap();
+                tokio::task::block_in_place(|| {
+                    tokio::runtime::Handle::current()
+                        .block_on(async { ztimeout!(sample.reply(Ok(rep)).res_async()).unwrap() })
+                });
+            })
+            .res_async())
+        .unwrap();
+
+        // Wait for the declaration to propagate
+        tokio::time::sleep(SLEEP).await;
+
+        // Get data through a querier owned by a task
+        println!("[QR][02d] Querying on peer02 session. {MSG_COUNT} msgs.");
+        let querier = ztimeout!(peer02.declare_querier(key_expr).res_async()).unwrap();
+        let cnt = tokio::spawn(async move {
+            let mut cnt = 0;
+            for _ in 0..MSG_COUNT {
+                let rs = ztimeout!(querier.get().res_async()).unwrap();
+                while let Ok(s) = ztimeout!(rs.recv_async()) {
+                    assert_eq!(s.sample.unwrap().value.payload.len(), size);
+                    cnt += 1;
+                }
+            }
+            ztimeout!(querier.undeclare().res_async()).unwrap();
+            cnt
+        })
+        .await
+        .unwrap();
+        println!("[QR][02d] Queried on peer02 session. {cnt}/{MSG_COUNT} msgs.");
+        assert_eq!(msgs.load(Ordering::Relaxed), MSG_COUNT);
+        assert_eq!(cnt, MSG_COUNT);
+
+        println!("[QR][03d] Unqueryable on peer01 session");
+        ztimeout!(qbl.undeclare().res_async()).unwrap();
+
+        // Wait for the declaration to propagate
+        tokio::time::sleep(SLEEP).await;
+    }
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
 async fn zenoh_session_unicast() {
     let _ = env_logger::try_init();
@@ -198,3 +253,13 @@ async fn zenoh_session_multicast() {
     test_session_pubsub(&peer01, &peer02, Reliability::BestEffort).await;
     close_session(peer01, peer02).await;
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn zenoh_session_querier() {
+    let _ = env_logger::try_init();
+    let (peer01, peer02) = open_session_unicast(&["tcp/127.0.0.1:17445"]).await;
+    let peer02 = peer02.into_arc();
+    test_session_querier(&peer01, &peer02).await;
+    let peer02 = Arc::try_unwrap(peer02).unwrap();
+    close_session(peer01, peer02).await;
+}
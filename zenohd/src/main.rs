@@ -11,12 +11,13 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use futures::future;
 use git_version::git_version;
 use std::collections::HashSet;
 use zenoh::config::{Config, ModeDependentValue, PermissionsConf, PluginLoad, ValidatedMap};
 use zenoh::plugins::PluginsManager;
+use zenoh::prelude::r#async::*;
 use zenoh::prelude::{EndPoint, WhatAmI};
 use zenoh::runtime::{AdminSpace, Runtime};
 use zenoh::Result;
@@ -77,6 +78,39 @@ struct Args {
     /// Configure the read and/or write permissions on the admin space. Default is read only.
     #[arg(long, value_name = "[r|w|rw|none]")]
     adminspace_permissions: Option<String>,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Exports or imports the contents of a storage of a running router (reached with --connect).
+    #[command(subcommand)]
+    Storage(StorageCommand),
+}
+
+#[derive(Debug, Subcommand)]
+enum StorageCommand {
+    /// Exports the contents of a storage (values, encodings, timestamps and tombstones) to a file, or to the standard output.
+    Export {
+        /// The name of the storage.
+        storage: String,
+        /// The file to write the contents to.
+        file: Option<String>,
+        /// The identifier of the router running the storage.
+        #[arg(long, default_value = "*")]
+        router: String,
+    },
+    /// Imports in a storage the contents of a file previously exported.
+    Import {
+        /// The name of the storage.
+        storage: String,
+        /// The file to read the contents from.
+        file: String,
+        /// The identifier of the router running the storage.
+        #[arg(long, default_value = "*")]
+        router: String,
+    },
 }
 
 fn load_plugin(
@@ -121,6 +155,13 @@ fn main() {
         log::info!("zenohd {}", *LONG_VERSION);
 
         let args = Args::parse();
+        if let Some(Command::Storage(command)) = &args.command {
+            if let Err(e) = run_storage_command(&args, command).await {
+                println!("{e}. Exiting...");
+                std::process::exit(-1);
+            }
+            return;
+        }
         let config = config_from_args(&args);
         log::info!("Initial conf: {}", &config);
 
@@ -209,6 +250,80 @@ fn main() {
     });
 }
 
+// Exports or imports the contents of a storage through its admin space, from a client session
+async fn run_storage_command(args: &Args, command: &StorageCommand) -> Result<()> {
+    let mut config = Config::default();
+    config.set_mode(Some(WhatAmI::Client)).unwrap();
+    if !args.connect.is_empty() {
+        let endpoints = args
+            .connect
+            .iter()
+            .map(|v| v.parse::<EndPoint>())
+            .collect::<Result<_>>()?;
+        config.connect.set_endpoints(endpoints).unwrap();
+    }
+    let session = zenoh::open(config).res().await?;
+    // resolve the storage first, as the storages only answer on their exact export and import keys
+    let (router, storage) = match command {
+        StorageCommand::Export {
+            storage, router, ..
+        }
+        | StorageCommand::Import {
+            storage, router, ..
+        } => (router, storage),
+    };
+    let key_expr = format!("@/router/{router}/status/plugins/*/storages/{storage}");
+    let status = get_storage_reply(&session, &key_expr, None).await?;
+    match command {
+        StorageCommand::Export { file, .. } => {
+            let key_expr = format!("{}/export", status.key_expr);
+            let sample = get_storage_reply(&session, &key_expr, None).await?;
+            let payload = sample.value.payload.contiguous();
+            match file {
+                Some(file) => std::fs::write(file, payload)?,
+                None => println!("{}", String::from_utf8_lossy(&payload)),
+            }
+        }
+        StorageCommand::Import { file, .. } => {
+            let key_expr = format!("{}/import", status.key_expr);
+            let value = Value::from(std::fs::read(file)?).encoding(KnownEncoding::AppJson.into());
+            let sample = get_storage_reply(&session, &key_expr, Some(value)).await?;
+            println!(
+                "{}",
+                String::from_utf8_lossy(&sample.value.payload.contiguous())
+            );
+        }
+    }
+    session.close().res().await
+}
+
+// Gets the reply of a single storage to a query on the admin space
+async fn get_storage_reply(
+    session: &Session,
+    key_expr: &str,
+    value: Option<Value>,
+) -> Result<Sample> {
+    let mut get = session.get(key_expr);
+    if let Some(value) = value {
+        get = get.with_value(value);
+    }
+    let mut samples = Vec::new();
+    for reply in get.res().await?.into_iter() {
+        match reply.sample {
+            Ok(sample) => samples.push(sample),
+            Err(e) => return Err(format!("{e}").into()),
+        }
+    }
+    match samples.len() {
+        1 => Ok(samples.pop().unwrap()),
+        0 => Err(format!("No storage replied on {key_expr}").into()),
+        n => Err(format!(
+            "{n} storages replied on {key_expr}, select one of their routers with --router"
+        )
+        .into()),
+    }
+}
+
 fn config_from_args(args: &Args) -> Config {
     let mut config = args
         .config